@@ -0,0 +1,104 @@
+use crate::errors::{AppError, Result};
+use crate::models::{AccessSettings, Role};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{Manager, State, Window};
+
+// Settings file under the app data dir
+const SETTINGS_FILE: &str = "access.json";
+
+// Which window may do what. A lobby display and the host stand share one backend, so
+// the check is made here on the calling window's label, not trusted to the frontend.
+// Every command that changes a session or a shift, writes files or runs the engine
+// checks it.
+// Settings are read from disk on first use and saved whenever they change.
+#[derive(Default)]
+pub struct AccessStore {
+    settings: Mutex<Option<AccessSettings>>,
+}
+
+fn settings_path(window: &Window) -> Result<PathBuf> {
+    let dir = window.path().app_data_dir()
+        .map_err(|e| AppError::IoError(std::io::Error::other(e.to_string())))?;
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir.join(SETTINGS_FILE))
+}
+
+impl AccessStore {
+    fn with_settings<T>(&self, window: &Window, f: impl FnOnce(&mut AccessSettings) -> T) -> Result<T> {
+        let mut settings = self.settings.lock().unwrap();
+        if settings.is_none() {
+            let path = settings_path(window)?;
+            let loaded = match std::fs::read_to_string(&path) {
+                Ok(json) => serde_json::from_str(&json).map_err(|e| AppError::JsonParseError(e.to_string()))?,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => AccessSettings::default(),
+                Err(e) => return Err(e.into()),
+            };
+            *settings = Some(loaded);
+        }
+        Ok(f(settings.as_mut().expect("loaded above")))
+    }
+
+    fn role_of(&self, window: &Window) -> Result<Role> {
+        self.with_settings(window, |s| role_in(s, window.label()))
+    }
+}
+
+fn role_in(settings: &AccessSettings, label: &str) -> Role {
+    settings.window_roles.get(label).copied().unwrap_or(settings.default_role)
+}
+
+// Fail unless the calling window has the host role; `action` completes the message,
+// e.g. "seat parties"
+pub(crate) fn require_host(window: &Window, action: &str) -> Result<()> {
+    host_only(window.state::<AccessStore>().role_of(window)?, window.label(), action)
+}
+
+fn host_only(role: Role, label: &str, action: &str) -> Result<()> {
+    match role {
+        Role::Host => Ok(()),
+        Role::Viewer => Err(AppError::AccessDenied(format!("Window '{}' is a viewer and cannot {}", label, action))),
+    }
+}
+
+#[tauri::command]
+pub fn get_access_settings(window: Window, access: State<'_, AccessStore>) -> Result<AccessSettings> {
+    access.with_settings(&window, |s| s.clone())
+}
+
+// The calling window's own role, so the frontend can hide controls it can't use
+#[tauri::command]
+pub fn get_window_role(window: Window, access: State<'_, AccessStore>) -> Result<Role> {
+    access.role_of(&window)
+}
+
+// Replace and save the settings. Only a host may, and it can't demote itself, so
+// there is always a window left that can change them back.
+#[tauri::command]
+pub fn set_access_settings(settings: AccessSettings, window: Window, access: State<'_, AccessStore>) -> Result<AccessSettings> {
+    require_host(&window, "change access settings")?;
+    if role_in(&settings, window.label()) != Role::Host {
+        return Err(AppError::ValidationError(format!("Window '{}' would lose the host role it needs to change settings", window.label())));
+    }
+    let json = serde_json::to_string_pretty(&settings).map_err(|e| AppError::JsonParseError(e.to_string()))?;
+    std::fs::write(settings_path(&window)?, json)?;
+    access.with_settings(&window, |s| *s = settings.clone())?;
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MAIN_WINDOW;
+
+    #[test]
+    fn viewers_are_refused() {
+        let mut settings = AccessSettings::default();
+        settings.window_roles.insert("lobby".to_string(), Role::Viewer);
+        assert!(host_only(role_in(&settings, MAIN_WINDOW), MAIN_WINDOW, "save runs").is_ok());
+        let refused = host_only(role_in(&settings, "lobby"), "lobby", "save runs");
+        assert!(matches!(refused, Err(AppError::AccessDenied(message)) if message == "Window 'lobby' is a viewer and cannot save runs"));
+        // Windows nobody listed get the default role, viewer out of the box
+        assert!(matches!(host_only(role_in(&settings, "session-2"), "session-2", "run simulations"), Err(AppError::AccessDenied(_))));
+    }
+}
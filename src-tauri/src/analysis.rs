@@ -0,0 +1,123 @@
+use crate::access;
+use crate::models::{CustomerConfig, SeatConfig, CounterfactualResult, CounterfactualReport};
+use crate::errors::Result;
+use crate::session::SessionStore;
+use crate::simulation::{self, EngineParams};
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use tauri::{State, Window};
+
+// Hypothetical additions tried by the counterfactual analysis: (seat type, wheelchair accessible)
+const WHAT_IF_SEATS: [(&str, bool); 5] = [
+    ("SINGLE", false),
+    ("4P", false),
+    ("4P", true),
+    ("6P", false),
+    ("6P", true),
+];
+
+pub(crate) struct WaitSummary {
+    pub avg_wait: f64,
+    pub max_wait: u64,
+    pub seated_count: usize,
+}
+
+fn run_and_summarize(customers: &[CustomerConfig], seats: &[SeatConfig], params: &EngineParams, cancel: &Arc<AtomicBool>) -> Result<WaitSummary> {
+    let res = simulation::run_engine(customers, seats, params, cancel)?;
+    Ok(summarize(customers, &simulation::sit_times(&res)))
+}
+
+pub(crate) fn summarize(customers: &[CustomerConfig], sit_times: &HashMap<u32, u64>) -> WaitSummary {
+    let waits: Vec<u64> = customers.iter()
+        .filter_map(|c| sit_times.get(&c.family_id).map(|t| t.saturating_sub(c.arrival_time)))
+        .collect();
+
+    WaitSummary {
+        avg_wait: if waits.is_empty() { 0.0 } else { waits.iter().sum::<u64>() as f64 / waits.len() as f64 },
+        max_wait: waits.iter().copied().max().unwrap_or(0),
+        seated_count: waits.len(),
+    }
+}
+
+// Copy of the layout with one extra seat. Bar seats are inserted right after the
+// last existing bar seat so they extend the contiguous counter.
+fn with_extra_seat(seats: &[SeatConfig], seat_type: &str, accessible: bool) -> Vec<SeatConfig> {
+    // An extra bar seat goes at the end of the last seat's contiguity group
+    let contiguity_group = seats.iter().rev().find(|s| s.type_ == seat_type).and_then(|s| s.contiguity_group.clone());
+    let group_position = seats.iter()
+        .filter(|s| contiguity_group.is_some() && s.contiguity_group == contiguity_group)
+        .filter_map(|s| s.group_position)
+        .max()
+        .map(|p| p + 1);
+    let extra = SeatConfig {
+        id: format!("WHATIF-{}{}", seat_type, if accessible { "-W" } else { "" }),
+        x: None,
+        y: None,
+        type_: seat_type.to_string(),
+        is_wheelchair_accessible: accessible,
+        label: Some("What-if".to_string()),
+        revenue_weight: None,
+        faces_conveyor: false,
+        adjacent_to: Vec::new(),
+        max_baby_chairs: None,
+        wheelchair_capacity: None,
+        attributes: HashMap::new(),
+        contiguity_group,
+        group_position,
+    };
+
+    let mut layout = seats.to_vec();
+    let insert_at = layout.iter()
+        .rposition(|s| s.type_ == seat_type)
+        .map(|i| i + 1)
+        .unwrap_or(layout.len());
+    layout.insert(insert_at, extra);
+    layout
+}
+
+// Re-run the session's scenario once per hypothetical extra seat and rank the
+// additions by how much they reduce the average wait.
+#[tauri::command]
+pub fn analyze_extra_seats(session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<CounterfactualReport> {
+    access::require_host(&window, "run simulations")?;
+    let session_id = sessions.resolve(session_id, &window);
+    let (customers, seats, params) = sessions.with_session(&session_id, |run| {
+        (run.customers.clone(), run.seats_config.clone(), EngineParams {
+            shadow_policy: None,
+            ..run.params.clone()
+        })
+    })?;
+
+    let _run_guard = sessions.begin_run()?;
+    let cancel = sessions.cancel_flag();
+
+    // Re-run the baseline too: the threaded engine is timing dependent, so
+    // comparing against fresh numbers is fairer than against the stored run.
+    let baseline = run_and_summarize(&customers, &seats, &params, &cancel)?;
+
+    let mut additions = Vec::new();
+    for (seat_type, accessible) in WHAT_IF_SEATS {
+        let layout = with_extra_seat(&seats, seat_type, accessible);
+        let summary = run_and_summarize(&customers, &layout, &params, &cancel)?;
+        additions.push(CounterfactualResult {
+            seat_type: seat_type.to_string(),
+            wheelchair_accessible: accessible,
+            avg_wait: summary.avg_wait,
+            max_wait: summary.max_wait,
+            seated_count: summary.seated_count,
+            avg_wait_improvement: baseline.avg_wait - summary.avg_wait,
+            max_wait_improvement: baseline.max_wait as i64 - summary.max_wait as i64,
+        });
+    }
+
+    additions.sort_by(|a, b| b.avg_wait_improvement.total_cmp(&a.avg_wait_improvement)
+        .then(b.max_wait_improvement.cmp(&a.max_wait_improvement)));
+
+    Ok(CounterfactualReport {
+        baseline_avg_wait: baseline.avg_wait,
+        baseline_max_wait: baseline.max_wait,
+        baseline_seated_count: baseline.seated_count,
+        additions,
+    })
+}
@@ -0,0 +1,192 @@
+use crate::errors::Result;
+use crate::models::{Announcement, CustomerConfig, QuoteAccuracyReport, QuoteSizeAccuracy, SimulationFrame, WaitQuote};
+use crate::session::SessionStore;
+use std::collections::HashMap;
+use tauri::{AppHandle, Emitter, State, Window};
+
+// Channel the lobby screen listens on
+pub const ANNOUNCEMENT_EVENT: &str = "lobby-announcements";
+
+pub const TABLE_READY: &str = "TABLE_READY";
+pub const WAIT_ESTIMATE: &str = "WAIT_ESTIMATE";
+
+// Recent seatings of the same party size an estimate averages over
+const ESTIMATE_WINDOW: usize = 3;
+
+// Minutes a quote may be off and still count as accurate
+const DEFAULT_QUOTE_TOLERANCE: u64 = 5;
+
+// Wait estimate per party size: the average of the last few waits of that size
+#[derive(Default)]
+struct WaitEstimator {
+    recent_waits: HashMap<u32, Vec<u64>>,
+}
+
+impl WaitEstimator {
+    fn estimate(&self, size: u32) -> Option<u64> {
+        let waits = self.recent_waits.get(&size).filter(|w| !w.is_empty())?;
+        Some(waits.iter().sum::<u64>().div_ceil(waits.len() as u64))
+    }
+
+    fn record(&mut self, size: u32, waited: u64) {
+        let waits = self.recent_waits.entry(size).or_default();
+        waits.push(waited);
+        if waits.len() > ESTIMATE_WINDOW { waits.remove(0); }
+    }
+}
+
+// Lobby announcements derived from a run's event log: a call when a waiting party's
+// table is ready, and a fresh wait estimate for its party size whenever a party
+// starts waiting (average of the last few waits of that size, once there are any)
+pub fn announcements(frames: &[SimulationFrame], customers: &[CustomerConfig]) -> Vec<Announcement> {
+    let size_of = |fid: u32| customers.iter().find(|c| c.family_id == fid).map(|c| c.party_size);
+    let mut arrivals: HashMap<u32, u64> = HashMap::new();
+    let mut estimator = WaitEstimator::default();
+    let mut out = Vec::new();
+
+    for e in frames.iter().flat_map(|f| f.events.iter()) {
+        match e.type_.as_str() {
+            "ARRIVAL" => { arrivals.insert(e.family_id, e.timestamp); }
+            "WAITING" => {
+                let Some(size) = size_of(e.family_id) else { continue };
+                let Some(estimate) = estimator.estimate(size) else { continue };
+                out.push(Announcement {
+                    timestamp: e.timestamp,
+                    kind: WAIT_ESTIMATE.to_string(),
+                    family_id: None,
+                    party_size: Some(size),
+                    estimated_wait: Some(estimate),
+                    message: format!("Estimated wait for parties of {}: {} min", size, estimate),
+                });
+            }
+            "SEATED" => {
+                let waited = e.timestamp.saturating_sub(arrivals.get(&e.family_id).copied().unwrap_or(e.timestamp));
+                let Some(size) = size_of(e.family_id) else { continue };
+                estimator.record(size, waited);
+
+                // Parties seated on arrival were never in the lobby
+                if waited == 0 { continue; }
+                out.push(Announcement {
+                    timestamp: e.timestamp,
+                    kind: TABLE_READY.to_string(),
+                    family_id: Some(e.family_id),
+                    party_size: Some(size),
+                    estimated_wait: None,
+                    message: format!("Party {}, your table is ready", e.family_id),
+                });
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+// The wait each party was quoted when it started waiting, next to the wait it actually
+// had. Parties waiting before any party of their size was seated get no quote.
+pub fn wait_quotes(frames: &[SimulationFrame], customers: &[CustomerConfig]) -> Vec<WaitQuote> {
+    let size_of = |fid: u32| customers.iter().find(|c| c.family_id == fid).map(|c| c.party_size);
+    let mut arrivals: HashMap<u32, u64> = HashMap::new();
+    let mut estimator = WaitEstimator::default();
+    let mut quotes: Vec<WaitQuote> = Vec::new();
+
+    for e in frames.iter().flat_map(|f| f.events.iter()) {
+        match e.type_.as_str() {
+            "ARRIVAL" => { arrivals.insert(e.family_id, e.timestamp); }
+            "WAITING" => {
+                // Only the first quote counts; a party re-entering the queue keeps it
+                if quotes.iter().any(|q| q.family_id == e.family_id) { continue };
+                let Some(size) = size_of(e.family_id) else { continue };
+                let Some(quoted_wait) = estimator.estimate(size) else { continue };
+                quotes.push(WaitQuote { family_id: e.family_id, party_size: size, quoted_at: e.timestamp, quoted_wait, actual_wait: None, error: None });
+            }
+            "SEATED" => {
+                let waited = e.timestamp.saturating_sub(arrivals.get(&e.family_id).copied().unwrap_or(e.timestamp));
+                let Some(size) = size_of(e.family_id) else { continue };
+                estimator.record(size, waited);
+                if let Some(q) = quotes.iter_mut().find(|q| q.family_id == e.family_id && q.actual_wait.is_none()) {
+                    q.actual_wait = Some(waited);
+                    q.error = Some(waited as i64 - q.quoted_wait as i64);
+                }
+            }
+            _ => {}
+        }
+    }
+    quotes
+}
+
+// Mean absolute and signed error over the quotes that were resolved by a seating
+fn errors(quotes: &[&WaitQuote]) -> (usize, f64, f64) {
+    let errors: Vec<i64> = quotes.iter().filter_map(|q| q.error).collect();
+    if errors.is_empty() {
+        return (0, 0.0, 0.0);
+    }
+    let n = errors.len() as f64;
+    let mae = errors.iter().map(|e| e.unsigned_abs() as f64).sum::<f64>() / n;
+    let bias = errors.iter().sum::<i64>() as f64 / n;
+    (errors.len(), mae, bias)
+}
+
+pub fn quote_accuracy(frames: &[SimulationFrame], customers: &[CustomerConfig], tolerance: u64) -> QuoteAccuracyReport {
+    let quotes = wait_quotes(frames, customers);
+    let all: Vec<&WaitQuote> = quotes.iter().collect();
+    let (resolved, mean_absolute_error, bias) = errors(&all);
+    let within = quotes.iter().filter(|q| q.error.is_some_and(|e| e.unsigned_abs() <= tolerance)).count();
+
+    let mut sizes: Vec<u32> = quotes.iter().map(|q| q.party_size).collect();
+    sizes.sort_unstable();
+    sizes.dedup();
+    let by_party_size = sizes.into_iter().map(|size| {
+        let of_size: Vec<&WaitQuote> = quotes.iter().filter(|q| q.party_size == size).collect();
+        let (resolved, mean_absolute_error, bias) = errors(&of_size);
+        QuoteSizeAccuracy { party_size: size, quotes: of_size.len(), resolved, mean_absolute_error, bias }
+    }).collect();
+
+    QuoteAccuracyReport {
+        quoted: quotes.len(),
+        resolved,
+        tolerance,
+        within_tolerance: if resolved == 0 { 0.0 } else { within as f64 / resolved as f64 },
+        mean_absolute_error,
+        bias,
+        by_party_size,
+        quotes,
+    }
+}
+
+// Send the announcements falling in (from, to] of the session's timeline to the windows
+// showing the session
+pub fn emit_due(app: &AppHandle, session_id: &str, sessions: &SessionStore, from: f64, to: f64) {
+    let Ok(due) = sessions.with_session(session_id, |run| {
+        run.announcements.iter()
+            .filter(|a| (a.timestamp as f64) > from && (a.timestamp as f64) <= to)
+            .cloned()
+            .collect::<Vec<_>>()
+    }) else { return };
+    let labels = sessions.windows_showing(app, session_id);
+    for announcement in due {
+        for label in &labels {
+            let _ = app.emit_to(label.as_str(), ANNOUNCEMENT_EVENT, announcement.clone());
+        }
+    }
+}
+
+// Announcements up to `until` (default: the whole run), for a lobby joining late
+#[tauri::command]
+pub fn get_announcements(session_id: Option<String>, until: Option<u64>, window: Window, sessions: State<'_, SessionStore>) -> Result<Vec<Announcement>> {
+    let session_id = sessions.resolve(session_id, &window);
+    sessions.with_session(&session_id, |run| {
+        run.announcements.iter()
+            .filter(|a| until.is_none_or(|t| a.timestamp <= t))
+            .cloned()
+            .collect()
+    })
+}
+
+// How well the wait quotes matched the waits parties actually had. `tolerance` (minutes,
+// default 5) is how far off a quote may be and still count as accurate.
+#[tauri::command]
+pub fn get_quote_accuracy(tolerance: Option<u64>, session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<QuoteAccuracyReport> {
+    let session_id = sessions.resolve(session_id, &window);
+    let tolerance = tolerance.unwrap_or(DEFAULT_QUOTE_TOLERANCE);
+    sessions.with_session(&session_id, |run| quote_accuracy(&run.frames, &run.customers, tolerance))
+}
@@ -0,0 +1,129 @@
+use crate::access;
+use crate::analysis;
+use crate::errors::{AppError, Result};
+use crate::generator;
+use crate::models::{GeneratorConfig, MonteCarloResult, ServiceTarget, SlaBatchResult};
+use crate::rng;
+use crate::session::SessionStore;
+use crate::simulation::{self, EngineParams};
+use crate::stats;
+use tauri::{State, Window};
+
+const DEFAULT_MAX_REPLICATIONS: u32 = 100;
+// Fewer runs than this give a standard deviation too noisy to stop on
+const MIN_REPLICATIONS: u32 = 5;
+const METRICS: [&str; 3] = ["avgWait", "maxWait", "seatedShare"];
+
+// Two-sided normal quantiles for the supported confidence levels
+fn z_score(confidence: f64) -> Result<f64> {
+    [(0.90, 1.645), (0.95, 1.960), (0.99, 2.576)].iter()
+        .find(|(level, _)| (level - confidence).abs() < 1e-9)
+        .map(|(_, z)| *z)
+        .ok_or_else(|| AppError::ValidationError(format!("Unsupported confidence level {}; use 0.90, 0.95 or 0.99", confidence)))
+}
+
+fn metric_value(metric: &str, summary: &analysis::WaitSummary, count: u32) -> f64 {
+    match metric {
+        "maxWait" => summary.max_wait as f64,
+        "seatedShare" => if count == 0 { 1.0 } else { summary.seated_count as f64 / count as f64 },
+        _ => summary.avg_wait,
+    }
+}
+
+// Sample mean, standard deviation and confidence half-width
+fn interval(values: &[f64], z: f64) -> (f64, f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    if values.len() < 2 {
+        return (mean, 0.0, f64::INFINITY);
+    }
+    let std_dev = (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt();
+    (mean, std_dev, z * std_dev / n.sqrt())
+}
+
+// Replicate the session's layout and engine settings over rosters drawn from the
+// generator config. With a tolerance, stop as soon as the metric's confidence interval
+// is at most that wide on either side; otherwise (or if it never gets there) run
+// max_replications times. Service targets, if given, are checked on every replication.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command(async)]
+pub fn run_monte_carlo(
+    generator: GeneratorConfig,
+    metric: String,
+    tolerance: Option<f64>,
+    confidence: Option<f64>,
+    max_replications: Option<u32>,
+    slas: Option<Vec<ServiceTarget>>,
+    session_id: Option<String>,
+    window: Window,
+    sessions: State<'_, SessionStore>,
+) -> Result<MonteCarloResult> {
+    access::require_host(&window, "run simulations")?;
+    let session_id = sessions.resolve(session_id, &window);
+    let (seats, params) = sessions.with_session(&session_id, |run| {
+        (run.seats_config.clone(), EngineParams {
+            shadow_policy: None,
+            instrument: false,
+            trace_sync: false,
+            ..run.params.clone()
+        })
+    })?;
+
+    generator::validate_config(&generator)?;
+    if !METRICS.contains(&metric.as_str()) {
+        return Err(AppError::ValidationError(format!("Unknown metric '{}'; use one of {}", metric, METRICS.join(", "))));
+    }
+    let confidence = confidence.unwrap_or(0.95);
+    let z = z_score(confidence)?;
+    if tolerance.is_some_and(|t| t.is_nan() || t <= 0.0) {
+        return Err(AppError::ValidationError("tolerance must be positive".to_string()));
+    }
+    let max_replications = max_replications.unwrap_or(DEFAULT_MAX_REPLICATIONS).max(1);
+    let base_seed = generator.seed.unwrap_or_else(rng::fresh_seed);
+
+    let _run_guard = sessions.begin_run()?;
+    let cancel = sessions.cancel_flag();
+
+    let targets = slas.unwrap_or_default();
+    let mut sla_results: Vec<SlaBatchResult> = Vec::new();
+    let mut values = Vec::new();
+    let mut converged = false;
+    for i in 0..max_replications {
+        let customers = generator::sample_roster(&generator, base_seed.wrapping_add(i as u64));
+        let sit_times = simulation::sit_times(&simulation::run_engine(&customers, &seats, &params, &cancel)?);
+        values.push(metric_value(&metric, &analysis::summarize(&customers, &sit_times), generator.count));
+
+        for (n, result) in stats::evaluate_slas(&customers, &sit_times, &targets)?.into_iter().enumerate() {
+            if sla_results.len() <= n {
+                sla_results.push(SlaBatchResult { name: result.name.clone(), passed_runs: 0, runs: 0, pass_rate: 0.0, violating_family_ids: Vec::new() });
+            }
+            let batch = &mut sla_results[n];
+            batch.runs += 1;
+            batch.passed_runs += result.passed as u32;
+            batch.pass_rate = batch.passed_runs as f64 / batch.runs as f64;
+            batch.violating_family_ids.push(result.violating_family_ids);
+        }
+
+        if let Some(tolerance) = tolerance {
+            if values.len() as u32 >= MIN_REPLICATIONS && interval(&values, z).2 <= tolerance {
+                converged = true;
+                break;
+            }
+        }
+    }
+
+    let (mean, std_dev, half_width) = interval(&values, z);
+    Ok(MonteCarloResult {
+        metric,
+        replications: values.len() as u32,
+        converged,
+        mean,
+        std_dev,
+        half_width,
+        confidence,
+        tolerance,
+        base_seed,
+        values,
+        slas: sla_results,
+    })
+}
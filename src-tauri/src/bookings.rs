@@ -0,0 +1,394 @@
+use crate::errors::{AppError, Result};
+use crate::generator::enforce_composition;
+use crate::models::{BookingImport, CustomerConfig};
+use crate::parser;
+use std::collections::{BTreeSet, HashMap};
+
+// Header names reservation systems (OpenTable-style exports, hand-kept booking sheets)
+// use for the fields we need, compared case-insensitively
+const NAME_COLUMNS: [&str; 4] = ["name", "guest", "guest name", "customer"];
+const COVERS_COLUMNS: [&str; 6] = ["covers", "party size", "party", "guests", "size", "pax"];
+const TIME_COLUMNS: [&str; 5] = ["time", "reservation time", "visit time", "date time", "datetime"];
+const NOTES_COLUMNS: [&str; 5] = ["notes", "special requests", "requests", "comments", "visit notes"];
+const DURATION_COLUMNS: [&str; 3] = ["duration", "turn time", "dining time"];
+
+// Note keywords implying a need the simulation models, matched as whole words (a
+// trailing "s" allowed, so "2 high chairs" counts but "minute" isn't a nut allergy)
+const WHEELCHAIR_KEYWORDS: [&str; 4] = ["wheelchair", "wheel chair", "accessible", "mobility"];
+const BABY_KEYWORDS: [&str; 7] = ["high chair", "highchair", "baby", "babies", "infant", "booster", "toddler"];
+const DIETARY_KEYWORDS: [(&str, &str); 6] = [
+    ("gluten", "GLUTEN_FREE"),
+    ("nut", "NUT_ALLERGY"),
+    ("peanut", "NUT_ALLERGY"),
+    ("vegan", "VEGAN"),
+    ("vegetarian", "VEGETARIAN"),
+    ("shellfish", "SHELLFISH_ALLERGY"),
+];
+
+const DEFAULT_DINING_TIME: u64 = 60;
+const MINUTES_PER_DAY: u64 = 24 * 60;
+
+// Split one CSV record, honouring double-quoted fields (notes often contain commas)
+fn split_record(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => { field.push('"'); chars.next(); }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+fn find_column(header: &[String], names: &[&str]) -> Option<usize> {
+    header.iter().position(|h| names.contains(&h.to_lowercase().as_str()))
+}
+
+// Minute of the day from "19:30", "7:30 PM", "2024-05-01 19:30" or "2024-05-01T19:30:00"
+fn minute_of_day(text: &str) -> Option<u64> {
+    let lower = text.trim().to_lowercase();
+    let pm = lower.ends_with("pm");
+    let am = lower.ends_with("am");
+    let clock = lower.split([' ', 'T', 't']).find(|token| token.contains(':'))?;
+    let mut parts = clock.split(':');
+    let hour: u64 = parts.next()?.trim().parse().ok()?;
+    let minute: u64 = parts.next()?.trim().trim_end_matches(|c: char| !c.is_ascii_digit()).parse().ok()?;
+    let hour = match (am, pm, hour) {
+        (_, true, h) if h < 12 => h + 12,
+        (true, _, 12) => 0,
+        (_, _, h) => h,
+    };
+    (hour < 24 && minute < 60).then_some(hour * 60 + minute)
+}
+
+// Where `keyword` first appears in `text` as a word of its own, or its plural
+fn find_word(text: &str, keyword: &str) -> Option<usize> {
+    let word_char = |c: Option<char>| c.is_some_and(char::is_alphanumeric);
+    text.match_indices(keyword).map(|(pos, _)| pos).find(|&pos| {
+        let rest = &text[pos + keyword.len()..];
+        let rest = rest.strip_prefix('s').unwrap_or(rest);
+        !word_char(text[..pos].chars().next_back()) && !word_char(rest.chars().next())
+    })
+}
+
+// Count attached to the first matching keyword ("2 high chairs" -> 2), 1 without a number
+fn keyword_count(notes: &str, keywords: &[&str]) -> u32 {
+    let Some(pos) = keywords.iter().filter_map(|k| find_word(notes, k)).min() else { return 0 };
+    notes[..pos].split_whitespace().last()
+        .and_then(|word| word.parse().ok())
+        .unwrap_or(1)
+}
+
+// Keep passthrough values parseable by the positional roster format
+fn roster_value(value: &str) -> String {
+    value.replace(',', ";").replace(['\n', '\r'], " ")
+}
+
+// Customers as roster CSV text, passthrough columns appended after the known ones and
+// max_wait_time and priority columns last if any party has one
+pub(crate) fn roster_csv(customers: &[CustomerConfig]) -> String {
+    let extra_columns: BTreeSet<&String> = customers.iter().flat_map(|c| c.extra.keys()).collect();
+    let max_wait_column = customers.iter().any(|c| c.max_wait_time.is_some());
+    let priority_column = customers.iter().any(|c| c.priority > 0);
+    let mut lines = vec![
+        ["id", "arrival_time", "type", "party_size", "baby_chair", "wheel_chair", "est_dining_time",
+            "conveyor_preference", "dietary_flags", "ordered_ahead"].iter().map(|s| s.to_string())
+            .chain(extra_columns.iter().map(|c| roster_value(c)))
+            .chain(max_wait_column.then(|| parser::MAX_WAIT_COLUMN.to_string()))
+            .chain(priority_column.then(|| parser::PRIORITY_COLUMN.to_string()))
+            .collect::<Vec<_>>()
+            .join(","),
+    ];
+    for c in customers {
+        let mut fields = vec![
+            c.id.to_string(),
+            c.arrival_time.to_string(),
+            c.type_.clone(),
+            c.party_size.to_string(),
+            c.baby_chair_count.to_string(),
+            c.wheelchair_count.to_string(),
+            c.est_dining_time.to_string(),
+            c.conveyor_preference.to_string(),
+            c.dietary_flags.join("|"),
+            c.ordered_ahead.to_string(),
+        ];
+        fields.extend(extra_columns.iter().map(|col| c.extra.get(*col).map(|v| roster_value(v)).unwrap_or_default()));
+        if max_wait_column {
+            fields.push(c.max_wait_time.map(|m| m.to_string()).unwrap_or_default());
+        }
+        if priority_column {
+            fields.push(c.priority.to_string());
+        }
+        lines.push(fields.join(","));
+    }
+    lines.join("\n") + "\n"
+}
+
+// Customer for one booking, with wheelchair and high chair needs and dietary flags
+// inferred from the notes. Ids and arrival are filled in by `into_roster`.
+fn booking_customer(covers: u32, notes: &str, duration: Option<u64>, dining_time: Option<u64>, extra: HashMap<String, String>) -> CustomerConfig {
+    let notes = notes.to_lowercase();
+    let babies = keyword_count(&notes, &BABY_KEYWORDS);
+    let wheelchairs = keyword_count(&notes, &WHEELCHAIR_KEYWORDS);
+    let ((party_size, baby_chair_count, wheelchair_count), _) = enforce_composition(covers, babies, wheelchairs);
+    let mut dietary_flags: Vec<String> = DIETARY_KEYWORDS.iter()
+        .filter(|(keyword, _)| find_word(&notes, keyword).is_some())
+        .map(|(_, flag)| flag.to_string())
+        .collect();
+    // "nut" and "peanut" flag the same allergy
+    dietary_flags.dedup();
+
+    CustomerConfig {
+        id: 0.into(),
+        family_id: 0,
+        arrival_time: 0,
+        type_: parser::customer_type(party_size, baby_chair_count, wheelchair_count),
+        party_size,
+        baby_chair_count,
+        wheelchair_count,
+        est_dining_time: duration.or(dining_time).unwrap_or(DEFAULT_DINING_TIME),
+        conveyor_preference: 0.0,
+        dietary_flags,
+        ordered_ahead: false,
+        extra,
+        max_wait_time: None,
+        priority: 0,
+    }
+}
+
+// Number bookings in time order and make arrivals relative to `service_start`
+// ("HH:MM" on the first booking's day, default: the earliest booking). Booking times
+// are minutes on any consistent scale whose multiples of a day are midnights.
+fn into_roster(mut bookings: Vec<(u64, CustomerConfig)>, service_start: Option<&str>, skipped: Vec<String>) -> Result<BookingImport> {
+    let earliest = bookings.iter().map(|(m, _)| *m).min().unwrap_or(0);
+    let start = match service_start {
+        Some(text) => earliest - earliest % MINUTES_PER_DAY + minute_of_day(text).ok_or_else(|| AppError::ValidationError(
+            format!("Invalid service start '{}', expected HH:MM", text)))?,
+        None => earliest,
+    };
+    bookings.sort_by_key(|(minute, _)| *minute);
+    let customers: Vec<CustomerConfig> = bookings.into_iter().enumerate().map(|(i, (minute, mut c))| {
+        c.family_id = i as u32 + 1;
+        c.id = c.family_id.into();
+        c.arrival_time = minute.saturating_sub(start);
+        c
+    }).collect();
+
+    Ok(BookingImport { csv_content: roster_csv(&customers), customers, skipped })
+}
+
+// Map a reservation export (name, covers, time, notes) to a roster. Arrival times are
+// minutes after `service_start` ("HH:MM", default: the earliest booking).
+pub fn import(csv_content: &str, service_start: Option<&str>, dining_time: Option<u64>) -> Result<BookingImport> {
+    let mut lines = csv_content.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+    let header = lines.next().map(|(_, l)| split_record(l)).unwrap_or_default();
+
+    let covers_col = find_column(&header, &COVERS_COLUMNS);
+    let time_col = find_column(&header, &TIME_COLUMNS);
+    let (Some(covers_col), Some(time_col)) = (covers_col, time_col) else {
+        return Err(AppError::CsvParseError(format!(
+            "Booking export needs a covers column ({}) and a time column ({})",
+            COVERS_COLUMNS.join("/"), TIME_COLUMNS.join("/"))));
+    };
+    let name_col = find_column(&header, &NAME_COLUMNS);
+    let notes_col = find_column(&header, &NOTES_COLUMNS);
+    let duration_col = find_column(&header, &DURATION_COLUMNS);
+
+    let mut bookings = Vec::new();
+    for (i, line) in lines {
+        let fields = split_record(line);
+        let field = |col: usize| fields.get(col).map(String::as_str).unwrap_or("");
+
+        let minute = minute_of_day(field(time_col)).ok_or_else(|| AppError::CsvParseError(
+            format!("Line {}: unreadable reservation time '{}'", i + 1, field(time_col))))?;
+        let covers = field(covers_col).parse::<u32>().map_err(|_| AppError::CsvParseError(
+            format!("Line {}: unreadable covers '{}'", i + 1, field(covers_col))))?;
+
+        // Everything but the mapped columns travels along; name and notes under fixed keys
+        let mapped = [Some(covers_col), Some(time_col), name_col, notes_col, duration_col];
+        let mut extra: HashMap<String, String> = header.iter().enumerate()
+            .filter(|(col, _)| !mapped.contains(&Some(*col)) && !field(*col).is_empty())
+            .map(|(col, name)| (name.clone(), field(col).to_string()))
+            .collect();
+        if let Some(name) = name_col.map(field).filter(|n| !n.is_empty()) {
+            extra.insert("name".to_string(), name.to_string());
+        }
+        if let Some(notes) = notes_col.map(field).filter(|n| !n.is_empty()) {
+            extra.insert("notes".to_string(), notes.to_string());
+        }
+
+        let notes = notes_col.map(field).unwrap_or("");
+        let duration = duration_col.and_then(|c| field(c).parse().ok());
+        bookings.push((minute, booking_customer(covers, notes, duration, dining_time, extra)));
+    }
+
+    into_roster(bookings, service_start, Vec::new())
+}
+
+// Days since 1970-01-01 for a proleptic Gregorian date
+fn days_from_civil(year: i64, month: u64, day: u64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+// Minutes since the epoch from an iCalendar date-time ("20240501T193000" or with a
+// trailing Z for UTC, which is shifted by `utc_offset` minutes to local time)
+fn calendar_minutes(value: &str, utc_offset: i64) -> Option<u64> {
+    let value = value.trim();
+    let (date, time) = value.split_once('T')?;
+    if date.len() != 8 || time.len() < 4 { return None; }
+    let days = days_from_civil(date[..4].parse().ok()?, date[4..6].parse().ok()?, date[6..8].parse().ok()?);
+    let (hour, minute): (i64, i64) = (time[..2].parse().ok()?, time[2..4].parse().ok()?);
+    let offset = if value.ends_with('Z') { utc_offset } else { 0 };
+    u64::try_from(days * MINUTES_PER_DAY as i64 + hour * 60 + minute + offset).ok()
+}
+
+// Undo iCalendar text escaping
+fn unescape(text: &str) -> String {
+    text.replace("\\n", "\n").replace("\\N", "\n").replace("\\,", ",").replace("\\;", ";").replace("\\\\", "\\")
+}
+
+// Words around the party size in summaries like "Smith x4", "Table for 4 - Lee" or "Kim (3 pax)"
+const SUMMARY_FILLER: [&str; 9] = ["party", "of", "table", "for", "pax", "ppl", "people", "guests", "x"];
+
+// Party size and guest name from an event summary; None without a number
+fn summary_party(summary: &str) -> Option<(u32, String)> {
+    let size = summary.split(|c: char| !c.is_ascii_digit()).find(|n| !n.is_empty())?.parse().ok()?;
+    let name = summary.split_whitespace()
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|w| !w.is_empty() && !w.chars().any(|c| c.is_ascii_digit()))
+        .filter(|w| !SUMMARY_FILLER.contains(&w.to_lowercase().as_str()))
+        .collect::<Vec<_>>()
+        .join(" ");
+    Some((size, name))
+}
+
+// Map an .ics calendar of bookings to a roster: each VEVENT is one party, its size read
+// from the summary, notes from the description and dining time from DTEND when present.
+// Events without a start time or party size (staff meetings, ...) are skipped.
+pub fn import_calendar(ics_content: &str, service_start: Option<&str>, dining_time: Option<u64>, utc_offset: i64) -> Result<BookingImport> {
+    // Unfold continuation lines (RFC 5545 3.1)
+    let mut lines: Vec<String> = Vec::new();
+    for line in ics_content.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut bookings = Vec::new();
+    let mut skipped = Vec::new();
+    let mut event: Option<HashMap<String, String>> = None;
+    for line in &lines {
+        match line.trim() {
+            "BEGIN:VEVENT" => { event = Some(HashMap::new()); continue; }
+            "END:VEVENT" => {}
+            _ => {
+                let (Some(props), Some((key, value))) = (event.as_mut(), line.split_once(':')) else { continue };
+                let name = key.split(';').next().unwrap_or(key).to_uppercase();
+                props.insert(name, unescape(value));
+                continue;
+            }
+        }
+        let Some(props) = event.take() else { continue };
+        let summary = props.get("SUMMARY").cloned().unwrap_or_default();
+        let start = props.get("DTSTART").and_then(|v| calendar_minutes(v, utc_offset));
+        let (Some(start), Some((covers, name))) = (start, summary_party(&summary)) else {
+            skipped.push(format!("'{}': no start time or party size", summary));
+            continue;
+        };
+        let duration = props.get("DTEND")
+            .and_then(|v| calendar_minutes(v, utc_offset))
+            .filter(|end| *end > start)
+            .map(|end| end - start);
+        let notes = props.get("DESCRIPTION").cloned().unwrap_or_default();
+
+        let mut extra = HashMap::new();
+        if !name.is_empty() { extra.insert("name".to_string(), name); }
+        if !notes.is_empty() { extra.insert("notes".to_string(), notes.clone()); }
+        if let Some(uid) = props.get("UID") { extra.insert("booking_id".to_string(), uid.clone()); }
+        bookings.push((start, booking_customer(covers, &notes, duration, dining_time, extra)));
+    }
+
+    if bookings.is_empty() && skipped.is_empty() {
+        return Err(AppError::ValidationError("Calendar has no VEVENT entries".to_string()));
+    }
+    into_roster(bookings, service_start, skipped)
+}
+
+// Seed a simulation from a booking calendar (.ics). `utc_offset` is the restaurant's
+// offset from UTC in minutes, applied to times the calendar stores in UTC.
+#[tauri::command]
+pub fn import_calendar_bookings(ics_content: String, service_start: Option<String>, dining_time: Option<u64>, utc_offset: Option<i64>) -> Result<BookingImport> {
+    import_calendar(&ics_content, service_start.as_deref(), dining_time, utc_offset.unwrap_or(0))
+}
+
+// Seed a simulation from a reservation system export
+#[tauri::command]
+pub fn import_bookings(csv_content: String, service_start: Option<String>, dining_time: Option<u64>) -> Result<BookingImport> {
+    import(&csv_content, service_start.as_deref(), dining_time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn times_read_in_common_formats() {
+        assert_eq!(minute_of_day("19:30"), Some(1170));
+        assert_eq!(minute_of_day("7:30 PM"), Some(1170));
+        assert_eq!(minute_of_day("12:05 am"), Some(5));
+        assert_eq!(minute_of_day("2024-05-01T19:30:00"), Some(1170));
+        assert_eq!(minute_of_day("24:00"), None);
+        assert_eq!(minute_of_day("tonight"), None);
+    }
+
+    #[test]
+    fn needs_come_from_whole_words_in_the_notes() {
+        let customer = booking_customer(4, "2 High Chairs, peanut allergy; back in a minute", None, None, HashMap::new());
+        assert_eq!((customer.party_size, customer.baby_chair_count, customer.wheelchair_count), (4, 2, 0));
+        assert_eq!(customer.dietary_flags, ["NUT_ALLERGY"]);
+        assert_eq!(customer.est_dining_time, DEFAULT_DINING_TIME);
+        let customer = booking_customer(2, "wheelchair user", Some(90), Some(45), HashMap::new());
+        assert_eq!((customer.type_.as_str(), customer.wheelchair_count, customer.est_dining_time), ("WHEELCHAIR", 1, 90));
+    }
+
+    // Bookings are numbered in time order, arrivals relative to the service start; quoted
+    // notes keep their commas and unmapped columns travel along
+    #[test]
+    fn exports_become_rosters_in_time_order() {
+        let csv = "Guest Name,Covers,Reservation Time,Special Requests,Table\n\
+                   Lee,2,19:30,\"vegan, window\",12\n\
+                   Chen,4,18:45,,\n";
+        let import = import(csv, Some("18:00"), Some(75)).unwrap();
+        let summary: Vec<(String, u64, u32, u64)> = import.customers.iter()
+            .map(|c| (c.extra["name"].clone(), c.arrival_time, c.party_size, c.est_dining_time))
+            .collect();
+        assert_eq!(summary, [("Chen".to_string(), 45, 4, 75), ("Lee".to_string(), 90, 2, 75)]);
+        assert_eq!((import.customers[1].family_id, import.customers[1].dietary_flags.as_slice()), (2, ["VEGAN".to_string()].as_slice()));
+        assert_eq!(import.customers[1].extra.get("Table").map(String::as_str), Some("12"));
+        assert_eq!(import.customers[1].extra.get("notes").map(String::as_str), Some("vegan, window"));
+
+        let roster = parser::parse_customers(&import.csv_content).unwrap();
+        assert_eq!(roster.iter().map(|c| (c.family_id, c.arrival_time)).collect::<Vec<_>>(), [(1, 45), (2, 90)]);
+    }
+
+    #[test]
+    fn exports_without_covers_or_times_are_refused() {
+        assert!(matches!(import("Name,Time\nLee,19:30\n", None, None), Err(AppError::CsvParseError(_))));
+        let error = import("Covers,Time\n2,later\n", None, None).err().unwrap();
+        assert!(matches!(error, AppError::CsvParseError(m) if m == "Line 2: unreadable reservation time 'later'"));
+        assert!(matches!(import("Covers,Time\n2,19:00\n", Some("7pm"), None), Err(AppError::ValidationError(_))));
+    }
+}
@@ -0,0 +1,28 @@
+use crate::models::EngineCapabilities;
+use crate::protocol::PROTOCOL_VERSION;
+use crate::simulation::{strategy, AllocationObjective, EngineBackend};
+
+// Feature switches for this build. Flip these as the features land.
+const STREAMING: bool = true; // stream_simulation
+const WASM_STRATEGIES: bool = false;
+const BELT_MODEL: bool = true; // conveyor-facing seats + conveyor preference
+const REST_SERVER: bool = false;
+
+pub fn engine_capabilities() -> EngineCapabilities {
+    EngineCapabilities {
+        engine_version: env!("CARGO_PKG_VERSION").to_string(),
+        protocol_version: PROTOCOL_VERSION.to_string(),
+        streaming: STREAMING,
+        wasm_strategies: WASM_STRATEGIES,
+        belt_model: BELT_MODEL,
+        rest_server: REST_SERVER,
+        allocation_strategies: strategy::ALL.iter().map(|s| s.name().to_string()).collect(),
+        objectives: AllocationObjective::ALL.iter().map(|o| o.name().to_string()).collect(),
+        engines: EngineBackend::ALL.iter().map(|e| e.name().to_string()).collect(),
+    }
+}
+
+#[tauri::command]
+pub fn get_engine_capabilities() -> EngineCapabilities {
+    engine_capabilities()
+}
@@ -0,0 +1,169 @@
+use crate::access;
+use crate::config::SimulationConfig;
+use crate::errors::{AppError, Result};
+use crate::generator;
+use crate::layouts;
+use crate::models::{CapacityConfig, CapacityEvaluation, CapacityPlan, CustomerConfig, GeneratorConfig, SeatConfig};
+use crate::parser;
+use crate::rng;
+use crate::session::SessionStore;
+use crate::simulation::{self, EngineBackend, EngineParams};
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use tauri::{State, Window};
+
+// Rosters drawn per configuration when planning from a generator config
+const DEFAULT_REPLICATIONS: u32 = 10;
+// Most seats of one type (or pool items) the search will try
+const MAX_PER_DIMENSION: u32 = 256;
+const PERCENTILE: f64 = 95.0;
+
+// The dimensions searched, most expensive first so the cheap ones are fitted around them
+const DIMENSIONS: [fn(&mut CapacityConfig) -> &mut u32; 6] = [
+    |c| &mut c.six_tops,
+    |c| &mut c.four_tops,
+    |c| &mut c.bar_seats,
+    |c| &mut c.accessible_tables,
+    |c| &mut c.wheelchairs,
+    |c| &mut c.baby_chairs,
+];
+
+struct Planner<'a> {
+    rosters: Vec<Vec<CustomerConfig>>,
+    params: EngineParams,
+    cancel: &'a Arc<AtomicBool>,
+    target_wait: u64,
+    evaluations: Vec<CapacityEvaluation>,
+    tried: HashMap<CapacityConfig, usize>, // index into evaluations
+}
+
+impl Planner<'_> {
+    // Run every roster on the configuration's floor plan (once per configuration)
+    fn evaluate(&mut self, config: CapacityConfig) -> Result<&CapacityEvaluation> {
+        if let Some(&i) = self.tried.get(&config) {
+            return Ok(&self.evaluations[i]);
+        }
+        let mut waits: Vec<Option<u64>> = Vec::new();
+        if config.bar_seats + config.four_tops + config.six_tops > 0 {
+            let seats = floor_plan(&config)?;
+            let params = EngineParams { baby_chairs: config.baby_chairs as i32, wheelchairs: config.wheelchairs as i32, ..self.params.clone() };
+            for customers in &self.rosters {
+                let sit_times = simulation::sit_times(&simulation::run_engine(customers, &seats, &params, self.cancel)?);
+                waits.extend(customers.iter().map(|c| sit_times.get(&c.family_id).map(|t| t.saturating_sub(c.arrival_time))));
+            }
+        } else {
+            waits.extend(self.rosters.iter().flatten().map(|_| None));
+        }
+
+        // Nearest-rank percentile with parties never seated counted as the longest waits
+        waits.sort_by_key(|w| (w.is_none(), w.unwrap_or(0)));
+        let rank = ((PERCENTILE / 100.0 * waits.len() as f64).ceil() as usize).clamp(1, waits.len().max(1));
+        let p95_wait = waits.get(rank - 1).copied().flatten();
+        let seated: Vec<u64> = waits.iter().flatten().copied().collect();
+        self.evaluations.push(CapacityEvaluation {
+            config,
+            runs: self.rosters.len(),
+            p95_wait,
+            avg_wait: if seated.is_empty() { 0.0 } else { seated.iter().sum::<u64>() as f64 / seated.len() as f64 },
+            seated_share: if waits.is_empty() { 1.0 } else { seated.len() as f64 / waits.len() as f64 },
+            meets_target: waits.is_empty() || p95_wait.is_some_and(|w| w <= self.target_wait),
+        });
+        self.tried.insert(config, self.evaluations.len() - 1);
+        Ok(&self.evaluations[self.evaluations.len() - 1])
+    }
+
+    fn meets(&mut self, config: CapacityConfig) -> Result<bool> {
+        Ok(self.evaluate(config)?.meets_target)
+    }
+}
+
+fn floor_plan(config: &CapacityConfig) -> Result<Vec<SeatConfig>> {
+    layouts::generate_seat_layout(config.bar_seats, config.four_tops, config.six_tops, Some(config.accessible_tables), None, None)
+}
+
+// A small floor plan and pools whose p95 wait over the roster (or a batch of rosters
+// drawn from a generator config) is within `target_wait` minutes. Every dimension is
+// doubled until the target is met, then each is binary searched down in turn with the
+// others held. This is a heuristic: it assumes more capacity never makes the wait longer,
+// which a strategy or a merge can break, and a different order of dimensions may find a
+// smaller plan, so the result is small but not proven minimal. Runs use the DES engine
+// with the settings of `scenario` (merging, strategy, kitchen, dining time bands, ...),
+// or the defaults; its layout, pools and table reservations don't apply to the plans tried.
+#[tauri::command(async)]
+pub fn plan_capacity(target_wait: u64, roster: Option<String>, generator: Option<GeneratorConfig>, replications: Option<u32>, scenario: Option<SimulationConfig>, window: Window, sessions: State<'_, SessionStore>) -> Result<CapacityPlan> {
+    access::require_host(&window, "run simulations")?;
+    let scenario = SimulationConfig {
+        engine: Some(EngineBackend::Des.name().to_string()),
+        instrument: false,
+        trace_sync: false,
+        fairness: None, // the DES keeps ticket order
+        reservations_json: None,
+        ..scenario.unwrap_or_default()
+    };
+    generator::validate_dining_bands(&scenario.dining_time_bands)?;
+    let mut rosters = match (roster, generator) {
+        (Some(csv), None) => {
+            let customers = parser::parse_customers(&csv).map_err(|e| AppError::CsvParseError(e.to_string()))?;
+            vec![simulation::sort_customers(customers)]
+        }
+        (None, Some(generator)) => {
+            generator::validate_config(&generator)?;
+            let base_seed = generator.seed.unwrap_or_else(rng::fresh_seed);
+            (0..replications.unwrap_or(DEFAULT_REPLICATIONS).max(1))
+                .map(|i| generator::sample_roster(&generator, base_seed.wrapping_add(i as u64)))
+                .collect()
+        }
+        _ => return Err(AppError::ValidationError("Plan capacity for either a roster or a generator config".to_string())),
+    };
+    // Stays depend on the time of day, as in a run of the scenario
+    for c in rosters.iter_mut().flatten() {
+        c.est_dining_time = generator::banded_dining_time(&scenario.dining_time_bands, c.arrival_time, c.est_dining_time);
+    }
+    let params = simulation::engine_params(&scenario, &[])?;
+
+    let _run_guard = sessions.begin_run()?;
+    let cancel = sessions.cancel_flag();
+    let mut planner = Planner { rosters, params, cancel: &cancel, target_wait, evaluations: Vec::new(), tried: HashMap::new() };
+
+    let mut config = CapacityConfig { bar_seats: 2, four_tops: 1, six_tops: 1, accessible_tables: 1, baby_chairs: 1, wheelchairs: 1 };
+    while !planner.meets(config)? {
+        if DIMENSIONS.iter().all(|dim| *dim(&mut config) >= MAX_PER_DIMENSION) {
+            return Err(AppError::ValidationError(format!("No configuration with up to {} of each seat type and pool meets a p95 wait of {} minutes", MAX_PER_DIMENSION, target_wait)));
+        }
+        for dim in DIMENSIONS {
+            let value = dim(&mut config);
+            *value = (*value * 2).clamp(1, MAX_PER_DIMENSION);
+        }
+    }
+
+    for dim in DIMENSIONS {
+        let (mut low, mut high) = (0, *dim(&mut config));
+        while low < high {
+            let middle = (low + high) / 2;
+            let mut candidate = config;
+            *dim(&mut candidate) = middle;
+            if planner.meets(candidate)? {
+                high = middle;
+            } else {
+                low = middle + 1;
+            }
+        }
+        *dim(&mut config) = high;
+    }
+
+    let evidence = planner.evaluate(config)?.clone();
+    // Only an empty roster needs no seats at all
+    let layout = if config.bar_seats + config.four_tops + config.six_tops == 0 {
+        Vec::new()
+    } else {
+        floor_plan(&config)?
+    };
+    Ok(CapacityPlan {
+        target_wait,
+        recommended: config,
+        layout,
+        evidence,
+        evaluations: planner.evaluations,
+    })
+}
@@ -0,0 +1,216 @@
+use crate::errors::{AppError, Result};
+use crate::models::{CondimentConfig, CondimentEvent, CondimentReport, CustomerConfig, PriorityInversion};
+use crate::session::SessionStore;
+use std::collections::HashMap;
+use tauri::{State, Window};
+
+// Priority inversion teaching scenario. One staff member serves requests one time unit
+// at a time, always the runnable request with the highest (effective) priority. High
+// and low priority parties also need the single condiment station while being served;
+// medium priority parties only need the staff. A low party holding the station can then
+// be starved of staff by medium parties while a high party waits on the station: the
+// classic inversion. Priority inheritance lends the holder the blocked party's priority.
+const LOW: u8 = 1;
+const MEDIUM: u8 = 2;
+const HIGH: u8 = 3;
+
+// Parties needing assistance are served first, individuals last
+fn priority(customer: &CustomerConfig) -> u8 {
+    match customer.type_.as_str() {
+        "WHEELCHAIR" | "WITH_BABY" => HIGH,
+        "INDIVIDUAL" => LOW,
+        _ => MEDIUM,
+    }
+}
+
+struct Job {
+    family_id: u32,
+    priority: u8,
+    effective: u8,
+    request_time: u64,
+    remaining: u64,
+    needs_station: bool,
+    blocked_since: Option<u64>,
+    blocked_by: Option<usize>, // lower priority holder that kept the station from this job
+    inverted_time: u64, // time blocked while staff served lower priority work
+}
+
+struct Station<'a> {
+    config: &'a CondimentConfig,
+    jobs: Vec<Job>,
+    holder: Option<usize>,
+    serving: Option<usize>,
+    timeline: Vec<CondimentEvent>,
+    inversions: Vec<PriorityInversion>,
+}
+
+impl Station<'_> {
+    fn log(&mut self, time: u64, idx: usize, event: &str, detail: String) {
+        let job = &self.jobs[idx];
+        self.timeline.push(CondimentEvent {
+            time,
+            family_id: job.family_id,
+            priority: job.priority,
+            effective_priority: job.effective,
+            event: event.to_string(),
+            detail,
+        });
+    }
+
+    fn request(&mut self, time: u64, idx: usize) {
+        self.log(time, idx, "REQUEST", String::new());
+        if !self.jobs[idx].needs_station { return; }
+
+        match self.holder {
+            None => {
+                self.holder = Some(idx);
+                self.log(time, idx, "ACQUIRE", "condiment station".to_string());
+            }
+            Some(holder) => {
+                self.jobs[idx].blocked_since = Some(time);
+                self.note_lower_holder(idx, holder);
+                self.log(time, idx, "BLOCKED", format!("station held by family {}", self.jobs[holder].family_id));
+                if self.config.priority_inheritance && self.jobs[idx].effective > self.jobs[holder].effective {
+                    self.jobs[holder].effective = self.jobs[idx].effective;
+                    self.log(time, holder, "INHERIT", format!("priority {} from family {}", self.jobs[idx].effective, self.jobs[idx].family_id));
+                }
+            }
+        }
+    }
+
+    // Remember the first holder with a lower base priority than a blocked job
+    fn note_lower_holder(&mut self, idx: usize, holder: usize) {
+        if self.jobs[idx].blocked_by.is_none() && self.jobs[holder].priority < self.jobs[idx].priority {
+            self.jobs[idx].blocked_by = Some(holder);
+        }
+    }
+
+    fn runnable(&self, idx: usize) -> bool {
+        let job = &self.jobs[idx];
+        job.remaining > 0 && (!job.needs_station || self.holder == Some(idx))
+    }
+
+    // Highest effective priority first, then earliest request
+    fn pick(&self, requested: &[usize]) -> Option<usize> {
+        requested.iter().copied()
+            .filter(|&i| self.runnable(i))
+            .max_by(|&a, &b| self.jobs[a].effective.cmp(&self.jobs[b].effective)
+                .then(self.jobs[b].request_time.cmp(&self.jobs[a].request_time)))
+    }
+
+    fn finish(&mut self, time: u64, idx: usize, requested: &[usize]) {
+        self.log(time, idx, "DONE", String::new());
+        if self.holder != Some(idx) { return; }
+
+        self.jobs[idx].effective = self.jobs[idx].priority;
+        self.log(time, idx, "RELEASE", "condiment station".to_string());
+        self.holder = None;
+
+        // Hand the station to the highest priority waiter
+        let next = requested.iter().copied()
+            .filter(|&i| self.jobs[i].blocked_since.is_some())
+            .max_by(|&a, &b| self.jobs[a].priority.cmp(&self.jobs[b].priority)
+                .then(self.jobs[b].request_time.cmp(&self.jobs[a].request_time)));
+        if let Some(next) = next {
+            let since = self.jobs[next].blocked_since.take().unwrap_or(time);
+            self.holder = Some(next);
+            self.log(time, next, "ACQUIRE", format!("condiment station after waiting {}", time - since));
+            // Parties still waiting are now blocked by the new holder
+            for &i in requested {
+                if self.jobs[i].blocked_since.is_some() {
+                    self.note_lower_holder(i, next);
+                }
+            }
+            // Only a wait behind a lower priority holder is an inversion
+            if let Some(holder) = self.jobs[next].blocked_by.take() {
+                let job = &self.jobs[next];
+                self.inversions.push(PriorityInversion {
+                    family_id: job.family_id,
+                    holder_family_id: self.jobs[holder].family_id,
+                    blocked_from: since,
+                    blocked_until: time,
+                    inverted_time: job.inverted_time,
+                });
+            }
+        }
+    }
+}
+
+// Run the scenario for every seated party, each requesting at its sit time
+pub fn simulate(customers: &[CustomerConfig], sit_times: &HashMap<u32, u64>, config: &CondimentConfig) -> CondimentReport {
+    let mut jobs: Vec<Job> = customers.iter()
+        .filter_map(|c| sit_times.get(&c.family_id).map(|&t| (c, t)))
+        .map(|(c, t)| {
+            let p = priority(c);
+            Job {
+                family_id: c.family_id,
+                priority: p,
+                effective: p,
+                request_time: t,
+                remaining: config.service_time.max(1),
+                needs_station: p != MEDIUM,
+                blocked_since: None,
+                blocked_by: None,
+                inverted_time: 0,
+            }
+        })
+        .collect();
+    jobs.sort_by_key(|j| (j.request_time, j.family_id));
+
+    let mut station = Station { config, jobs, holder: None, serving: None, timeline: Vec::new(), inversions: Vec::new() };
+    let mut requested: Vec<usize> = Vec::new();
+    let mut next_job = 0;
+    let mut time = station.jobs.first().map(|j| j.request_time).unwrap_or(0);
+
+    while next_job < station.jobs.len() || requested.iter().any(|&i| station.jobs[i].remaining > 0) {
+        while next_job < station.jobs.len() && station.jobs[next_job].request_time <= time {
+            requested.push(next_job);
+            station.request(time, next_job);
+            next_job += 1;
+        }
+
+        let Some(current) = station.pick(&requested) else {
+            // Idle until the next request
+            time = station.jobs.get(next_job).map(|j| j.request_time).unwrap_or(time + 1);
+            continue;
+        };
+        if station.serving != Some(current) {
+            if let Some(prev) = station.serving.filter(|&p| station.jobs[p].remaining > 0) {
+                station.log(time, prev, "PREEMPTED", format!("by family {}", station.jobs[current].family_id));
+            }
+            station.log(time, current, "SERVE", String::new());
+            station.serving = Some(current);
+        }
+
+        // Blocked high parties lose this unit to lower priority work that isn't the holder
+        let served_effective = station.jobs[current].effective;
+        let holder = station.holder;
+        for &i in &requested {
+            let job = &mut station.jobs[i];
+            if job.blocked_since.is_some() && job.priority > served_effective && Some(current) != holder {
+                job.inverted_time += 1;
+            }
+        }
+
+        station.jobs[current].remaining -= 1;
+        time += 1;
+        if station.jobs[current].remaining == 0 {
+            station.finish(time, current, &requested);
+            station.serving = None;
+        }
+    }
+
+    CondimentReport {
+        config: config.clone(),
+        max_inverted_time: station.inversions.iter().map(|i| i.inverted_time).max().unwrap_or(0),
+        inversions: station.inversions,
+        timeline: station.timeline,
+    }
+}
+
+#[tauri::command]
+pub fn get_condiment_report(session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<CondimentReport> {
+    let session_id = sessions.resolve(session_id, &window);
+    sessions.with_session(&session_id, |run| run.condiment_report.clone())?
+        .ok_or_else(|| AppError::SessionError("Run has no condiment station; start it with condiments.enabled".to_string()))
+}
@@ -0,0 +1,148 @@
+use crate::live::DEFAULT_LIVE_EMIT_RATE;
+use crate::models::{CondimentConfig, CourierConfig, DiningTimeBand, FrameResolution, GeneratorConfig, KitchenConfig, RequirementChange, RestroomConfig, SelfServiceStation};
+use crate::simulation::{DEFAULT_CONVEYOR_PATIENCE, DEFAULT_RESERVATION_GRACE};
+use serde::{Deserialize, Serialize};
+
+// Everything a simulation run needs, in one place. Missing fields fall back to defaults
+// so the frontend only has to send what it changes.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SimulationConfig {
+    pub csv_content: String,
+    pub seat_config_json: String,
+    pub baby_chairs: i32,
+    pub wheelchairs: i32,
+    pub shadow_strategy: Option<String>, // e.g. "BEST_FIT"; None disables shadow mode
+    pub objective: Option<String>,       // "WAIT" (default) or "REVENUE"
+    pub strategy: Option<String>,        // seating policy, e.g. "BEST_FIT"; "DEFAULT" if None
+    pub fairness: Option<String>,        // "FIFO" (default) or "OPPORTUNISTIC" (threaded engines only)
+    pub conveyor_patience: u64,
+    pub kitchen: KitchenConfig,
+    pub deadlock_resolution: Option<String>, // "BACK_OFF" (default) or "ABORT"; semaphores engine only
+    pub instrument: bool,                     // collect the engine-health report
+    pub trace_sync: bool,                     // teaching mode: record the synchronization trace
+    pub engine: Option<String>,               // "des" (default), "threads"/"realtime", "semaphores" or "tasks"
+    pub deliver_baby_chairs_later: bool,      // seat without free baby chairs, deliver them once freed (not on semaphores)
+    pub condiments: CondimentConfig,          // priority inversion scenario, off by default
+    pub merge_tables: bool,                   // push adjacent tables together for parties too big for one
+    pub reservations_json: Option<String>,    // tables held for booked parties (TableReservation list)
+    pub reservation_grace: u64,               // minutes a reserved table waits for a late party
+    pub stations: Vec<SelfServiceStation>,    // self-service stations; none by default
+    pub restroom: Option<RestroomConfig>,     // None: restroom trips don't affect stays
+    pub generator: Option<GeneratorConfig>,   // seeded generator the roster came from; draws it when csv_content is empty
+    pub live_emit_rate: u32,                  // max live updates per second in realtime runs; 0 for none
+    pub strict_csv: bool,                     // fail on unreadable roster rows instead of skipping them
+    pub max_wait_time: Option<u64>,           // minutes a party waits before leaving unserved; None waits forever
+    pub frame_resolution: FrameResolution,    // frames per second of the run, per event, ...
+    pub requirement_changes: Vec<RequirementChange>, // waiting parties whose needs change (des engine only)
+    pub couriers: CourierConfig,              // delivery orders collected at the pickup counter; none by default
+    pub dining_time_bands: Vec<DiningTimeBand>, // scale the roster's stays by time of arrival, e.g. quicker lunches
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        SimulationConfig {
+            csv_content: String::new(),
+            seat_config_json: "[]".to_string(),
+            baby_chairs: 4,
+            wheelchairs: 2,
+            shadow_strategy: None,
+            objective: None,
+            strategy: None,
+            fairness: None,
+            conveyor_patience: DEFAULT_CONVEYOR_PATIENCE,
+            kitchen: KitchenConfig::default(),
+            deadlock_resolution: None,
+            instrument: false,
+            trace_sync: false,
+            engine: None,
+            deliver_baby_chairs_later: false,
+            condiments: CondimentConfig::default(),
+            merge_tables: false,
+            reservations_json: None,
+            reservation_grace: DEFAULT_RESERVATION_GRACE,
+            stations: Vec::new(),
+            restroom: None,
+            generator: None,
+            live_emit_rate: DEFAULT_LIVE_EMIT_RATE,
+            strict_csv: false,
+            max_wait_time: None,
+            frame_resolution: FrameResolution::default(),
+            requirement_changes: Vec::new(),
+            couriers: CourierConfig::default(),
+            dining_time_bands: Vec::new(),
+        }
+    }
+}
+
+impl SimulationConfig {
+    pub fn builder(csv_content: impl Into<String>, seat_config_json: impl Into<String>) -> SimulationConfigBuilder {
+        SimulationConfigBuilder {
+            config: SimulationConfig {
+                csv_content: csv_content.into(),
+                seat_config_json: seat_config_json.into(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+pub struct SimulationConfigBuilder {
+    config: SimulationConfig,
+}
+
+impl SimulationConfigBuilder {
+    pub fn baby_chairs(mut self, count: i32) -> Self {
+        self.config.baby_chairs = count;
+        self
+    }
+
+    pub fn wheelchairs(mut self, count: i32) -> Self {
+        self.config.wheelchairs = count;
+        self
+    }
+
+    pub fn shadow_strategy(mut self, strategy: Option<String>) -> Self {
+        self.config.shadow_strategy = strategy;
+        self
+    }
+
+    pub fn objective(mut self, objective: Option<String>) -> Self {
+        self.config.objective = objective;
+        self
+    }
+
+    pub fn strategy(mut self, strategy: Option<String>) -> Self {
+        self.config.strategy = strategy;
+        self
+    }
+
+    pub fn fairness(mut self, fairness: Option<String>) -> Self {
+        self.config.fairness = fairness;
+        self
+    }
+
+    pub fn conveyor_patience(mut self, patience: u64) -> Self {
+        self.config.conveyor_patience = patience;
+        self
+    }
+
+    pub fn kitchen(mut self, kitchen: KitchenConfig) -> Self {
+        self.config.kitchen = kitchen;
+        self
+    }
+
+    pub fn merge_tables(mut self, merge: bool) -> Self {
+        self.config.merge_tables = merge;
+        self
+    }
+
+    pub fn reservations_json(mut self, json: Option<String>) -> Self {
+        self.config.reservations_json = json;
+        self
+    }
+
+    pub fn build(self) -> SimulationConfig {
+        self.config
+    }
+}
@@ -0,0 +1,103 @@
+use crate::access;
+use crate::errors::{AppError, Result};
+use crate::models::{Coupon, CouponDaySummary, CouponPolicy, CouponReport, CustomerConfig};
+use crate::session::SessionStore;
+use crate::simulation::{self, EngineParams};
+use std::ops::Range;
+use std::sync::atomic::Ordering;
+use tauri::{State, Window};
+
+// Family ids handed to returning coupon holders. Like COURIER_IDS (just below), far above
+// anything the parser lets through or hands out, so a returning party is never mistaken
+// for one on the roster.
+pub const RETURNING_IDS: Range<u32> = 3_100_000_000..3_200_000_000;
+
+// Replay the session's roster over several days. Parties that leave unserved get a coupon
+// and come back `return_after_days` later at the same time of day. Returning parties are
+// ordered first among same-time arrivals, so they get the first shot at a free seat.
+// Each party gets at most one coupon.
+#[tauri::command]
+pub fn run_coupon_days(session_id: Option<String>, days: u32, policy: Option<CouponPolicy>, window: Window, sessions: State<'_, SessionStore>) -> Result<CouponReport> {
+    access::require_host(&window, "run simulations")?;
+    let session_id = sessions.resolve(session_id, &window);
+    let policy = policy.unwrap_or_default();
+    if days == 0 {
+        return Err(AppError::SimulationError("days must be at least 1".to_string()));
+    }
+
+    let (roster, seats, params) = sessions.with_session(&session_id, |run| {
+        (run.customers.clone(), run.seats_config.clone(), EngineParams {
+            shadow_policy: None,
+            ..run.params.clone()
+        })
+    })?;
+
+    let _run_guard = sessions.begin_run()?;
+    let cancel = sessions.cancel_flag();
+
+    let mut coupons: Vec<Coupon> = Vec::new();
+    let mut day_summaries = Vec::new();
+    let mut next_returning_id = RETURNING_IDS.start;
+
+    for day in 1..=days {
+        let mut customers = roster.clone();
+        let mut redeemed_today = 0;
+        for coupon in coupons.iter_mut().filter(|c| c.redeem_day == day) {
+            let Some(original) = roster.iter().find(|c| c.family_id == coupon.original_family_id) else { continue };
+            if !RETURNING_IDS.contains(&next_returning_id) {
+                return Err(AppError::SimulationError(format!("More than {} coupons redeemed", RETURNING_IDS.len())));
+            }
+            let returning = CustomerConfig { family_id: next_returning_id, id: next_returning_id.into(), ..original.clone() };
+            coupon.returned_family_id = Some(next_returning_id);
+            next_returning_id += 1;
+            redeemed_today += 1;
+            customers.push(returning);
+        }
+        // Stable sort: roster order is kept, returning parties go first at equal times
+        customers.sort_by_key(|c| (c.arrival_time, !RETURNING_IDS.contains(&c.family_id)));
+
+        let res = simulation::run_engine(&customers, &seats, &params, &cancel)?;
+        if cancel.load(Ordering::SeqCst) {
+            return Err(AppError::SimulationError("Application is shutting down".to_string()));
+        }
+        let sit_times = simulation::sit_times(&res);
+
+        let mut lost = 0;
+        let mut issued = 0;
+        for customer in customers.iter().filter(|c| !sit_times.contains_key(&c.family_id)) {
+            lost += 1;
+            let returning = RETURNING_IDS.contains(&customer.family_id);
+            if !policy.enabled || returning {
+                continue;
+            }
+            issued += 1;
+            coupons.push(Coupon {
+                original_family_id: customer.family_id,
+                issued_day: day,
+                redeem_day: day + policy.return_after_days.max(1),
+                returned_family_id: None,
+                served_on_return: false,
+            });
+        }
+
+        // Mark which returning parties actually got a seat
+        for coupon in coupons.iter_mut().filter(|c| c.redeem_day == day) {
+            coupon.served_on_return = coupon.returned_family_id.is_some_and(|id| sit_times.contains_key(&id));
+        }
+
+        day_summaries.push(CouponDaySummary {
+            day,
+            arrivals: customers.len(),
+            served: customers.len() - lost,
+            lost,
+            coupons_issued: issued,
+            coupons_redeemed: redeemed_today,
+        });
+    }
+
+    Ok(CouponReport {
+        days: day_summaries,
+        outstanding: coupons.iter().filter(|c| c.redeem_day > days).count(),
+        coupons,
+    })
+}
@@ -0,0 +1,109 @@
+use crate::errors::{AppError, Result};
+use crate::models::{CourierConfig, CourierOrder, CourierPickup, CourierReport};
+use crate::session::SessionStore;
+use crate::stations::book_slot;
+use std::ops::Range;
+use tauri::{State, Window};
+
+// Family ids courier orders log their events under: the base plus the order id. The
+// parser refuses roster ids at or above CODE_FAMILY_ID_BASE and hands out codes far
+// below this, so a courier never shares a family with a party.
+pub const COURIER_IDS: Range<u32> = 3_000_000_000..3_100_000_000;
+
+// Event types of a courier order's steps
+pub const COURIER_ORDER_EVENT: &str = "COURIER_ORDER";
+pub const COURIER_ARRIVAL_EVENT: &str = "COURIER_ARRIVAL";
+pub const COURIER_LEFT_EVENT: &str = "COURIER_LEFT";
+
+// Delivery couriers. Their orders come in through an app and queue for the kitchen line
+// with the dine-in orders, first come first served by the time each was fired, so a
+// rush of couriers stretches dine-in stays and a full dining room delays couriers. A
+// courier never takes a seat, only a pickup counter slot once the food is ready. The
+// engine fires the courier orders due before each dine-in order, then the rest at the end.
+#[derive(Clone)]
+pub struct CourierDesk {
+    config: CourierConfig,
+    pending: Vec<CourierOrder>, // not fired yet, latest order first
+    counter: Vec<Vec<(u64, u64)>>,
+    pickups: Vec<CourierPickup>,
+}
+
+impl CourierDesk {
+    pub fn new(config: &CourierConfig) -> Self {
+        let mut pending = config.orders.clone();
+        pending.sort_by_key(|o| std::cmp::Reverse((o.order_time, o.id)));
+        CourierDesk {
+            config: config.clone(),
+            pending,
+            counter: vec![Vec::new(); config.pickup_slots.max(1) as usize],
+            pickups: Vec::new(),
+        }
+    }
+
+    // Fire the orders placed up to `until` on the line and book their pickups; returns
+    // how many were fired, which are the last ones in pickups()
+    pub fn fire(&mut self, line: &mut [Vec<(u64, u64)>], standard_prep_time: u64, until: u64) -> usize {
+        let pickup_time = self.config.pickup_time.max(1);
+        let mut fired = 0;
+        while let Some(order) = self.pending.pop_if(|o| o.order_time <= until) {
+            let prep_time = order.prep_time.unwrap_or(standard_prep_time).max(1);
+            let ready_time = book_slot(line, order.order_time, prep_time) + prep_time;
+            let pickup_start = book_slot(&mut self.counter, order.arrival_time.max(ready_time), pickup_time);
+            let departure = pickup_start + pickup_time;
+            let wait = departure - order.arrival_time;
+            self.pickups.push(CourierPickup {
+                id: order.id,
+                order_time: order.order_time,
+                arrival_time: order.arrival_time,
+                ready_time,
+                pickup_start,
+                departure,
+                wait,
+                sla_met: wait <= self.config.sla,
+            });
+            fired += 1;
+        }
+        fired
+    }
+
+    pub fn pickups(&self) -> &[CourierPickup] {
+        &self.pickups
+    }
+
+    pub fn report(&self) -> CourierReport {
+        let pickups = &self.pickups;
+        let total_wait: u64 = pickups.iter().map(|p| p.wait).sum();
+        CourierReport {
+            pickup_slots: self.config.pickup_slots.max(1),
+            sla: self.config.sla,
+            couriers: pickups.len(),
+            sla_breaches: pickups.iter().filter(|p| !p.sla_met).count(),
+            avg_wait: if pickups.is_empty() { 0.0 } else { total_wait as f64 / pickups.len() as f64 },
+            max_wait: pickups.iter().map(|p| p.wait).max().unwrap_or(0),
+            pickups: pickups.clone(),
+        }
+    }
+}
+
+// Couriers only compete with dine-in for a line of limited capacity, and their ids must
+// fit the range their events are logged under
+pub fn validate(config: &CourierConfig, line_capacity: u32) -> Result<()> {
+    if config.orders.is_empty() {
+        return Ok(());
+    }
+    if line_capacity == 0 {
+        return Err(AppError::ValidationError("Courier orders share the kitchen line with dine-in orders; set kitchen.lineCapacity".to_string()));
+    }
+    let limit = COURIER_IDS.end - COURIER_IDS.start;
+    if let Some(order) = config.orders.iter().find(|o| o.id >= limit) {
+        return Err(AppError::ValidationError(format!("Courier order id {} is too large (at most {})", order.id, limit - 1)));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_courier_report(session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<CourierReport> {
+    let session_id = sessions.resolve(session_id, &window);
+    sessions.with_session(&session_id, |run| run.kitchen_report.couriers.clone())?
+        .ok_or_else(|| AppError::SessionError("Run has no courier orders; add them under couriers.orders".to_string()))
+}
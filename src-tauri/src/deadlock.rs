@@ -0,0 +1,112 @@
+use crate::errors::{AppError, Result};
+use std::collections::{HashMap, HashSet};
+
+// Event type recorded when a circular wait is found
+pub const DEADLOCK_EVENT: &str = "DEADLOCK_DETECTED";
+
+// What the party that closes a wait cycle does
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) enum DeadlockResolution {
+    // Give back what it holds, let the next release wake it and try again
+    #[default]
+    BackOff,
+    // Give back what it holds and leave unserved
+    Abort,
+}
+
+impl DeadlockResolution {
+    pub(crate) fn from_name(name: &str) -> Result<Self> {
+        match name.to_uppercase().as_str() {
+            "BACK_OFF" | "BACKOFF" => Ok(DeadlockResolution::BackOff),
+            "ABORT" => Ok(DeadlockResolution::Abort),
+            _ => Err(AppError::SimulationError(format!("Unknown deadlock resolution: {}", name))),
+        }
+    }
+}
+
+// Wait-for graph: an edge runs from a waiting party to every party holding a resource it
+// waits on. The semaphores engine feeds it: a party there takes chair permits one at a
+// time and holds them while it waits for the rest and for a seat, so two parties short
+// of chairs can each hold what the other waits on.
+#[derive(Clone, Default)]
+pub(crate) struct WaitForGraph {
+    holders: HashMap<String, HashSet<u32>>,
+    waiting: HashMap<u32, Vec<String>>,
+}
+
+impl WaitForGraph {
+    pub(crate) fn hold(&mut self, party: u32, resources: &[String]) {
+        for r in resources {
+            self.holders.entry(r.clone()).or_default().insert(party);
+        }
+    }
+
+    pub(crate) fn release_all(&mut self, party: u32) {
+        for parties in self.holders.values_mut() {
+            parties.remove(&party);
+        }
+        self.holders.retain(|_, parties| !parties.is_empty());
+    }
+
+    pub(crate) fn wait_for(&mut self, party: u32, resources: Vec<String>) {
+        self.waiting.insert(party, resources);
+    }
+
+    pub(crate) fn stop_waiting(&mut self, party: u32) {
+        self.waiting.remove(&party);
+    }
+
+    #[cfg(test)]
+    pub(crate) fn is_waiting(&self, party: u32) -> bool {
+        self.waiting.contains_key(&party)
+    }
+
+    fn blockers(&self, party: u32) -> Vec<u32> {
+        let mut blockers: Vec<u32> = self.waiting.get(&party).into_iter()
+            .flatten()
+            .filter_map(|r| self.holders.get(r))
+            .flatten()
+            .copied()
+            .filter(|&p| p != party)
+            .collect();
+        blockers.sort_unstable();
+        blockers.dedup();
+        blockers
+    }
+
+    // A cycle of parties through `start`, starting with it, if one exists
+    pub(crate) fn cycle_through(&self, start: u32) -> Option<Vec<u32>> {
+        let mut path = vec![start];
+        let mut visited = HashSet::from([start]);
+        self.search(start, &mut path, &mut visited)
+    }
+
+    fn search(&self, party: u32, path: &mut Vec<u32>, visited: &mut HashSet<u32>) -> Option<Vec<u32>> {
+        for next in self.blockers(party) {
+            if next == path[0] {
+                return Some(path.clone());
+            }
+            if visited.insert(next) {
+                path.push(next);
+                if let Some(cycle) = self.search(next, path, visited) {
+                    return Some(cycle);
+                }
+                path.pop();
+            }
+        }
+        None
+    }
+
+    // Resources each party in the cycle waits on that the next one holds, for the event message
+    pub(crate) fn describe(&self, cycle: &[u32]) -> String {
+        cycle.iter().enumerate().map(|(i, &party)| {
+            let next = cycle[(i + 1) % cycle.len()];
+            let contested: Vec<&str> = self.waiting.get(&party).into_iter()
+                .flatten()
+                .filter(|r| self.holders.get(*r).is_some_and(|h| h.contains(&next)))
+                .map(String::as_str)
+                .collect();
+            format!("{} waits on {} for [{}]", party, next, contested.join(","))
+        }).collect::<Vec<_>>().join(" -> ")
+    }
+}
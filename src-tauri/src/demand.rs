@@ -0,0 +1,163 @@
+use crate::errors::{AppError, Result};
+use crate::models::{CustomerConfig, DemandFit, DiningDistribution, DiningTimeModel, GeneratorConfig};
+use crate::parser;
+use crate::session::{SessionRun, SessionStore};
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use tauri::State;
+
+const DEFAULT_BUCKET_MINUTES: u64 = 30;
+
+// One past day of demand: who came when, and how long each party stayed
+struct Sample {
+    customers: Vec<CustomerConfig>,
+    dining: Vec<u64>,
+}
+
+// Stay lengths actually observed in a run (seated to left), falling back to the
+// roster's estimate for parties that never finished
+fn observed_dining(run: &SessionRun) -> Vec<u64> {
+    let mut seated: HashMap<u32, u64> = HashMap::new();
+    let mut stays: HashMap<u32, u64> = HashMap::new();
+    for e in run.frames.iter().flat_map(|f| f.events.iter()) {
+        match e.type_.as_str() {
+            "SEATED" => { seated.entry(e.family_id).or_insert(e.timestamp); }
+            "LEFT" => {
+                if let Some(start) = seated.get(&e.family_id) {
+                    stays.entry(e.family_id).or_insert(e.timestamp.saturating_sub(*start));
+                }
+            }
+            _ => {}
+        }
+    }
+    run.customers.iter()
+        .map(|c| stays.get(&c.family_id).copied().unwrap_or(c.est_dining_time))
+        .collect()
+}
+
+fn mean_and_std_dev(values: &[f64]) -> (f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+    (mean, variance.sqrt())
+}
+
+fn normal_log_likelihood(values: &[f64], mean: f64, std_dev: f64) -> f64 {
+    let variance = std_dev * std_dev;
+    values.iter()
+        .map(|v| -0.5 * (2.0 * PI * variance).ln() - (v - mean).powi(2) / (2.0 * variance))
+        .sum()
+}
+
+// Maximum-likelihood fits of each supported distribution; the most likely one wins
+fn fit_dining(dining: &[u64]) -> (DiningTimeModel, f64) {
+    let values: Vec<f64> = dining.iter().map(|&d| d.max(1) as f64).collect();
+    let min = dining.iter().copied().min().unwrap_or(0).max(1);
+    let max = dining.iter().copied().max().unwrap_or(0).max(1);
+    let n = values.len() as f64;
+
+    let uniform_ll = if max > min { -n * ((max - min) as f64).ln() } else { 0.0 };
+    let mut best = (DiningTimeModel { distribution: DiningDistribution::Uniform, mean: (min + max) as f64 / 2.0, std_dev: 0.0, min, max }, uniform_ll);
+    if max == min {
+        return best;
+    }
+
+    let (mean, std_dev) = mean_and_std_dev(&values);
+    let normal_ll = normal_log_likelihood(&values, mean, std_dev);
+    if normal_ll > best.1 {
+        best = (DiningTimeModel { distribution: DiningDistribution::Normal, mean, std_dev, min, max }, normal_ll);
+    }
+
+    let logs: Vec<f64> = values.iter().map(|v| v.ln()).collect();
+    let (log_mean, log_std_dev) = mean_and_std_dev(&logs);
+    if log_std_dev > 0.0 {
+        // Change of variables: the density of x picks up a 1/x factor
+        let log_normal_ll = normal_log_likelihood(&logs, log_mean, log_std_dev) - logs.iter().sum::<f64>();
+        if log_normal_ll > best.1 {
+            best = (DiningTimeModel { distribution: DiningDistribution::LogNormal, mean: log_mean, std_dev: log_std_dev, min, max }, log_normal_ll);
+        }
+    }
+    best
+}
+
+fn fit(samples: &[Sample], bucket_minutes: u64) -> DemandFit {
+    let sources = samples.len();
+    let customers: Vec<&CustomerConfig> = samples.iter().flat_map(|s| s.customers.iter()).collect();
+    let dining: Vec<u64> = samples.iter().flat_map(|s| s.dining.iter().copied()).collect();
+    let parties = customers.len();
+    let mut notes = Vec::new();
+
+    // Arrival curve: a piecewise-constant rate per bucket, averaged over the days
+    let max_arrival_time = customers.iter().map(|c| c.arrival_time).max().unwrap_or(0);
+    let buckets = (max_arrival_time / bucket_minutes + 1) as usize;
+    let mut counts = vec![0usize; buckets];
+    for c in &customers {
+        counts[(c.arrival_time / bucket_minutes) as usize] += 1;
+    }
+    let arrivals_per_minute: Vec<f64> = counts.iter()
+        .map(|&n| n as f64 / (sources as f64 * bucket_minutes as f64))
+        .collect();
+
+    // Party mix and special needs by frequency (the MLE for categorical data)
+    let largest = customers.iter().map(|c| c.party_size.max(1)).max().unwrap_or(1) as usize;
+    let mut party_size_weights = vec![0.0; largest];
+    for c in &customers {
+        party_size_weights[c.party_size.max(1) as usize - 1] += 1.0 / parties as f64;
+    }
+    let share = |pred: &dyn Fn(&CustomerConfig) -> bool| customers.iter().filter(|c| pred(c)).count() as f64 / parties as f64;
+    let baby_probability = share(&|c| c.baby_chair_count > 0);
+    let wheelchair_probability = share(&|c| c.wheelchair_count > 0);
+
+    let (dining_time, dining_log_likelihood) = fit_dining(&dining);
+    if dining_time.distribution == DiningDistribution::Uniform && dining_time.min == dining_time.max {
+        notes.push(format!("every party stayed {} minutes; dining time is fixed", dining_time.min));
+    }
+    if parties < 30 {
+        notes.push(format!("only {} parties observed; the fit is rough", parties));
+    }
+
+    DemandFit {
+        generator: GeneratorConfig {
+            count: (parties as f64 / sources as f64).round() as u32,
+            max_arrival_time,
+            arrival_rates: arrivals_per_minute.clone(),
+            bucket_minutes,
+            party_size_weights,
+            baby_probability,
+            wheelchair_probability,
+            dining_time,
+            seed: None,
+            dining_time_bands: Vec::new(),
+        },
+        sources,
+        parties,
+        arrivals_per_minute,
+        dining_log_likelihood,
+        notes,
+    }
+}
+
+// Fit a generator config to past days: stored runs (by session) and/or roster CSVs,
+// each counted as one day. Feed the result to generate_customers to get look-alike
+// synthetic scenarios.
+#[tauri::command]
+pub fn fit_demand_model(session_ids: Option<Vec<String>>, csv_contents: Option<Vec<String>>, bucket_minutes: Option<u64>, sessions: State<'_, SessionStore>) -> Result<DemandFit> {
+    let mut samples = Vec::new();
+    for session_id in session_ids.unwrap_or_default() {
+        samples.push(sessions.with_session(&session_id, |run| Sample { customers: run.customers.clone(), dining: observed_dining(run) })?);
+    }
+    for csv_content in csv_contents.unwrap_or_default() {
+        let customers = parser::parse_customers(&csv_content).map_err(|e| AppError::CsvParseError(e.to_string()))?;
+        let dining = customers.iter().map(|c| c.est_dining_time).collect();
+        samples.push(Sample { customers, dining });
+    }
+
+    if samples.iter().all(|s| s.customers.is_empty()) {
+        return Err(AppError::ValidationError("No parties to fit: pass at least one non-empty run or roster".to_string()));
+    }
+    let bucket_minutes = bucket_minutes.unwrap_or(DEFAULT_BUCKET_MINUTES);
+    if bucket_minutes == 0 {
+        return Err(AppError::ValidationError("bucket_minutes must be positive".to_string()));
+    }
+    Ok(fit(&samples, bucket_minutes))
+}
@@ -0,0 +1,395 @@
+use crate::models::{CsvRowIssue, CustomerId, Diagnostic, DiagnosticSeverity, QuickFix, SeatConfig, TextEdit, TextPosition, TextRange};
+use crate::errors::{AppError, Result};
+use crate::parser;
+use std::collections::HashSet;
+
+// Columns of the customer CSV, in positional order
+pub const CSV_COLUMNS: [&str; 7] = [
+    "id", "arrival_time", "type", "party_size", "baby_chair", "wheel_chair", "est_dining_time",
+];
+
+const KNOWN_SEAT_TYPES: [&str; 3] = ["SINGLE", "4P", "6P"];
+const MAX_PARTY_SIZE: u32 = 12;
+
+fn range(line: u32, start: u32, end: u32) -> TextRange {
+    TextRange {
+        start: TextPosition { line, character: start },
+        end: TextPosition { line, character: end },
+    }
+}
+
+fn diagnostic(source: &str, code: &str, severity: DiagnosticSeverity, range: TextRange, message: String, fix: Option<QuickFix>) -> Diagnostic {
+    Diagnostic {
+        range,
+        severity,
+        code: code.to_string(),
+        source: source.to_string(),
+        message,
+        quick_fix: fix,
+    }
+}
+
+fn suggestion(title: &str) -> Option<QuickFix> {
+    Some(QuickFix { title: title.to_string(), edits: Vec::new() })
+}
+
+// Fix that replaces `range` with `new_text`
+fn replace_fix(title: &str, range: TextRange, new_text: String) -> Option<QuickFix> {
+    Some(QuickFix { title: title.to_string(), edits: vec![TextEdit { range, new_text }] })
+}
+
+// Split a CSV line into (field text, start column, end column)
+fn csv_fields(line: &str) -> Vec<(&str, u32, u32)> {
+    let mut fields = Vec::new();
+    let mut start = 0usize;
+    for field in line.split(',') {
+        let end = start + field.chars().count();
+        fields.push((field, start as u32, end as u32));
+        start = end + 1;
+    }
+    fields
+}
+
+// Validate customer CSV text, mirroring what parse_customers accepts or silently defaults
+pub fn validate_customer_csv(csv_content: &str) -> Vec<Diagnostic> {
+    const SOURCE: &str = "customer-csv";
+    let mut diagnostics = Vec::new();
+    let mut seen_ids = HashSet::new();
+
+    for (i, raw_line) in csv_content.lines().enumerate() {
+        let line_no = i as u32;
+        let line = raw_line.trim_end();
+        if line.trim().is_empty() { continue; }
+
+        if i == 0 {
+            if parser::is_header_line(line) { continue; }
+            diagnostics.push(diagnostic(SOURCE, "missing-header", DiagnosticSeverity::Warning,
+                range(0, 0, 0),
+                "No header row; columns are read positionally".to_string(),
+                replace_fix("Insert the standard header row", range(0, 0, 0), format!("{}\n", CSV_COLUMNS.join(",")))));
+        }
+
+        let fields = csv_fields(line);
+        let line_end = line.chars().count() as u32;
+
+        if fields.len() < CSV_COLUMNS.len() {
+            diagnostics.push(diagnostic(SOURCE, "missing-columns", DiagnosticSeverity::Warning,
+                range(line_no, 0, line_end),
+                format!("Expected {} columns, found {}; missing values use defaults", CSV_COLUMNS.len(), fields.len()),
+                None));
+        }
+
+        // id
+        let (id_text, id_start, id_end) = fields[0];
+        // Positive numbers and booking codes are ids; negative numbers mark pre-seated parties
+        let pre_seated = id_text.trim().parse::<i32>().is_ok_and(|n| n < 0);
+        match CustomerId::parse(id_text) {
+            _ if pre_seated => {}
+            Some(id) if id.number() != Some(0) => {
+                if !seen_ids.insert(id.clone()) {
+                    diagnostics.push(diagnostic(SOURCE, "duplicate-id", DiagnosticSeverity::Warning,
+                        range(line_no, id_start, id_end),
+                        format!("Duplicate id {}", id),
+                        None));
+                }
+            }
+            _ => {
+                diagnostics.push(diagnostic(SOURCE, "invalid-id", DiagnosticSeverity::Error,
+                    range(line_no, id_start, id_end),
+                    format!("Invalid id '{}'; this row will be skipped", id_text.trim()),
+                    None));
+                continue;
+            }
+        }
+
+        // Numeric columns (index, allows "true"/"false")
+        for (col, allows_bool) in [(1, false), (3, false), (4, true), (5, true), (6, false)] {
+            let Some(&(text, start, end)) = fields.get(col) else { continue };
+            let value = text.trim().to_lowercase();
+            if value.is_empty() || value.parse::<i64>().is_ok() { continue; }
+
+            if allows_bool && (value == "true" || value == "false") {
+                diagnostics.push(diagnostic(SOURCE, "boolean-in-numeric", DiagnosticSeverity::Hint,
+                    range(line_no, start, end),
+                    format!("'{}' in numeric column {} is read as {}", text.trim(), CSV_COLUMNS[col], if value == "true" { 1 } else { 0 }),
+                    replace_fix("Replace with a number", range(line_no, start, end), if value == "true" { "1" } else { "0" }.to_string())));
+            } else {
+                diagnostics.push(diagnostic(SOURCE, "not-a-number", DiagnosticSeverity::Warning,
+                    range(line_no, start, end),
+                    format!("'{}' is not a number in column {}; a default is used", text.trim(), CSV_COLUMNS[col]),
+                    None));
+            }
+        }
+
+        // Party size bounds
+        if let Some(&(text, start, end)) = fields.get(3) {
+            if let Ok(size) = text.trim().parse::<u32>() {
+                if size == 0 || size > MAX_PARTY_SIZE {
+                    diagnostics.push(diagnostic(SOURCE, "party-size-range", DiagnosticSeverity::Warning,
+                        range(line_no, start, end),
+                        format!("Party size {} is outside 1..={}", size, MAX_PARTY_SIZE),
+                        replace_fix("Clamp party size into range", range(line_no, start, end), size.clamp(1, MAX_PARTY_SIZE).to_string())));
+                }
+            }
+        }
+    }
+
+    diagnostics
+}
+
+// Customer CSV diagnostics as row/field issues: a diagnostic covering exactly one
+// field names that column, anything wider is a whole-row issue
+pub fn customer_row_issues(csv_content: &str) -> Vec<CsvRowIssue> {
+    let lines: Vec<&str> = csv_content.lines().collect();
+    let header: Vec<String> = lines.first()
+        .filter(|l| parser::is_header_line(l))
+        .map(|l| l.split(',').map(|h| h.trim().to_string()).collect())
+        .unwrap_or_default();
+
+    validate_customer_csv(csv_content).into_iter().map(|d| {
+        let line = d.range.start.line;
+        let fields = lines.get(line as usize).map(|l| csv_fields(l.trim_end())).unwrap_or_default();
+        let field = fields.iter()
+            .position(|&(_, start, end)| start == d.range.start.character && end == d.range.end.character)
+            .filter(|_| fields.len() > 1 || d.range.start.character != 0)
+            .map(|idx| match header.get(idx) {
+                Some(name) if !name.is_empty() => name.clone(),
+                _ => CSV_COLUMNS.get(idx).map(|c| c.to_string()).unwrap_or_else(|| parser::extra_column_name(&header, idx)),
+            });
+        CsvRowIssue {
+            line: line + 1,
+            family_id: fields.first().and_then(|(id, _, _)| id.trim().parse::<u32>().ok()).filter(|id| *id > 0),
+            field,
+            severity: d.severity,
+            code: d.code,
+            reason: d.message,
+        }
+    }).collect()
+}
+
+// Convert a byte offset into a (line, character) position
+fn position_of(text: &str, offset: usize) -> TextPosition {
+    let before = &text[..offset.min(text.len())];
+    let line = before.matches('\n').count() as u32;
+    let character = before.rsplit('\n').next().unwrap_or("").chars().count() as u32;
+    TextPosition { line, character }
+}
+
+// Best-effort location of a seat's `"id": "X"` entry in the JSON text
+fn seat_range(json: &str, seat_id: &str, nth: usize) -> TextRange {
+    let needle = format!("\"{}\"", seat_id);
+    match json.match_indices(&needle).nth(nth) {
+        Some((offset, _)) => TextRange {
+            start: position_of(json, offset),
+            end: position_of(json, offset + needle.len()),
+        },
+        None => range(0, 0, 0),
+    }
+}
+
+// Validate seat layout JSON (as passed to start_simulation)
+pub fn validate_seat_layout_json(seat_config_json: &str) -> Vec<Diagnostic> {
+    const SOURCE: &str = "seat-layout";
+
+    let seats: Vec<SeatConfig> = match serde_json::from_str(seat_config_json) {
+        Ok(seats) => seats,
+        Err(e) => {
+            let line = e.line().saturating_sub(1) as u32;
+            let col = e.column().saturating_sub(1) as u32;
+            return vec![diagnostic(SOURCE, "invalid-json", DiagnosticSeverity::Error,
+                range(line, col, col + 1), e.to_string(), None)];
+        }
+    };
+
+    let mut diagnostics = Vec::new();
+
+    if seats.is_empty() {
+        diagnostics.push(diagnostic(SOURCE, "no-seats", DiagnosticSeverity::Error,
+            range(0, 0, 0), "The layout has no seats".to_string(), None));
+    }
+
+    let all_ids: HashSet<&str> = seats.iter().map(|s| s.id.as_str()).collect();
+    let mut seen = std::collections::HashMap::new();
+    for seat in &seats {
+        let count = seen.entry(seat.id.as_str()).or_insert(0usize);
+        if *count > 0 {
+            // First free "<id>-<n>" name
+            let new_id = (2..).map(|n| format!("{}-{}", seat.id, n))
+                .find(|candidate| !all_ids.contains(candidate.as_str()))
+                .unwrap_or_default();
+            let at = seat_range(seat_config_json, &seat.id, *count);
+            diagnostics.push(diagnostic(SOURCE, "duplicate-seat-id", DiagnosticSeverity::Error,
+                at,
+                format!("Duplicate seat id '{}'", seat.id),
+                replace_fix(&format!("Rename to '{}'", new_id), at, format!("\"{}\"", new_id))));
+        }
+        *count += 1;
+
+        if !KNOWN_SEAT_TYPES.contains(&seat.type_.as_str()) {
+            diagnostics.push(diagnostic(SOURCE, "unknown-seat-type", DiagnosticSeverity::Error,
+                seat_range(seat_config_json, &seat.id, 0),
+                format!("Seat '{}' has unknown type '{}' (expected SINGLE, 4P or 6P)", seat.id, seat.type_),
+                None));
+        }
+
+        if seat.type_ != "SINGLE" {
+            if let Some(group) = &seat.contiguity_group {
+                diagnostics.push(diagnostic(SOURCE, "table-in-contiguity-group", DiagnosticSeverity::Warning,
+                    seat_range(seat_config_json, &seat.id, 0),
+                    format!("Table '{}' is in contiguity group '{}', which only joins bar seats", seat.id, group),
+                    suggestion("Use adjacentTo to let tables be pushed together")));
+            }
+        }
+    }
+
+    // Seats drawn on top of each other. A layout whose seats all sit at one point
+    // hasn't been arranged yet, so that isn't reported.
+    let positioned: Vec<(&SeatConfig, f32, f32)> = seats.iter()
+        .filter_map(|s| Some((s, s.x?, s.y?)))
+        .collect();
+    let arranged = positioned.windows(2).any(|w| w[0].1 != w[1].1 || w[0].2 != w[1].2);
+    if arranged {
+        for (i, (a, ax, ay)) in positioned.iter().enumerate() {
+            if let Some((b, _, _)) = positioned[..i].iter().find(|(_, bx, by)| (ax - bx).abs() < f32::EPSILON && (ay - by).abs() < f32::EPSILON) {
+                diagnostics.push(diagnostic(SOURCE, "overlapping-seats", DiagnosticSeverity::Warning,
+                    seat_range(seat_config_json, &a.id, 0),
+                    format!("Seat '{}' is at the same position ({}, {}) as seat '{}'", a.id, ax, ay, b.id),
+                    suggestion("Move one of the seats")));
+            }
+        }
+    }
+
+    if !seats.is_empty() && !seats.iter().any(|s| s.is_wheelchair_accessible && s.type_ != "SINGLE") {
+        diagnostics.push(diagnostic(SOURCE, "no-accessible-seat", DiagnosticSeverity::Warning,
+            range(0, 0, 0),
+            "No wheelchair-accessible table; wheelchair parties can never be seated".to_string(),
+            suggestion("Mark a 4P or 6P table as wheelchair accessible")));
+    }
+
+    diagnostics
+}
+
+// Byte offset of a (line, character) position; positions past the end clamp to it
+fn offset_of(text: &str, pos: TextPosition) -> usize {
+    let mut line_start = 0;
+    for _ in 0..pos.line {
+        match text[line_start..].find('\n') {
+            Some(i) => line_start += i + 1,
+            None => return text.len(),
+        }
+    }
+    let line_end = text[line_start..].find('\n').map(|i| line_start + i).unwrap_or(text.len());
+    text[line_start..line_end].char_indices()
+        .nth(pos.character as usize)
+        .map(|(i, _)| line_start + i)
+        .unwrap_or(line_end)
+}
+
+// Apply the edits of the given fixes to `text`. Edits are applied back to front so
+// earlier offsets stay valid; overlapping edits are rejected.
+pub fn apply_text_fixes(text: &str, fixes: &[QuickFix]) -> Result<String> {
+    let mut edits: Vec<(usize, usize, &str)> = fixes.iter()
+        .flat_map(|f| f.edits.iter())
+        .map(|e| (offset_of(text, e.range.start), offset_of(text, e.range.end), e.new_text.as_str()))
+        .collect();
+    edits.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.cmp(&a.1)));
+
+    for pair in edits.windows(2) {
+        // pair[0] starts at or after pair[1]
+        if pair[1].1 > pair[0].0 {
+            return Err(AppError::ValidationError("Quick fixes overlap; apply them one at a time".to_string()));
+        }
+    }
+
+    let mut patched = text.to_string();
+    for (start, end, new_text) in edits {
+        patched.replace_range(start..end.max(start), new_text);
+    }
+    Ok(patched)
+}
+
+#[tauri::command]
+pub fn apply_fixes(text: String, fixes: Vec<QuickFix>) -> Result<String> {
+    apply_text_fixes(&text, &fixes)
+}
+
+#[tauri::command]
+pub fn validate_customer_csv_text(csv_content: String) -> Vec<Diagnostic> {
+    validate_customer_csv(&csv_content)
+}
+
+#[tauri::command]
+pub fn validate_seat_layout(seat_config_json: String) -> Vec<Diagnostic> {
+    validate_seat_layout_json(&seat_config_json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(diagnostics: &[Diagnostic]) -> Vec<&str> {
+        diagnostics.iter().map(|d| d.code.as_str()).collect()
+    }
+
+    fn fixes(diagnostics: &[Diagnostic]) -> Vec<QuickFix> {
+        diagnostics.iter().filter_map(|d| d.quick_fix.clone()).filter(|f| !f.edits.is_empty()).collect()
+    }
+
+    #[test]
+    fn customer_csv_issues_point_at_their_field() {
+        let diagnostics = validate_customer_csv("id,arrival_time,type,party_size,baby_chair,wheel_chair,est_dining_time\n1,0,,2,0,0,30\n1,x,,2,0,0,30\n0,0,,2,0,0,30\n");
+        assert_eq!(codes(&diagnostics), ["duplicate-id", "not-a-number", "invalid-id"]);
+        assert_eq!(diagnostics[1].range, range(2, 2, 3));
+    }
+
+    // Applying every quick fix leaves a roster the validator has nothing to fix in
+    #[test]
+    fn customer_csv_fixes_apply_cleanly() {
+        let text = "1,0,,20,true,0,30\n2,5,,0,0,false,30\n";
+        let diagnostics = validate_customer_csv(text);
+        assert_eq!(codes(&diagnostics), ["missing-header", "boolean-in-numeric", "party-size-range", "boolean-in-numeric", "party-size-range"]);
+        let fixed = apply_text_fixes(text, &fixes(&diagnostics)).unwrap();
+        assert_eq!(fixed, "id,arrival_time,type,party_size,baby_chair,wheel_chair,est_dining_time\n1,0,,12,1,0,30\n2,5,,1,0,0,30\n");
+        assert!(validate_customer_csv(&fixed).is_empty());
+    }
+
+    #[test]
+    fn overlapping_fixes_are_refused() {
+        let fix = |start, end, new_text: &str| QuickFix {
+            title: "edit".to_string(),
+            edits: vec![TextEdit { range: range(0, start, end), new_text: new_text.to_string() }],
+        };
+        assert_eq!(apply_text_fixes("abcdef", &[fix(0, 2, "X"), fix(4, 6, "Y")]).unwrap(), "XcdY");
+        assert!(matches!(apply_text_fixes("abcdef", &[fix(0, 3, "X"), fix(2, 4, "Y")]), Err(AppError::ValidationError(_))));
+        // Positions past the end of a line clamp to it
+        assert_eq!(apply_text_fixes("ab\ncd", &[fix(9, 9, "!")]).unwrap(), "ab!\ncd");
+    }
+
+    #[test]
+    fn layout_issues_and_the_duplicate_id_fix() {
+        let json = r#"[{"id":"T1","type":"4P","isWheelchairAccessible":false},{"id":"T1","type":"8P","isWheelchairAccessible":false}]"#;
+        let diagnostics = validate_seat_layout_json(json);
+        assert_eq!(codes(&diagnostics), ["duplicate-seat-id", "unknown-seat-type", "no-accessible-seat"]);
+        let fixed = apply_text_fixes(json, &fixes(&diagnostics)).unwrap();
+        assert_eq!(codes(&validate_seat_layout_json(&fixed)), ["unknown-seat-type", "no-accessible-seat"]);
+        assert!(fixed.contains(r#"{"id":"T1-2","type":"8P""#));
+    }
+
+    #[test]
+    fn layouts_that_are_not_json_or_empty_are_errors() {
+        assert_eq!(codes(&validate_seat_layout_json("[{\"id\":")), ["invalid-json"]);
+        assert_eq!(codes(&validate_seat_layout_json("[]")), ["no-seats"]);
+    }
+
+    // Seats stacked at one point only count once the layout has been arranged
+    #[test]
+    fn overlapping_seats_in_an_arranged_layout() {
+        let seat = |id: &str, x: f32| format!(r#"{{"id":"{}","type":"4P","isWheelchairAccessible":true,"x":{},"y":0}}"#, id, x);
+        let unarranged = format!("[{},{}]", seat("T1", 0.0), seat("T2", 0.0));
+        assert!(validate_seat_layout_json(&unarranged).is_empty());
+        let arranged = format!("[{},{},{}]", seat("T1", 0.0), seat("T2", 3.0), seat("T3", 0.0));
+        let diagnostics = validate_seat_layout_json(&arranged);
+        assert_eq!(codes(&diagnostics), ["overlapping-seats"]);
+        assert_eq!(diagnostics[0].message, "Seat 'T3' is at the same position (0, 0) as seat 'T1'");
+    }
+}
@@ -11,6 +11,14 @@ pub enum AppError {
     CsvParseError(String),
     #[error("JSON parsing error: {0}")]
     JsonParseError(String),
+    #[error("Session error: {0}")]
+    SessionError(String),
+    #[error("Validation error: {0}")]
+    ValidationError(String),
+    #[error("Protocol error: {0}")]
+    ProtocolError(String),
+    #[error("Access denied: {0}")]
+    AccessDenied(String),
 }
 
 impl Serialize for AppError {
@@ -0,0 +1,98 @@
+use crate::access;
+use crate::errors::{AppError, Result};
+use crate::session::{SessionRun, SessionStore};
+use crate::stats;
+use tauri::{AppHandle, State, Window};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+// The run's log lines in event order, optionally limited to [start, end] (minutes)
+pub fn log_lines(run: &SessionRun, start: Option<u64>, end: Option<u64>) -> Vec<String> {
+    let mut events: Vec<_> = run.frames.iter()
+        .flat_map(|f| f.events.iter())
+        .filter(|e| start.is_none_or(|s| e.timestamp >= s) && end.is_none_or(|t| e.timestamp <= t))
+        .collect();
+    events.sort_by_key(|e| (e.timestamp, e.sequence));
+    events.into_iter().map(|e| e.message.clone()).collect()
+}
+
+fn stats_markdown(run: &SessionRun) -> String {
+    let kpis = stats::kpis(run);
+    let rows = [
+        ("Families served", kpis.families_served.to_string()),
+        ("Covers served", kpis.covers_served.to_string()),
+        ("Average wait (min)", format!("{:.1}", kpis.avg_wait)),
+        ("Table turns per seat", format!("{:.2}", kpis.table_turns_per_seat)),
+        ("Peak occupancy", format!("{:.0}%", kpis.peak_occupancy_pct)),
+        ("Rejection rate", format!("{:.1}%", kpis.rejection_rate * 100.0)),
+        ("Starved families", kpis.starved_families.to_string()),
+        ("Revenue", format!("{:.2}", run.metadata.total_revenue)),
+        ("Engine", format!("{} / {}", run.metadata.engine, run.metadata.strategy)),
+    ];
+    let mut table = vec!["| Metric | Value |".to_string(), "| --- | ---: |".to_string()];
+    table.extend(rows.iter().map(|(name, value)| format!("| {} | {} |", name, value)));
+    table.join("\n") + "\n"
+}
+
+fn copy(app: &AppHandle, text: String) -> Result<String> {
+    app.clipboard().write_text(text.as_str())
+        .map_err(|e| AppError::IoError(std::io::Error::other(format!("Clipboard unavailable: {}", e))))?;
+    Ok(text)
+}
+
+// Copy the run's headline statistics as a Markdown table; returns the copied text
+#[tauri::command]
+pub fn copy_stats_summary(session_id: Option<String>, app: AppHandle, window: Window, sessions: State<'_, SessionStore>) -> Result<String> {
+    let session_id = sessions.resolve(session_id, &window);
+    let text = sessions.with_session(&session_id, |run| stats_markdown(run))?;
+    copy(&app, text)
+}
+
+// Copy the log lines between two timestamps (inclusive; either end open)
+#[tauri::command]
+pub fn copy_log_range(start: Option<u64>, end: Option<u64>, session_id: Option<String>, app: AppHandle, window: Window, sessions: State<'_, SessionStore>) -> Result<String> {
+    let session_id = sessions.resolve(session_id, &window);
+    let lines = sessions.with_session(&session_id, |run| log_lines(run, start, end))?;
+    if lines.is_empty() {
+        return Err(AppError::ValidationError("No log lines in the selected range".to_string()));
+    }
+    copy(&app, lines.join("\n") + "\n")
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn log_csv(run: &SessionRun) -> String {
+    let mut events: Vec<_> = run.frames.iter().flat_map(|f| f.events.iter()).collect();
+    events.sort_by_key(|e| (e.timestamp, e.sequence));
+    let mut lines = vec!["timestamp,sequence,type,family_id,seat_id,message".to_string()];
+    lines.extend(events.into_iter().map(|e| format!("{},{},{},{},{},{}",
+        e.timestamp, e.sequence, e.type_, e.family_id,
+        csv_field(e.seat_id.as_deref().unwrap_or("")), csv_field(&e.message))));
+    lines.join("\n") + "\n"
+}
+
+// Write the run's log to a file: one output_rule.txt line per event ("txt", the default)
+// or one CSV row per event ("csv"). Without a format the path's extension decides.
+#[tauri::command]
+pub fn export_log(path: String, format: Option<String>, session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<usize> {
+    access::require_host(&window, "export logs")?;
+    let session_id = sessions.resolve(session_id, &window);
+    let format = format.unwrap_or_else(|| {
+        if path.to_lowercase().ends_with(".csv") { "csv".to_string() } else { "txt".to_string() }
+    });
+    let (content, count) = sessions.with_session(&session_id, |run| match format.to_lowercase().as_str() {
+        "txt" => {
+            let lines = log_lines(run, None, None);
+            Ok((lines.join("\n") + "\n", lines.len()))
+        }
+        "csv" => Ok((log_csv(run), run.frames.iter().map(|f| f.events.len()).sum())),
+        other => Err(AppError::ValidationError(format!("Unknown log format '{}'; use txt or csv", other))),
+    })??;
+    std::fs::write(&path, content)?;
+    Ok(count)
+}
@@ -0,0 +1,149 @@
+use crate::errors::{AppError, Result};
+use crate::models::{CustomerConfig, DiagnosticSeverity, FeasibilityIssue, FeasibilityReport, SeatConfig};
+use crate::parser;
+use crate::simulation::{bar_runs, baby_chair_limit, merge, seat_capacity, wheelchair_limit};
+
+fn issue(severity: DiagnosticSeverity, code: &str, family_ids: Vec<u32>, time: Option<u64>, message: String) -> FeasibilityIssue {
+    FeasibilityIssue { severity, code: code.to_string(), family_ids, time, message }
+}
+
+// Largest party any seating option can hold: one table, a run of bar seats or, with
+// merge_tables, a group of adjacent tables pushed together
+pub fn max_mergeable_capacity(seats: &[SeatConfig], merge_tables: bool) -> u32 {
+    let largest_table = seats.iter()
+        .filter(|s| s.type_ != "SINGLE")
+        .map(|s| seat_capacity(&s.type_))
+        .max()
+        .unwrap_or(0);
+    let bar_seats = bar_runs(seats, |s| s).iter().map(|run| run.len() as u32).max().unwrap_or(0);
+    let merged = if merge_tables { merge::largest_merged_capacity(seats, false) } else { 0 };
+    largest_table.max(bar_seats).max(merged)
+}
+
+// Largest party like this one the layout can hold. Wheelchair users never sit at the
+// bar, so theirs is an accessible table or a table group that includes one.
+pub fn party_capacity(customer: &CustomerConfig, seats: &[SeatConfig], merge_tables: bool) -> u32 {
+    if customer.wheelchair_count == 0 {
+        return max_mergeable_capacity(seats, merge_tables);
+    }
+    let table = seats.iter()
+        .filter(|s| wheelchair_limit(s) > 0)
+        .map(|s| seat_capacity(&s.type_))
+        .max()
+        .unwrap_or(0);
+    let merged = if merge_tables { merge::largest_merged_capacity(seats, true) } else { 0 };
+    table.max(merged)
+}
+
+// Why a party can never be seated with this layout and resource pool, if it can't
+pub fn party_infeasibility(customer: &CustomerConfig, seats: &[SeatConfig], baby_chairs: i32, wheelchairs: i32, merge_tables: bool) -> Option<(&'static str, String)> {
+    if customer.wheelchair_count > 0 {
+        if !seats.iter().any(|s| s.is_wheelchair_accessible && s.type_ != "SINGLE") {
+            return Some(("no-accessible-table", format!("Family {} needs a wheelchair-accessible table but the layout has none", customer.family_id)));
+        }
+        if customer.wheelchair_count as i32 > wheelchairs {
+            return Some(("wheelchair-pool", format!("Family {} needs {} wheelchair spaces but the pool has {}", customer.family_id, customer.wheelchair_count, wheelchairs)));
+        }
+        // One table or, with merge_tables, accessible tables together
+        let table = seats.iter().map(wheelchair_limit).max().unwrap_or(0);
+        let merged = if merge_tables { seats.iter().map(wheelchair_limit).sum() } else { 0 };
+        let room = table.max(merged);
+        if customer.wheelchair_count > room {
+            return Some(("no-wheelchair-room", format!("Family {} needs {} wheelchair spaces but no table can take more than {}", customer.family_id, customer.wheelchair_count, room)));
+        }
+    }
+    let capacity = party_capacity(customer, seats, merge_tables);
+    if customer.party_size > capacity {
+        return Some(("party-too-large", format!("Family {} has {} people but the largest table, bar run or table group it can use seats {}", customer.family_id, customer.party_size, capacity)));
+    }
+    if customer.baby_chair_count > 0 {
+        // One table, a whole bar run or, with merge_tables, any tables together
+        let bar: u32 = bar_runs(seats, |s| s).iter()
+            .map(|run| run.iter().map(|s| baby_chair_limit(s)).sum())
+            .max()
+            .unwrap_or(0);
+        let table = seats.iter().filter(|s| s.type_ != "SINGLE").map(baby_chair_limit).max().unwrap_or(0);
+        let merged = if merge_tables { seats.iter().filter(|s| s.type_ != "SINGLE").map(baby_chair_limit).sum() } else { 0 };
+        let room = bar.max(table).max(merged);
+        if customer.baby_chair_count > room {
+            return Some(("no-baby-chair-seat", format!("Family {} needs {} baby chairs but no seat can take more than {}", customer.family_id, customer.baby_chair_count, room)));
+        }
+    }
+    if customer.baby_chair_count as i32 > baby_chairs {
+        return Some(("baby-chair-pool", format!("Family {} needs {} baby chairs but the pool has {}", customer.family_id, customer.baby_chair_count, baby_chairs)));
+    }
+    None
+}
+
+// Highest number of overlapping stays (and when it first happens), assuming every party
+// is seated on arrival. `weight` is how much of the resource a party holds.
+fn peak_demand(customers: &[CustomerConfig], weight: impl Fn(&CustomerConfig) -> u32) -> (u32, u64, Vec<u32>) {
+    let mut changes: Vec<(u64, i64)> = Vec::new();
+    for c in customers.iter().filter(|c| weight(c) > 0) {
+        changes.push((c.arrival_time, weight(c) as i64));
+        changes.push((c.arrival_time + c.est_dining_time.max(1), -(weight(c) as i64)));
+    }
+    // Departures before arrivals at the same instant
+    changes.sort_by_key(|&(t, delta)| (t, delta));
+
+    let mut current = 0i64;
+    let (mut peak, mut peak_time) = (0i64, 0u64);
+    for (t, delta) in changes {
+        current += delta;
+        if current > peak {
+            peak = current;
+            peak_time = t;
+        }
+    }
+
+    let present = customers.iter()
+        .filter(|c| weight(c) > 0 && c.arrival_time <= peak_time && peak_time < c.arrival_time + c.est_dining_time.max(1))
+        .map(|c| c.family_id)
+        .collect();
+    (peak as u32, peak_time, present)
+}
+
+pub fn check(customers: &[CustomerConfig], seats: &[SeatConfig], baby_chairs: i32, wheelchairs: i32, merge_tables: bool) -> FeasibilityReport {
+    let mut issues = Vec::new();
+
+    for customer in customers {
+        if let Some((code, message)) = party_infeasibility(customer, seats, baby_chairs, wheelchairs, merge_tables) {
+            issues.push(issue(DiagnosticSeverity::Error, code, vec![customer.family_id], Some(customer.arrival_time), message));
+        }
+    }
+
+    // Contention that forces waiting even if nobody is ever delayed
+    let accessible_tables = seats.iter().filter(|s| s.is_wheelchair_accessible && s.type_ != "SINGLE").count() as u32;
+    let (peak, t, families) = peak_demand(customers, |c| u32::from(c.wheelchair_count > 0));
+    if peak > accessible_tables {
+        issues.push(issue(DiagnosticSeverity::Warning, "accessible-table-contention", families, Some(t),
+            format!("{} wheelchair parties would dine at t={} but there are only {} accessible tables", peak, t, accessible_tables)));
+    }
+    let (peak, t, families) = peak_demand(customers, |c| c.wheelchair_count);
+    if peak as i32 > wheelchairs {
+        issues.push(issue(DiagnosticSeverity::Warning, "wheelchair-contention", families, Some(t),
+            format!("{} wheelchair spaces needed at t={} but the pool has {}", peak, t, wheelchairs)));
+    }
+    let (peak, t, families) = peak_demand(customers, |c| c.baby_chair_count);
+    if peak as i32 > baby_chairs {
+        issues.push(issue(DiagnosticSeverity::Warning, "baby-chair-contention", families, Some(t),
+            format!("{} baby chairs needed at t={} but the pool has {}", peak, t, baby_chairs)));
+    }
+
+    FeasibilityReport {
+        feasible: !issues.iter().any(|i| i.severity == DiagnosticSeverity::Error),
+        issues,
+    }
+}
+
+// Flag demands the layout can never meet before starting a run.
+// Errors are parties that would wait forever; warnings are guaranteed contention.
+// Pass merge_tables when the run will push adjacent tables together.
+#[tauri::command]
+pub fn check_feasibility(csv_content: String, seat_config_json: String, baby_chairs: i32, wheelchairs: i32, merge_tables: Option<bool>) -> Result<FeasibilityReport> {
+    let customers = parser::parse_customers(&csv_content)
+        .map_err(|e| AppError::CsvParseError(e.to_string()))?;
+    let seats: Vec<SeatConfig> = serde_json::from_str(&seat_config_json)
+        .map_err(|e| AppError::JsonParseError(e.to_string()))?;
+    Ok(check(&customers, &seats, baby_chairs, wheelchairs, merge_tables.unwrap_or(false)))
+}
@@ -0,0 +1,318 @@
+use crate::diagnostics::CSV_COLUMNS;
+use crate::errors::{AppError, Result};
+use crate::hashing::KeyedHasher;
+use crate::bookings;
+use crate::models::{CustomerConfig, DiningDistribution, DiningTimeBand, DiningTimeModel, GeneratedRoster, GeneratorConfig, RngDraw, RosterRepair, RosterRepairResult};
+use crate::parser;
+use crate::rng::{self, SimRng};
+
+// Defaults for generate_customers when no config is given
+const PARTY_SIZE_WEIGHTS: [f64; 6] = [30.0, 30.0, 15.0, 15.0, 5.0, 5.0];
+const BABY_PROBABILITY: f64 = 0.15;
+const WHEELCHAIR_PROBABILITY: f64 = 0.05;
+const MIN_DINING_TIME: u64 = 30;
+const MAX_DINING_TIME: u64 = 90;
+
+// Party composition rules: at least one person, baby chairs and wheelchairs never exceed
+// the party, and a wheelchair party has at least one adult (babies don't count, and
+// every wheelchair user is an adult). Returns the fixed (party, babies, wheelchairs)
+// and a note per rule that had to be applied.
+pub fn enforce_composition(party_size: u32, babies: u32, wheelchairs: u32) -> ((u32, u32, u32), Vec<String>) {
+    let mut notes = Vec::new();
+
+    let party = party_size.max(1);
+    if party != party_size {
+        notes.push("party size raised to 1".to_string());
+    }
+
+    let wheel = wheelchairs.min(party);
+    if wheel != wheelchairs {
+        notes.push(format!("wheelchairs {} capped at party size {}", wheelchairs, party));
+    }
+
+    let mut baby = babies.min(party);
+    if baby != babies {
+        notes.push(format!("baby chairs {} capped at party size {}", babies, party));
+    }
+    if wheel > 0 {
+        let max_babies = party - wheel.max(1);
+        if baby > max_babies {
+            notes.push(format!("baby chairs reduced to {} so the wheelchair party keeps an adult", max_babies));
+            baby = max_babies;
+        }
+    }
+
+    ((party, baby, wheel), notes)
+}
+
+fn default_config(count: u32, max_arrival_time: u64) -> GeneratorConfig {
+    GeneratorConfig {
+        count,
+        max_arrival_time,
+        arrival_rates: Vec::new(),
+        bucket_minutes: 0,
+        party_size_weights: PARTY_SIZE_WEIGHTS.to_vec(),
+        baby_probability: BABY_PROBABILITY,
+        wheelchair_probability: WHEELCHAIR_PROBABILITY,
+        dining_time: DiningTimeModel {
+            distribution: DiningDistribution::Uniform,
+            mean: (MIN_DINING_TIME + MAX_DINING_TIME) as f64 / 2.0,
+            std_dev: 0.0,
+            min: MIN_DINING_TIME,
+            max: MAX_DINING_TIME,
+        },
+        seed: None,
+        dining_time_bands: Vec::new(),
+    }
+}
+
+pub(crate) fn validate_config(config: &GeneratorConfig) -> Result<()> {
+    let invalid = |msg: &str| Err(AppError::ValidationError(format!("Invalid generator config: {}", msg)));
+    let usable = |weights: &[f64]| weights.iter().all(|w| w.is_finite() && *w >= 0.0) && weights.iter().sum::<f64>() > 0.0;
+
+    if !usable(&config.party_size_weights) {
+        return invalid("party size weights must be non-negative with a positive total");
+    }
+    if !config.arrival_rates.is_empty() && (!usable(&config.arrival_rates) || config.bucket_minutes == 0) {
+        return invalid("arrival rates need a positive total and a positive bucket length");
+    }
+    for (name, p) in [("baby", config.baby_probability), ("wheelchair", config.wheelchair_probability)] {
+        if !(0.0..=1.0).contains(&p) {
+            return invalid(&format!("{} probability {} is outside 0..1", name, p));
+        }
+    }
+    let dining = &config.dining_time;
+    if dining.min > dining.max || !dining.mean.is_finite() || !dining.std_dev.is_finite() || dining.std_dev < 0.0 {
+        return invalid("dining time needs min <= max and a finite, non-negative spread");
+    }
+    validate_dining_bands(&config.dining_time_bands)
+}
+
+// Bands need a positive multiplier and must not overlap, so every arrival has one factor
+pub(crate) fn validate_dining_bands(bands: &[DiningTimeBand]) -> Result<()> {
+    let label = |b: &DiningTimeBand| b.name.clone().unwrap_or_else(|| format!("{}..{}", b.from, b.to));
+    for (i, band) in bands.iter().enumerate() {
+        if band.from >= band.to || !band.multiplier.is_finite() || band.multiplier <= 0.0 {
+            return Err(AppError::ValidationError(format!("Dining time band {} needs from < to and a positive multiplier", label(band))));
+        }
+        if let Some(other) = bands[..i].iter().find(|o| o.from < band.to && band.from < o.to) {
+            return Err(AppError::ValidationError(format!("Dining time bands {} and {} overlap", label(other), label(band))));
+        }
+    }
+    Ok(())
+}
+
+// A stay of `minutes` scaled by the band the arrival falls in; at least a minute
+pub(crate) fn banded_dining_time(bands: &[DiningTimeBand], arrival_time: u64, minutes: u64) -> u64 {
+    match bands.iter().find(|b| (b.from..b.to).contains(&arrival_time)) {
+        Some(band) => ((minutes as f64 * band.multiplier).round() as u64).max(1),
+        None => minutes,
+    }
+}
+
+// Index drawn with probability proportional to its weight
+fn weighted_index(weights: &[f64], purpose: &str, rng: &mut SimRng) -> usize {
+    let mut pick = rng.below(purpose, weights.iter().sum::<f64>());
+    for (i, weight) in weights.iter().enumerate() {
+        if pick < *weight {
+            return i;
+        }
+        pick -= weight;
+    }
+    weights.iter().rposition(|w| *w > 0.0).unwrap_or(0)
+}
+
+// Standard normal draw (Box-Muller)
+fn standard_normal(rng: &mut SimRng) -> f64 {
+    let u1 = 1.0 - rng.unit("dining time"); // in (0, 1], so the log is finite
+    let u2 = rng.unit("dining time");
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+fn sample_arrival(config: &GeneratorConfig, rng: &mut SimRng) -> u64 {
+    if config.arrival_rates.is_empty() {
+        return rng.range("arrival time", 0..=config.max_arrival_time);
+    }
+    // Buckets past max_arrival_time can't be reached
+    let reachable = ((config.max_arrival_time / config.bucket_minutes + 1) as usize).min(config.arrival_rates.len());
+    let rates = &config.arrival_rates[..reachable];
+    if rates.iter().sum::<f64>() <= 0.0 {
+        return rng.range("arrival time", 0..=config.max_arrival_time);
+    }
+    let start = weighted_index(rates, "arrival bucket", rng) as u64 * config.bucket_minutes;
+    let end = (start + config.bucket_minutes - 1).min(config.max_arrival_time);
+    rng.range("arrival time", start..=end)
+}
+
+fn sample_dining_time(model: &DiningTimeModel, rng: &mut SimRng) -> u64 {
+    let minutes = match model.distribution {
+        DiningDistribution::Uniform => return rng.range("dining time", model.min..=model.max),
+        DiningDistribution::Normal => model.mean + model.std_dev * standard_normal(rng),
+        DiningDistribution::LogNormal => (model.mean + model.std_dev * standard_normal(rng)).exp(),
+    };
+    (minutes.round().max(0.0) as u64).clamp(model.min, model.max)
+}
+
+// Random roster sorted by arrival, with ids 1..=count. Without a config this uses the
+// built-in mix (uniform arrivals, 30-90 minute stays); count and max_arrival_time
+// override the config's. The seed is reported so the roster can be regenerated, and
+// with record_draws every random draw behind it comes back too.
+#[tauri::command]
+pub fn generate_customers(count: Option<u32>, max_arrival_time: Option<u64>, config: Option<GeneratorConfig>, record_draws: Option<bool>) -> Result<GeneratedRoster> {
+    let mut config = config.unwrap_or_else(|| default_config(0, 0));
+    config.count = count.unwrap_or(config.count);
+    config.max_arrival_time = max_arrival_time.unwrap_or(config.max_arrival_time);
+    validate_config(&config)?;
+
+    let mut rng = SimRng::new(config.seed.unwrap_or_else(rng::fresh_seed), record_draws.unwrap_or(false));
+    let customers = draw_roster(&config, &mut rng);
+    let csv_content = bookings::roster_csv(&customers);
+    Ok(GeneratedRoster { customers, csv_content, seed: rng.seed(), draws: rng.into_draws() })
+}
+
+// One roster drawn from a validated config; the same seed always gives the same roster
+pub(crate) fn sample_roster(config: &GeneratorConfig, seed: u64) -> Vec<CustomerConfig> {
+    draw_roster(config, &mut SimRng::new(seed, false))
+}
+
+// sample_roster, also handing back every draw it made, in order
+pub(crate) fn recorded_roster(config: &GeneratorConfig, seed: u64) -> (Vec<CustomerConfig>, Vec<RngDraw>) {
+    let mut rng = SimRng::new(seed, true);
+    let customers = draw_roster(config, &mut rng);
+    (customers, rng.into_draws())
+}
+
+fn draw_roster(config: &GeneratorConfig, rng: &mut SimRng) -> Vec<CustomerConfig> {
+
+    let mut arrivals: Vec<u64> = (0..config.count).map(|_| sample_arrival(config, rng)).collect();
+    arrivals.sort_unstable();
+
+    arrivals.into_iter().enumerate().map(|(i, arrival_time)| {
+        let party = weighted_index(&config.party_size_weights, "party size", rng) as u32 + 1;
+        let babies = if party > 1 && rng.chance("has babies", config.baby_probability) { rng.count("baby chairs", 1..=party - 1) } else { 0 };
+        let wheelchairs = if rng.chance("wheelchair", config.wheelchair_probability) { 1 } else { 0 };
+        let ((party_size, baby_chair_count, wheelchair_count), _) = enforce_composition(party, babies, wheelchairs);
+
+        let id = i as u32 + 1;
+        CustomerConfig {
+            id: id.into(),
+            family_id: id,
+            arrival_time,
+            type_: parser::customer_type(party_size, baby_chair_count, wheelchair_count),
+            party_size,
+            baby_chair_count,
+            wheelchair_count,
+            est_dining_time: banded_dining_time(&config.dining_time_bands, arrival_time, sample_dining_time(&config.dining_time, rng)),
+            conveyor_preference: 0.0,
+            dietary_flags: Vec::new(),
+            ordered_ahead: false,
+            extra: Default::default(),
+            max_wait_time: None,
+            priority: 0,
+        }
+    }).collect()
+}
+
+// Numeric value of a count column as the parser reads it ("true" counts as 1)
+fn count_field(text: &str) -> Option<u32> {
+    match text.trim().to_lowercase().as_str() {
+        "true" => Some(1),
+        "false" | "" => Some(0),
+        other => other.parse().ok(),
+    }
+}
+
+// Fix party composition violations in a hand-written roster CSV. Only the party size,
+// baby chair, wheelchair and type columns of offending rows are rewritten; everything
+// else (including extra columns) is kept as is.
+#[tauri::command]
+pub fn repair_roster(csv_content: String) -> Result<RosterRepairResult> {
+    let mut repairs = Vec::new();
+    let mut lines = Vec::new();
+
+    for (i, line) in csv_content.lines().enumerate() {
+        let is_header = i == 0 && parser::is_header_line(line);
+        let mut fields: Vec<String> = line.split(',').map(str::to_string).collect();
+
+        let family_id = fields[0].trim().parse::<i64>().unwrap_or(0);
+        if is_header || line.trim().is_empty() || family_id == 0 || fields.len() < 4 {
+            lines.push(line.to_string());
+            continue;
+        }
+
+        let party = fields[3].trim().parse::<u32>().unwrap_or(1);
+        let babies = fields.get(4).and_then(|f| count_field(f)).unwrap_or(0);
+        let wheelchairs = fields.get(5).and_then(|f| count_field(f)).unwrap_or(0);
+        let ((party, babies, wheelchairs), notes) = enforce_composition(party, babies, wheelchairs);
+
+        if !notes.is_empty() {
+            if fields.len() < 6 {
+                fields.resize(6, String::new());
+            }
+            fields[2] = parser::customer_type(party, babies, wheelchairs);
+            fields[3] = party.to_string();
+            fields[4] = babies.to_string();
+            fields[5] = wheelchairs.to_string();
+            repairs.extend(notes.into_iter().map(|message| RosterRepair { line: i as u32, family_id, message }));
+        }
+        lines.push(fields.join(","));
+    }
+
+    if lines.is_empty() {
+        return Err(AppError::CsvParseError(format!("Empty roster; expected columns {}", CSV_COLUMNS.join(","))));
+    }
+
+    let mut repaired = lines.join("\n");
+    if csv_content.ends_with('\n') {
+        repaired.push('\n');
+    }
+    Ok(RosterRepairResult { csv_content: repaired, repairs })
+}
+
+// Pseudonym for a personal value, e.g. "name-3fa2c1": within one export the same value in
+// the same column always maps to the same pseudonym, so repeat guests stay recognisable.
+// The hash is keyed afresh for every export, so values can't be guessed back from it.
+fn pseudonym(key: &KeyedHasher, column: &str, value: &str) -> String {
+    let hash = key.hash(column.bytes().chain([0]).chain(value.trim().to_lowercase().bytes()));
+    format!("{}-{:06x}", column.to_lowercase().replace(' ', "_"), hash & 0xff_ffff)
+}
+
+// Replace every passthrough column (name, phone, notes, ...) of a roster CSV with a
+// pseudonym so real data can be shared for demos. The columns the simulation reads
+// (ids, arrival and dining times, party composition, family, patience, priority) are
+// kept as is, wherever the header puts them.
+#[tauri::command]
+pub fn anonymize_roster(csv_content: String) -> Result<String> {
+    let key = KeyedHasher::new();
+    let mut header: Vec<String> = Vec::new();
+    let mut lines = Vec::new();
+
+    for (i, line) in csv_content.lines().enumerate() {
+        if i == 0 && parser::is_header_line(line) {
+            header = line.split(',').map(|h| h.trim().to_string()).collect();
+            lines.push(line.to_string());
+            continue;
+        }
+        let fields: Vec<String> = line.split(',')
+            .enumerate()
+            .map(|(idx, field)| {
+                if !parser::is_passthrough_column(&header, idx) || field.trim().is_empty() {
+                    field.to_string()
+                } else {
+                    pseudonym(&key, &parser::extra_column_name(&header, idx), field)
+                }
+            })
+            .collect();
+        lines.push(fields.join(","));
+    }
+
+    if lines.is_empty() {
+        return Err(AppError::CsvParseError(format!("Empty roster; expected columns {}", CSV_COLUMNS.join(","))));
+    }
+
+    let mut anonymized = lines.join("\n");
+    if csv_content.ends_with('\n') {
+        anonymized.push('\n');
+    }
+    Ok(anonymized)
+}
@@ -0,0 +1,35 @@
+use std::hash::{BuildHasher, Hasher, RandomState};
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+// FNV-1a: the same bytes give the same hash on every run and platform, which is what
+// fingerprints need. Anyone can recompute it, so it must not stand in for personal data.
+pub fn fnv1a(bytes: impl IntoIterator<Item = u8>) -> u64 {
+    let mut hash = FNV_OFFSET;
+    for byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+// SipHash under a random key drawn when the hasher is made and never written out: equal
+// inputs hash alike for as long as it lives, but nobody can hash guesses to match them.
+pub struct KeyedHasher {
+    key: RandomState,
+}
+
+impl KeyedHasher {
+    pub fn new() -> Self {
+        KeyedHasher { key: RandomState::new() }
+    }
+
+    pub fn hash(&self, bytes: impl IntoIterator<Item = u8>) -> u64 {
+        let mut hasher = self.key.build_hasher();
+        for byte in bytes {
+            hasher.write_u8(byte);
+        }
+        hasher.finish()
+    }
+}
@@ -0,0 +1,185 @@
+use crate::errors::{AppError, Result};
+use crate::models::{EngineHealthReport, SyncTraceEntry, WorkerHealth};
+use crate::session::SessionStore;
+use std::collections::BTreeMap;
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{State, Window};
+
+// Synchronization counters collected inside the monitor when instrumentation is on.
+// Everything is recorded while holding the monitor lock, so plain fields suffice.
+#[derive(Clone, Default)]
+pub(crate) struct Instrumentation {
+    enabled: bool,
+    workers: BTreeMap<u32, WorkerHealth>,
+    notify_calls: u64,
+    // Teaching mode: every lock acquisition, condvar wait and notification in order
+    trace: Option<Vec<SyncTraceEntry>>,
+}
+
+// Numeric id of the current worker thread, as shown in the engine logs
+fn worker_id() -> String {
+    format!("{:?}", thread::current().id())
+        .replace("ThreadId(", "")
+        .replace(")", "")
+}
+
+impl Instrumentation {
+    pub(crate) fn new(enabled: bool, trace: bool) -> Self {
+        Instrumentation { enabled, trace: trace.then(Vec::new), ..Default::default() }
+    }
+
+    fn trace(&mut self, virtual_time: u64, family_id: u32, operation: &str, detail: String) {
+        if let Some(trace) = &mut self.trace {
+            trace.push(SyncTraceEntry {
+                sequence: trace.len(),
+                virtual_time,
+                worker: worker_id(),
+                family_id,
+                operation: operation.to_string(),
+                detail,
+            });
+        }
+    }
+
+    pub(crate) fn take_trace(&mut self) -> Vec<SyncTraceEntry> {
+        self.trace.take().unwrap_or_default()
+    }
+
+    fn worker(&mut self, family_id: u32) -> &mut WorkerHealth {
+        self.workers.entry(family_id).or_insert_with(|| WorkerHealth { family_id, ..Default::default() })
+    }
+
+    fn lock_acquired(&mut self, family_id: u32, waited: Duration) {
+        if !self.enabled { return; }
+        let micros = waited.as_micros() as u64;
+        let w = self.worker(family_id);
+        w.lock_acquisitions += 1;
+        w.lock_wait_us += micros;
+        w.max_lock_wait_us = w.max_lock_wait_us.max(micros);
+    }
+
+    fn woke(&mut self, family_id: u32, timed_out: bool) {
+        if !self.enabled { return; }
+        let w = self.worker(family_id);
+        w.condvar_waits += 1;
+        if timed_out { w.timeouts += 1 } else { w.wakeups += 1 }
+    }
+
+    // A notification woke the worker but it still could not be seated
+    pub(crate) fn spurious_wakeup(&mut self, family_id: u32) {
+        if !self.enabled { return; }
+        self.worker(family_id).spurious_wakeups += 1;
+    }
+
+    pub(crate) fn report(&self) -> Option<EngineHealthReport> {
+        if !self.enabled { return None; }
+        let workers: Vec<WorkerHealth> = self.workers.values().cloned().collect();
+        Some(EngineHealthReport {
+            total_lock_wait_us: workers.iter().map(|w| w.lock_wait_us).sum(),
+            max_lock_wait_us: workers.iter().map(|w| w.max_lock_wait_us).max().unwrap_or(0),
+            condvar_waits: workers.iter().map(|w| w.condvar_waits).sum(),
+            wakeups: workers.iter().map(|w| w.wakeups).sum(),
+            spurious_wakeups: workers.iter().map(|w| w.spurious_wakeups).sum(),
+            timeouts: workers.iter().map(|w| w.timeouts).sum(),
+            notify_calls: self.notify_calls,
+            workers,
+        })
+    }
+}
+
+// Anything guarded by the monitor that carries instrumentation
+pub(crate) trait Instrumented {
+    fn instrumentation(&mut self) -> &mut Instrumentation;
+    // Simulation clock, used to timestamp trace entries
+    fn virtual_time(&self) -> u64;
+}
+
+// What the helpers below record, for monitors that aren't a std Mutex and Condvar (the
+// tasks engine's tokio Mutex and Notify); `res` is the state the monitor guards
+pub(crate) fn record_lock<T: Instrumented>(res: &mut T, family_id: u32, waited: Duration) {
+    let now = res.virtual_time();
+    let instrumentation = res.instrumentation();
+    instrumentation.lock_acquired(family_id, waited);
+    instrumentation.trace(now, family_id, "LOCK", format!("acquired after {}us", waited.as_micros()));
+}
+
+pub(crate) fn record_wait<T: Instrumented>(res: &mut T, family_id: u32) {
+    let now = res.virtual_time();
+    res.instrumentation().trace(now, family_id, "WAIT", "released lock, waiting on condvar".to_string());
+}
+
+pub(crate) fn record_wake<T: Instrumented>(res: &mut T, family_id: u32, timed_out: bool) {
+    let now = res.virtual_time();
+    let instrumentation = res.instrumentation();
+    instrumentation.woke(family_id, timed_out);
+    if timed_out {
+        instrumentation.trace(now, family_id, "TIMEOUT", "poll timeout, lock re-acquired".to_string());
+    } else {
+        instrumentation.trace(now, family_id, "WAKE", "notified, lock re-acquired".to_string());
+    }
+}
+
+pub(crate) fn record_notify<T: Instrumented>(res: &mut T, family_id: u32) {
+    let now = res.virtual_time();
+    let instrumentation = res.instrumentation();
+    if instrumentation.enabled {
+        instrumentation.notify_calls += 1;
+    }
+    instrumentation.trace(now, family_id, "NOTIFY_ALL", "waking all waiters".to_string());
+}
+
+// Lock the monitor, recording how long the worker waited for it
+pub(crate) fn lock_timed<T: Instrumented>(lock: &Mutex<T>, family_id: u32) -> MutexGuard<'_, T> {
+    let started = Instant::now();
+    let mut guard = lock.lock().unwrap();
+    record_lock(&mut *guard, family_id, started.elapsed());
+    guard
+}
+
+// Bounded condvar wait; returns whether it timed out rather than being notified
+pub(crate) fn wait_timed<'a, T: Instrumented>(cvar: &Condvar, mut guard: MutexGuard<'a, T>, family_id: u32, timeout: Duration) -> (MutexGuard<'a, T>, bool) {
+    record_wait(&mut *guard, family_id);
+    let (mut guard, result) = cvar.wait_timeout(guard, timeout).unwrap();
+    record_wake(&mut *guard, family_id, result.timed_out());
+    (guard, result.timed_out())
+}
+
+pub(crate) fn notify_all_counted<T: Instrumented>(cvar: &Condvar, guard: &mut MutexGuard<'_, T>, family_id: u32) {
+    record_notify(&mut **guard, family_id);
+    cvar.notify_all();
+}
+
+fn trace_text(entries: &[SyncTraceEntry]) -> String {
+    let mut text = String::from("# seq | virtual time | worker | family | operation | detail\n");
+    for e in entries {
+        text.push_str(&format!("[{}] [t={}] [worker {}] family {} {} {}\n",
+            e.sequence, e.virtual_time, e.worker, e.family_id, e.operation, e.detail));
+    }
+    text
+}
+
+#[tauri::command]
+pub fn get_engine_health(session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<EngineHealthReport> {
+    let session_id = sessions.resolve(session_id, &window);
+    sessions.with_session(&session_id, |run| run.engine_health.clone())?
+        .ok_or_else(|| AppError::SessionError("Run was not instrumented; start it with instrument: true".to_string()))
+}
+
+#[tauri::command]
+pub fn get_sync_trace(session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<Vec<SyncTraceEntry>> {
+    let session_id = sessions.resolve(session_id, &window);
+    sessions.with_session(&session_id, |run| run.sync_trace.clone())
+}
+
+// Plain-text trace for download
+#[tauri::command]
+pub fn export_sync_trace(session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<String> {
+    let session_id = sessions.resolve(session_id, &window);
+    let entries = sessions.with_session(&session_id, |run| run.sync_trace.clone())?;
+    if entries.is_empty() {
+        return Err(AppError::SessionError("Run has no synchronization trace; start it with traceSync: true".to_string()));
+    }
+    Ok(trace_text(&entries))
+}
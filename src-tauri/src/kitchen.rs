@@ -0,0 +1,113 @@
+use crate::couriers::CourierDesk;
+use crate::models::{CourierConfig, CourierPickup, CustomerConfig, KitchenConfig, KitchenDelay, KitchenReport};
+use crate::stations::book_slot;
+
+// Minimal kitchen model: standard orders are covered by est_dining_time, while
+// orders with dietary flags go through a small dedicated station. Queueing there
+// (plus the extra prep time) delays the food and stretches the party's stay.
+// Parties that ordered ahead skip the kitchen entirely: their food is ready on seating.
+// With a line capacity set, standard orders also queue for the line, together with
+// courier orders (see couriers.rs) in the order they were fired.
+#[derive(Clone)]
+pub struct KitchenModel {
+    config: KitchenConfig,
+    slot_free_at: Vec<u64>,
+    line: Vec<Vec<(u64, u64)>>, // per line slot: booked [start, end) orders
+    orders: Vec<KitchenDelay>,
+    ordered_ahead_parties: usize,
+    time_saved: u64,
+    line_delayed_orders: usize,
+    total_line_delay: u64,
+    couriers: Option<CourierDesk>,
+}
+
+impl KitchenModel {
+    pub fn new(config: KitchenConfig, couriers: &CourierConfig) -> Self {
+        let slots = config.dietary_station_capacity.max(1) as usize;
+        let couriers = (!couriers.orders.is_empty()).then(|| CourierDesk::new(couriers));
+        KitchenModel {
+            slot_free_at: vec![0; slots],
+            line: vec![Vec::new(); config.line_capacity as usize],
+            config,
+            orders: Vec::new(),
+            ordered_ahead_parties: 0,
+            time_saved: 0,
+            line_delayed_orders: 0,
+            total_line_delay: 0,
+            couriers,
+        }
+    }
+
+    // Put the courier orders placed up to `until` on the line; returns the ones fired now
+    pub fn fire_couriers(&mut self, until: u64) -> &[CourierPickup] {
+        let Some(desk) = &mut self.couriers else { return &[] };
+        let fired = desk.fire(&mut self.line, self.config.standard_prep_time, until);
+        let pickups = desk.pickups();
+        &pickups[pickups.len() - fired..]
+    }
+
+    // How long a party seated at `order_time` occupies its seats
+    pub fn dining_time(&mut self, order_time: u64, customer: &CustomerConfig) -> u64 {
+        if customer.ordered_ahead {
+            let dining = customer.est_dining_time.saturating_sub(self.config.standard_prep_time).max(1);
+            self.ordered_ahead_parties += 1;
+            self.time_saved += customer.est_dining_time - dining;
+            return dining;
+        }
+        customer.est_dining_time + self.fire_order(order_time, customer)
+    }
+
+    // Fire a party's order at `order_time` and return the extra time they wait for food
+    fn fire_order(&mut self, order_time: u64, customer: &CustomerConfig) -> u64 {
+        if customer.dietary_flags.is_empty() {
+            return self.fire_standard(order_time);
+        }
+
+        // Earliest free slot of the dedicated station
+        let (slot, free_at) = self.slot_free_at.iter().copied().enumerate()
+            .min_by_key(|(_, t)| *t)
+            .unwrap_or((0, 0));
+        let start = std::cmp::max(order_time, free_at);
+        let ready = start + self.config.dietary_prep_time;
+        self.slot_free_at[slot] = ready;
+
+        let added_delay = ready - order_time;
+        self.orders.push(KitchenDelay {
+            family_id: customer.family_id,
+            dietary_flags: customer.dietary_flags.clone(),
+            order_time,
+            queue_wait: start - order_time,
+            added_delay,
+        });
+        added_delay
+    }
+
+    // Standard prep is already in est_dining_time; only queueing for the line adds to it
+    fn fire_standard(&mut self, order_time: u64) -> u64 {
+        let start = book_slot(&mut self.line, order_time, self.config.standard_prep_time.max(1));
+        let queue_wait = start - order_time;
+        if queue_wait > 0 {
+            self.line_delayed_orders += 1;
+            self.total_line_delay += queue_wait;
+        }
+        queue_wait
+    }
+
+    pub fn report(&self) -> KitchenReport {
+        let total_added_delay: u64 = self.orders.iter().map(|o| o.added_delay).sum();
+        KitchenReport {
+            config: self.config.clone(),
+            flagged_orders: self.orders.len(),
+            total_added_delay,
+            avg_added_delay: if self.orders.is_empty() { 0.0 } else { total_added_delay as f64 / self.orders.len() as f64 },
+            max_added_delay: self.orders.iter().map(|o| o.added_delay).max().unwrap_or(0),
+            max_queue_wait: self.orders.iter().map(|o| o.queue_wait).max().unwrap_or(0),
+            orders: self.orders.clone(),
+            ordered_ahead_parties: self.ordered_ahead_parties,
+            total_time_saved: self.time_saved,
+            line_delayed_orders: self.line_delayed_orders,
+            total_line_delay: self.total_line_delay,
+            couriers: self.couriers.as_ref().map(CourierDesk::report),
+        }
+    }
+}
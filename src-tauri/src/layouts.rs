@@ -0,0 +1,223 @@
+use crate::access;
+use crate::diagnostics;
+use crate::errors::{AppError, Result};
+use crate::models::{Diagnostic, DiagnosticSeverity, SavedLayout, SeatConfig, SeatIdPatterns};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use tauri::{Manager, Window};
+
+// Folder under the app data dir holding one JSON file per named floor plan
+const LAYOUT_DIR: &str = "layouts";
+
+// generate_seat_layout grid: the bar along the top row, tables in rows below it,
+// TABLE_WIDTH cells apart and TABLE_ROW_GAP rows apart
+const DEFAULT_TABLES_PER_ROW: u32 = 4;
+const TABLE_WIDTH: f32 = 3.0;
+const TABLE_ROW_GAP: f32 = 2.0;
+
+// Most ids one label pattern may produce, so a typo like S{1..100000} fails fast
+const MAX_PATTERN_IDS: usize = 1000;
+
+fn layout_dir(window: &Window) -> Result<PathBuf> {
+    let dir = window.path().app_data_dir()
+        .map_err(|e| AppError::IoError(std::io::Error::other(e.to_string())))?
+        .join(LAYOUT_DIR);
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+// Names double as file names, so they are kept to letters, digits, spaces, '-' and '_'
+fn layout_path(window: &Window, name: &str) -> Result<PathBuf> {
+    let name = name.trim();
+    if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_')) {
+        return Err(AppError::ValidationError(format!("'{}' is not a valid layout name; use letters, digits, spaces, '-' and '_'", name)));
+    }
+    Ok(layout_dir(window)?.join(format!("{}.json", name)))
+}
+
+// Store a floor plan under `name`, replacing any earlier one. Layouts with errors are
+// refused; warnings (e.g. no accessible table) are saved along and returned.
+#[tauri::command]
+pub fn save_seat_layout(name: String, seat_config_json: String, window: Window) -> Result<Vec<Diagnostic>> {
+    access::require_host(&window, "save seat layouts")?;
+    let path = layout_path(&window, &name)?;
+    let diagnostics = diagnostics::validate_seat_layout_json(&seat_config_json);
+    let errors: Vec<&str> = diagnostics.iter()
+        .filter(|d| d.severity == DiagnosticSeverity::Error)
+        .map(|d| d.message.as_str())
+        .collect();
+    if !errors.is_empty() {
+        return Err(AppError::ValidationError(format!("Layout '{}' was not saved: {}", name.trim(), errors.join("; "))));
+    }
+    std::fs::write(path, seat_config_json)?;
+    Ok(diagnostics)
+}
+
+// The seat layout JSON saved under `name`, ready for the simulation commands
+#[tauri::command]
+pub fn load_seat_layout(name: String, window: Window) -> Result<String> {
+    let path = layout_path(&window, &name)?;
+    std::fs::read_to_string(&path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => AppError::ValidationError(format!("No layout named '{}'", name.trim())),
+        _ => e.into(),
+    })
+}
+
+// Saved layouts by name, with their seat counts
+#[tauri::command]
+pub fn list_seat_layouts(window: Window) -> Result<Vec<SavedLayout>> {
+    let mut layouts = Vec::new();
+    for file in std::fs::read_dir(layout_dir(&window)?)? {
+        let path = file?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") { continue; }
+        let Some(name) = path.file_stem().and_then(|s| s.to_str()).map(str::to_string) else { continue };
+        let seats = std::fs::read_to_string(&path).ok()
+            .and_then(|json| serde_json::from_str::<Vec<serde_json::Value>>(&json).ok())
+            .map_or(0, |seats| seats.len());
+        layouts.push(SavedLayout { name, seats });
+    }
+    layouts.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(layouts)
+}
+
+#[tauri::command]
+pub fn delete_seat_layout(name: String, window: Window) -> Result<()> {
+    access::require_host(&window, "delete seat layouts")?;
+    std::fs::remove_file(layout_path(&window, &name)?)?;
+    Ok(())
+}
+
+// Values of one `{start..end}` range: numbers zero-padded to the wider end, so ids
+// sort in seat order (S{1..12} gives S01 ... S12), or single letters (A..D)
+fn expand_range(range: &str) -> Option<Vec<String>> {
+    let (start, end) = range.split_once("..")?;
+    let (start, end) = (start.trim(), end.trim());
+    if let (Ok(from), Ok(to)) = (start.parse::<u32>(), end.parse::<u32>()) {
+        let width = start.len().max(end.len());
+        return (from <= to).then(|| (from..=to).map(|n| format!("{:0width$}", n)).collect());
+    }
+    let mut from = start.chars();
+    let mut to = end.chars();
+    match (from.next(), from.next(), to.next(), to.next()) {
+        (Some(a), None, Some(b), None) if a <= b && (a.is_ascii_uppercase() && b.is_ascii_uppercase() || a.is_ascii_lowercase() && b.is_ascii_lowercase()) => {
+            Some((a..=b).map(String::from).collect())
+        }
+        _ => None,
+    }
+}
+
+// Ids from a pattern like `S{01..12}` or `T4-{A..D}`: text outside braces is kept and
+// each `{start..end}` range is expanded, the last range varying fastest
+// (`T{1..2}{A..B}` gives T1A, T1B, T2A, T2B)
+pub fn expand_id_pattern(pattern: &str) -> Result<Vec<String>> {
+    let invalid = |why: &str| AppError::ValidationError(format!("Invalid seat id pattern '{}': {}", pattern, why));
+    let mut ids = vec![String::new()];
+    let mut rest = pattern.trim();
+    while let Some(open) = rest.find('{') {
+        let close = rest[open..].find('}').map(|i| open + i).ok_or_else(|| invalid("unclosed '{'"))?;
+        let text = &rest[..open];
+        if text.contains('}') { return Err(invalid("unmatched '}'")); }
+        let values = expand_range(&rest[open + 1..close])
+            .ok_or_else(|| invalid("ranges look like {01..12} or {A..D}, counting upwards"))?;
+        if ids.len() * values.len() > MAX_PATTERN_IDS {
+            return Err(invalid(&format!("more than {} ids", MAX_PATTERN_IDS)));
+        }
+        ids = ids.iter().flat_map(|id| values.iter().map(move |v| format!("{}{}{}", id, text, v))).collect();
+        rest = &rest[close + 1..];
+    }
+    if rest.contains('}') { return Err(invalid("unmatched '}'")); }
+    ids.iter_mut().for_each(|id| id.push_str(rest));
+    if ids.iter().any(String::is_empty) { return Err(invalid("it is empty")); }
+    Ok(ids)
+}
+
+// Ids for the layout editor's "add seats" from a pattern, refused if any is already
+// taken by `existing_ids` (the seats on the floor plan)
+#[tauri::command]
+pub fn expand_seat_ids(pattern: String, existing_ids: Option<Vec<String>>) -> Result<Vec<String>> {
+    let ids = expand_id_pattern(&pattern)?;
+    let taken: HashSet<&String> = existing_ids.iter().flatten().collect();
+    let clashes: Vec<&str> = ids.iter().filter(|id| taken.contains(id)).map(String::as_str).collect();
+    if !clashes.is_empty() {
+        return Err(AppError::ValidationError(format!("Seat ids already in the layout: {}", clashes.join(", "))));
+    }
+    Ok(ids)
+}
+
+// `count` ids from an optional pattern, or the default `<prefix>01`, `<prefix>02`, ...
+fn layout_ids(pattern: Option<&str>, prefix: &str, count: u32) -> Result<Vec<String>> {
+    let Some(pattern) = pattern else {
+        return Ok((1..=count).map(|n| format!("{}{:02}", prefix, n)).collect());
+    };
+    let ids = expand_id_pattern(pattern)?;
+    if ids.len() != count as usize {
+        return Err(AppError::ValidationError(format!("Pattern '{}' gives {} ids for {} seats", pattern, ids.len(), count)));
+    }
+    Ok(ids)
+}
+
+fn seat(id: String, type_: &str, x: f32, y: f32, accessible: bool) -> SeatConfig {
+    SeatConfig {
+        id,
+        x: Some(x),
+        y: Some(y),
+        type_: type_.to_string(),
+        is_wheelchair_accessible: accessible,
+        label: None,
+        revenue_weight: None,
+        faces_conveyor: false,
+        adjacent_to: Vec::new(),
+        max_baby_chairs: None,
+        wheelchair_capacity: None,
+        attributes: HashMap::new(),
+        contiguity_group: None,
+        group_position: None,
+    }
+}
+
+// A standard floor plan: `bar_seats` bar seats (S01, S02, ...) in a row facing the
+// conveyor, then 4P tables (4P01, ...) and 6P tables (6P01, ...) in rows of
+// `tables_per_row`. The first `accessible_tables` tables (one by default) are wheelchair
+// accessible, and tables side by side in a row can be pushed together. `id_patterns`
+// replaces the default ids, e.g. bar `S{01..12}` or four-tops `T4-{A..D}`.
+#[tauri::command]
+pub fn generate_seat_layout(bar_seats: u32, four_tops: u32, six_tops: u32, accessible_tables: Option<u32>, tables_per_row: Option<u32>, id_patterns: Option<SeatIdPatterns>) -> Result<Vec<SeatConfig>> {
+    let tables = four_tops + six_tops;
+    if bar_seats + tables == 0 {
+        return Err(AppError::ValidationError("A layout needs at least one seat".to_string()));
+    }
+    let accessible_tables = accessible_tables.unwrap_or(1).min(tables);
+    let per_row = tables_per_row.unwrap_or(DEFAULT_TABLES_PER_ROW).max(1);
+    let patterns = id_patterns.unwrap_or_default();
+    let bar_ids = layout_ids(patterns.bar.as_deref(), "S", bar_seats)?;
+    let four_top_ids = layout_ids(patterns.four_top.as_deref(), "4P", four_tops)?;
+    let six_top_ids = layout_ids(patterns.six_top.as_deref(), "6P", six_tops)?;
+    let mut unique = HashSet::new();
+    if let Some(id) = bar_ids.iter().chain(&four_top_ids).chain(&six_top_ids).find(|id| !unique.insert(*id)) {
+        return Err(AppError::ValidationError(format!("Seat id '{}' would be used twice", id)));
+    }
+
+    // Bar seats stay in id order, which is the order contiguous bar seats are taken in
+    let mut seats: Vec<SeatConfig> = bar_ids.into_iter().enumerate()
+        .map(|(i, id)| SeatConfig { faces_conveyor: true, ..seat(id, "SINGLE", i as f32, 0.0, false) })
+        .collect();
+    let kinds = four_top_ids.into_iter().map(|id| ("4P", id)).chain(six_top_ids.into_iter().map(|id| ("6P", id)));
+    let first_row = if bar_seats > 0 { TABLE_ROW_GAP } else { 0.0 };
+    let mut previous: Option<String> = None;
+    for (n, (type_, id)) in kinds.enumerate() {
+        let (row, column) = (n as u32 / per_row, n as u32 % per_row);
+        let mut table = seat(
+            id,
+            type_,
+            column as f32 * TABLE_WIDTH,
+            first_row + row as f32 * TABLE_ROW_GAP,
+            (n as u32) < accessible_tables,
+        );
+        if column > 0 {
+            table.adjacent_to.extend(previous.take());
+        }
+        previous = Some(table.id.clone());
+        seats.push(table);
+    }
+    Ok(seats)
+}
@@ -0,0 +1,88 @@
+use crate::errors::Result;
+use crate::models::LedgerEntry;
+use crate::session::SessionStore;
+use std::collections::BTreeMap;
+use tauri::{State, Window};
+
+pub const ACQUIRE: &str = "ACQUIRE";
+pub const RELEASE: &str = "RELEASE";
+
+// Pool resources held by several parties at once
+pub const BABY_CHAIR_POOL: &str = "BABY_CHAIRS";
+pub const WHEELCHAIR_POOL: &str = "WHEELCHAIRS";
+
+// Shared resource pools (baby chairs, wheelchairs). Every change goes through
+// acquire/release and is written down, so the remaining counts in the logs and the
+// audit trail can never disagree.
+#[derive(Clone, Default)]
+pub(crate) struct ResourceLedger {
+    balances: BTreeMap<String, i32>,
+    entries: Vec<LedgerEntry>,
+}
+
+impl ResourceLedger {
+    pub(crate) fn new(pools: &[(&str, i32)]) -> Self {
+        ResourceLedger {
+            balances: pools.iter().map(|(pool, count)| (pool.to_string(), *count)).collect(),
+            entries: Vec::new(),
+        }
+    }
+
+    // Same balances without the history, for what-if allocation checks
+    pub(crate) fn balances_only(&self) -> Self {
+        ResourceLedger { balances: self.balances.clone(), entries: Vec::new() }
+    }
+
+    pub(crate) fn available(&self, pool: &str) -> i32 {
+        self.balances.get(pool).copied().unwrap_or(0)
+    }
+
+    pub(crate) fn acquire(&mut self, pool: &str, count: u32, family_id: u32, time: u64) {
+        self.record(pool, -(count as i32), ACQUIRE, family_id, time);
+    }
+
+    pub(crate) fn release(&mut self, pool: &str, count: u32, family_id: u32, time: u64) {
+        self.record(pool, count as i32, RELEASE, family_id, time);
+    }
+
+    fn record(&mut self, pool: &str, delta: i32, operation: &str, family_id: u32, time: u64) {
+        if delta == 0 { return; }
+        let balance = self.balances.entry(pool.to_string()).or_insert(0);
+        *balance += delta;
+        self.entries.push(LedgerEntry {
+            sequence: self.entries.len(),
+            time,
+            family_id,
+            resource: pool.to_string(),
+            operation: operation.to_string(),
+            count: delta.unsigned_abs(),
+            balance: *balance,
+        });
+    }
+
+    pub(crate) fn entries(&self) -> &[LedgerEntry] {
+        &self.entries
+    }
+
+    // Balances with this history put back; the history must be the one that led to them
+    pub(crate) fn with_entries(&self, entries: &[LedgerEntry]) -> Self {
+        ResourceLedger { balances: self.balances.clone(), entries: entries.to_vec() }
+    }
+
+    pub(crate) fn take_entries(&mut self) -> Vec<LedgerEntry> {
+        std::mem::take(&mut self.entries)
+    }
+}
+
+// The run's acquisitions and releases, optionally narrowed to one resource or family
+#[tauri::command]
+pub fn get_resource_ledger(session_id: Option<String>, resource: Option<String>, family_id: Option<u32>, window: Window, sessions: State<'_, SessionStore>) -> Result<Vec<LedgerEntry>> {
+    let session_id = sessions.resolve(session_id, &window);
+    sessions.with_session(&session_id, |run| {
+        run.ledger.iter()
+            .filter(|e| resource.as_ref().is_none_or(|r| e.resource.eq_ignore_ascii_case(r)))
+            .filter(|e| family_id.is_none_or(|fid| e.family_id == fid))
+            .cloned()
+            .collect()
+    })
+}
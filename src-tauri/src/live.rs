@@ -0,0 +1,213 @@
+use crate::errors::{AppError, Result};
+use crate::models::{LiveDelta, LiveSnapshot};
+use crate::session::SessionStore;
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, State, Window};
+
+// Event live updates are pushed under, to the window watching the run
+pub const LIVE_EVENT: &str = "simulation://live";
+// Default cap on live updates per second; enough for smooth animation
+pub const DEFAULT_LIVE_EMIT_RATE: u32 = 30;
+
+// Latest published state of one run. The engine replaces the snapshot under a short
+// write lock after each change it makes while holding its own monitor; UI reads only
+// take the read lock, so they never touch the monitor or delay allocation decisions.
+// Every change also wakes the run's governor.
+pub(crate) type LiveView = Arc<Live>;
+
+pub(crate) struct Live {
+    snapshot: RwLock<LiveSnapshot>,
+    signal: Mutex<Signal>,
+    changed: Condvar,
+}
+
+// What the governor waits on: the version published last, and whether to stop early
+#[derive(Default)]
+struct Signal {
+    version: u64,
+    stopped: bool,
+}
+
+impl Live {
+    pub(crate) fn read(&self) -> LiveSnapshot {
+        self.snapshot.read().unwrap().clone()
+    }
+
+    fn changed(&self, version: u64) {
+        let mut signal = self.signal.lock().unwrap();
+        signal.version = signal.version.max(version);
+        self.changed.notify_all();
+    }
+
+    fn stop(&self) {
+        self.signal.lock().unwrap().stopped = true;
+        self.changed.notify_all();
+    }
+}
+
+#[derive(Default)]
+pub struct LiveStateStore {
+    views: Mutex<HashMap<String, LiveView>>,
+}
+
+impl LiveStateStore {
+    // Fresh view for a run that is about to start; replaces the previous run's view
+    pub(crate) fn begin(&self, session_id: &str) -> LiveView {
+        let view = Arc::new(Live {
+            snapshot: RwLock::new(LiveSnapshot {
+                session_id: session_id.to_string(),
+                running: true,
+                ..Default::default()
+            }),
+            signal: Mutex::default(),
+            changed: Condvar::new(),
+        });
+        self.views.lock().unwrap().insert(session_id.to_string(), Arc::clone(&view));
+        view
+    }
+
+    // Forget a session's view; a run still going keeps publishing to its own handle
+    pub(crate) fn drop_session(&self, session_id: &str) {
+        self.views.lock().unwrap().remove(session_id);
+    }
+
+    fn get(&self, session_id: &str) -> Option<LiveView> {
+        self.views.lock().unwrap().get(session_id).cloned()
+    }
+}
+
+// Swap in a new snapshot, keeping the version monotonic
+pub(crate) fn publish(view: &LiveView, mut snapshot: LiveSnapshot) {
+    let version = {
+        let mut current = view.snapshot.write().unwrap();
+        snapshot.session_id = std::mem::take(&mut current.session_id);
+        snapshot.running = current.running;
+        snapshot.version = current.version + 1;
+        *current = snapshot;
+        current.version
+    };
+    view.changed(version);
+}
+
+// Mark the run as finished; the last snapshot stays readable
+pub(crate) fn finish(view: &LiveView) {
+    let version = {
+        let mut current = view.snapshot.write().unwrap();
+        current.running = false;
+        current.version += 1;
+        current.version
+    };
+    view.changed(version);
+}
+
+// What changed between the last update sent and the current snapshot
+fn delta(sent: Option<&LiveSnapshot>, current: &LiveSnapshot) -> LiveDelta {
+    let seats = current.seats.iter()
+        .filter(|seat| sent.is_none_or(|s| !s.seats.contains(seat)))
+        .cloned()
+        .collect();
+    LiveDelta {
+        session_id: current.session_id.clone(),
+        running: current.running,
+        version: current.version,
+        coalesced: current.version - sent.map_or(0, |s| s.version),
+        virtual_time: current.virtual_time,
+        seats,
+        waiting_family_ids: current.waiting_family_ids.clone(),
+        baby_chairs_available: current.baby_chairs_available,
+        wheelchairs_available: current.wheelchairs_available,
+        event_count: current.event_count,
+    }
+}
+
+// The thread pushing a view's changes to its window. Dropping it stops the thread,
+// for a shift that is closed before its view is finished; join lets it send the final
+// state of a finished run first.
+pub(crate) struct Governor {
+    view: LiveView,
+    window_label: String,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Governor {
+    pub(crate) fn window_label(&self) -> &str {
+        &self.window_label
+    }
+
+    pub(crate) fn join(mut self) {
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for Governor {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            self.view.stop();
+            let _ = handle.join();
+        }
+    }
+}
+
+// Push the view's changes to the window, at most `rate` updates a second. A change
+// goes out as soon as the last update is old enough, so sparse runs stay smooth; in
+// busy runs the changes in between are coalesced into the next delta. The thread sleeps
+// until the engine publishes, and ends once the run is finished and its final state has
+// been sent, or when the Governor is dropped. A rate of 0 pushes nothing.
+pub(crate) fn govern(view: LiveView, window: Window, rate: u32) -> Option<Governor> {
+    if rate == 0 {
+        return None;
+    }
+    let interval = Duration::from_secs_f64(1.0 / rate as f64);
+    let window_label = window.label().to_string();
+    let watched = Arc::clone(&view);
+    let handle = std::thread::spawn(move || {
+        let label = window.label().to_string();
+        let mut sent: Option<LiveSnapshot> = None;
+        let mut last_emit: Option<Instant> = None;
+        loop {
+            let current = view.read();
+            let changed = sent.as_ref().is_none_or(|s| s.version != current.version);
+            if !changed && !current.running {
+                return;
+            }
+            // The final state is never held back
+            let wait = last_emit.map(|t| interval.saturating_sub(t.elapsed())).unwrap_or_default();
+            if changed && (!current.running || wait.is_zero()) {
+                if window.emit_to(label.as_str(), LIVE_EVENT, delta(sent.as_ref(), &current)).is_err() {
+                    return;
+                }
+                last_emit = Some(Instant::now());
+                sent = Some(current);
+                continue;
+            }
+
+            let signal = view.signal.lock().unwrap();
+            let signal = if changed {
+                // Too soon after the last update: hold the change back for the rest of the interval
+                view.changed.wait_timeout_while(signal, wait, |s| !s.stopped).unwrap().0
+            } else {
+                view.changed.wait_while(signal, |s| !s.stopped && s.version <= current.version).unwrap()
+            };
+            if signal.stopped {
+                return;
+            }
+        }
+    });
+    Some(Governor { view: watched, window_label, handle: Some(handle) })
+}
+
+// Consistent snapshot of a session's run, also while the engine is still running: seat
+// occupancy, the waiting queue in the order parties will be offered seats and the pools.
+// A reconnecting frontend can render from it without waiting for the run to finish.
+#[tauri::command]
+pub fn get_current_state(session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>, live: State<'_, LiveStateStore>) -> Result<LiveSnapshot> {
+    let session_id = sessions.resolve(session_id, &window);
+    let view = live.get(&session_id)
+        .ok_or_else(|| AppError::SessionError(format!("No simulation has run in session {}", session_id)))?;
+    Ok(view.read())
+}
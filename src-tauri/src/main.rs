@@ -5,14 +5,180 @@ mod models;
 mod parser;
 mod simulation;
 mod errors;
+mod session;
+mod analysis;
+mod stats;
+mod query;
+mod kitchen;
+mod stations;
+mod diagnostics;
+mod config;
+mod capabilities;
+mod protocol;
+mod subscriptions;
+mod playback;
+mod coupons;
+mod generator;
+mod rng;
+mod feasibility;
+mod deadlock;
+mod instrumentation;
+mod live;
+mod repro;
+mod ledger;
+mod condiments;
+mod couriers;
+mod capacity;
+mod validation;
+mod announcements;
+mod narration;
+mod bookings;
+mod streaming;
+mod demand;
+mod batch;
+mod workbook;
+mod export;
+mod replay;
+mod roster;
+mod access;
+mod layouts;
+mod hashing;
+
+use std::time::Duration;
+use tauri::{Manager, RunEvent, WindowEvent};
+
+// Upper bound on how long exit waits for in-flight simulations to wind down
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
 
 fn main() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init()) // Keep if you use opener
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .manage(session::SessionStore::default())
+        .manage(subscriptions::SubscriptionStore::default())
+        .manage(playback::PlaybackStore::default())
+        .manage(live::LiveStateStore::default())
+        .manage(simulation::operations::OperationsStore::default())
+        .manage(simulation::RunControlStore::default())
+        .manage(access::AccessStore::default())
+        .on_window_event(|window, event| {
+            // Forget the session binding, subscriptions and live updates of closed windows
+            if let WindowEvent::Destroyed = event {
+                let sessions = window.state::<session::SessionStore>();
+                let session_id = sessions.unbind_window(window.label());
+                // The live view goes once no other window shows its session
+                let shown = window.webview_windows().keys()
+                    .any(|label| label != window.label() && sessions.window_session(label) == session_id);
+                if !shown {
+                    window.state::<live::LiveStateStore>().drop_session(&session_id);
+                }
+                window.state::<subscriptions::SubscriptionStore>().drop_window(window.label());
+                window.state::<simulation::operations::OperationsStore>().drop_window(window.label());
+            }
+        })
         .invoke_handler(tauri::generate_handler![
+            capabilities::get_engine_capabilities,
+            protocol::negotiate_protocol,
+            protocol::run_simulation_versioned,
             simulation::start_simulation,
-            simulation::load_customers
+            simulation::run_simulation,
+            simulation::rerun_simulation,
+            simulation::preview_customers,
+            simulation::pause_simulation,
+            simulation::resume_simulation,
+            streaming::stream_simulation,
+            simulation::operations::start_operations,
+            simulation::operations::inject_arrival,
+            simulation::operations::seat_walk_in,
+            simulation::operations::record_departure,
+            simulation::operations::recommend_seats,
+            simulation::operations::get_operations_status,
+            simulation::operations::end_operations,
+            simulation::operations::list_operations_journals,
+            simulation::operations::restore_operations,
+            simulation::operations::discard_operations_journal,
+            access::get_access_settings,
+            access::get_window_role,
+            access::set_access_settings,
+            generator::generate_customers,
+            demand::fit_demand_model,
+            batch::run_monte_carlo,
+            generator::repair_roster,
+            generator::anonymize_roster,
+            bookings::import_bookings,
+            bookings::import_calendar_bookings,
+            simulation::get_shadow_report,
+            simulation::get_kitchen_report,
+            condiments::get_condiment_report,
+            couriers::get_courier_report,
+            simulation::compare_engines,
+            live::get_current_state,
+            analysis::analyze_extra_seats,
+            capacity::plan_capacity,
+            coupons::run_coupon_days,
+            diagnostics::validate_customer_csv_text,
+            diagnostics::validate_seat_layout,
+            layouts::save_seat_layout,
+            layouts::load_seat_layout,
+            layouts::list_seat_layouts,
+            layouts::delete_seat_layout,
+            layouts::generate_seat_layout,
+            layouts::expand_seat_ids,
+            diagnostics::apply_fixes,
+            feasibility::check_feasibility,
+            validation::validate_simulation_inputs,
+            query::query_frames,
+            subscriptions::subscribe_events,
+            subscriptions::unsubscribe_events,
+            playback::playback_play,
+            playback::playback_pause,
+            playback::playback_seek,
+            playback::playback_set_speed,
+            playback::get_playback_state,
+            playback::step_simulation,
+            announcements::get_announcements,
+            announcements::get_quote_accuracy,
+            stats::set_cost_model,
+            stats::get_abandonment_costs,
+            stats::get_kpis,
+            stats::get_conveyor_preference_stats,
+            stats::get_order_ahead_stats,
+            stats::get_wait_by_seat_class,
+            stats::get_starvation_report,
+            stats::get_day_report,
+            stats::check_slas,
+            stats::get_simulation_stats,
+            workbook::export_stats_xlsx,
+            export::copy_stats_summary,
+            export::copy_log_range,
+            export::export_log,
+            replay::save_run,
+            replay::load_run,
+            roster::load_customers,
+            instrumentation::get_engine_health,
+            instrumentation::get_sync_trace,
+            instrumentation::export_sync_trace,
+            ledger::get_resource_ledger,
+            repro::export_repro_bundle,
+            repro::import_repro_bundle,
+            narration::export_narration,
+            session::open_session_window,
+            session::get_window_session,
+            session::get_run_metadata,
+            session::annotate_event,
+            session::list_annotations,
+            session::add_bookmark,
+            session::remove_bookmark,
+            session::list_bookmarks,
+            session::get_session_memory,
+            session::downsample_frames
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Cancel running simulations and wait for their worker threads before exiting
+            if let RunEvent::ExitRequested { .. } | RunEvent::Exit = event {
+                app.state::<session::SessionStore>().shutdown(SHUTDOWN_GRACE);
+            }
+        });
 }
\ No newline at end of file
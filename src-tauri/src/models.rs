@@ -1,10 +1,107 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+// External customer id as it appears in the roster: a number, or an opaque code such as
+// a booking reference ("BK-7F3A"). Numbers stay numbers, also on the wire, so existing
+// numeric rosters and frontends are unaffected. The engine itself keys on family_id.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(transparent)]
+pub struct CustomerId(IdRepr);
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(untagged)]
+enum IdRepr {
+    Number(u32),
+    Code(String),
+}
+
+impl CustomerId {
+    // None for blank text; a plain number becomes a numeric id, anything else a code.
+    // Digits with leading zeros ("007") stay a code, so the id reads as written.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if text.is_empty() {
+            return None;
+        }
+        Some(match text.parse::<u32>() {
+            Ok(n) if n.to_string() == text => CustomerId(IdRepr::Number(n)),
+            _ => CustomerId(IdRepr::Code(text.to_string())),
+        })
+    }
+
+    pub fn number(&self) -> Option<u32> {
+        match &self.0 {
+            IdRepr::Number(n) => Some(*n),
+            IdRepr::Code(_) => None,
+        }
+    }
+}
+
+impl From<u32> for CustomerId {
+    fn from(n: u32) -> Self {
+        CustomerId(IdRepr::Number(n))
+    }
+}
+
+impl fmt::Display for CustomerId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            IdRepr::Number(n) => write!(f, "{}", n),
+            IdRepr::Code(code) => f.write_str(code),
+        }
+    }
+}
+
+// u64 seeds go over the wire as decimal strings, since JavaScript numbers lose digits
+// past 2^53; numbers are still accepted on the way in. Use with #[serde(with = ...)].
+pub mod seed_text {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    pub(super) enum Text {
+        Number(u64),
+        String(String),
+    }
+
+    pub(super) fn parse<E: serde::de::Error>(text: Text) -> Result<u64, E> {
+        match text {
+            Text::Number(n) => Ok(n),
+            Text::String(s) => s.trim().parse().map_err(|_| E::custom(format!("'{}' is not a seed", s))),
+        }
+    }
+
+    pub fn serialize<S: Serializer>(seed: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(seed)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        parse(Text::deserialize(deserializer)?)
+    }
+
+    pub mod option {
+        use super::{parse, Text};
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        pub fn serialize<S: Serializer>(seed: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error> {
+            match seed {
+                Some(seed) => serializer.collect_str(seed),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+            Option::<Text>::deserialize(deserializer)?.map(parse).transpose()
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct CustomerConfig {
-    pub id: u32,
-    pub family_id: u32,
+    pub id: CustomerId,
+    pub family_id: u32, // internal key; equals the id for numeric ids (see parser::IdNamespace)
     pub arrival_time: u64,
     #[serde(rename = "type")]
     pub type_: String,
@@ -12,6 +109,18 @@ pub struct CustomerConfig {
     pub baby_chair_count: u32,
     pub wheelchair_count: u32,
     pub est_dining_time: u64,
+    #[serde(default)]
+    pub conveyor_preference: f64, // 0..1, how strongly the party wants a seat facing the conveyor belt
+    #[serde(default)]
+    pub dietary_flags: Vec<String>, // e.g. GLUTEN_FREE, NUT_ALLERGY; routed to the dedicated prep station
+    #[serde(default)]
+    pub ordered_ahead: bool, // Food fires before arrival and is ready on seating
+    #[serde(default)]
+    pub extra: HashMap<String, String>, // Unknown CSV columns (name, phone, notes, ...) by header name
+    #[serde(default)]
+    pub max_wait_time: Option<u64>, // minutes before leaving unserved; overrides the run's max_wait_time
+    #[serde(default)]
+    pub priority: u32, // 0 for walk-ins; higher (VIP, elderly, disability) is offered freed seats first
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -24,10 +133,49 @@ pub struct SeatConfig {
     pub type_: String,
     pub is_wheelchair_accessible: bool,
     pub label: Option<String>,
+    pub revenue_weight: Option<f64>, // Relative revenue per cover (default 1.0), e.g. window tables earn more
+    #[serde(default)]
+    pub faces_conveyor: bool,
+    #[serde(default)]
+    pub adjacent_to: Vec<String>, // Tables that can be pushed together with this one (merge_tables)
+    #[serde(default)]
+    pub max_baby_chairs: Option<u32>, // Baby chairs the seat can take; default none at the bar, party-size at tables
+    #[serde(default)]
+    pub wheelchair_capacity: Option<u32>, // Wheelchair spaces at an accessible table; default one
+    #[serde(default)]
+    pub attributes: HashMap<String, f64>, // e.g. noise, temperature, view; matched against "<name>_tolerance" roster columns
+    #[serde(default)]
+    pub contiguity_group: Option<String>, // Bar seats in a row, e.g. "counter-north"; a family never spans two groups
+    #[serde(default)]
+    pub group_position: Option<u32>, // Place within the contiguity group; config order if unset
+}
+
+// A party's needs changing before it is seated (a member leaves, the baby chair is no
+// longer wanted, ...) at minute `at`, or when it arrives if that is later. Unset fields
+// stay as they were.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RequirementChange {
+    pub family_id: u32,
+    pub at: u64,
+    pub party_size: Option<u32>,
+    pub baby_chair_count: Option<u32>,
+    pub wheelchair_count: Option<u32>,
 }
 
+// A booked table: held for the family over [start, end] (minutes), with walk-ins kept
+// off it unless the family hasn't shown up by the end of the grace period
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
+pub struct TableReservation {
+    pub family_id: u32,
+    pub seat_ids: Vec<String>,
+    pub start: u64,
+    pub end: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct Seat {
     pub id: String,
     #[serde(rename = "type")]
@@ -35,19 +183,48 @@ pub struct Seat {
     pub occupied_by: Option<u32>,
     pub occupant_type: Option<String>, // Occupant type (BABY, WHEELCHAIR, NORMAL)
     pub baby_chair_count: u32,
+    #[serde(default)]
+    pub wheelchair_count: u32, // wheelchair users the engine placed at this seat
     pub is_wheelchair_accessible: bool,
+    #[serde(default)]
+    pub state: OccupancyState,
+}
+
+// What the renderer shows for a seat. Serialized as {"kind": "OCCUPIED", "familyId": 7};
+// renderers should treat unknown kinds as unavailable so new states degrade gracefully.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(tag = "kind", rename_all = "SCREAMING_SNAKE_CASE", rename_all_fields = "camelCase")]
+pub enum OccupancyState {
+    #[default]
+    Free,
+    Reserved,
+    Cleaning,
+    OutOfService,
+    Occupied { family_id: u32 },
+}
+
+impl OccupancyState {
+    pub fn for_occupant(occupied_by: Option<u32>) -> Self {
+        match occupied_by {
+            Some(family_id) => OccupancyState::Occupied { family_id },
+            None => OccupancyState::Free,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SimulationEvent {
     pub timestamp: u64,
+    pub sequence: usize, // Engine-wide order of the event, stable identifier within a run
     #[serde(rename = "type")]
     pub type_: String,
-    pub customer_id: u32,
+    pub customer_id: CustomerId,
     pub family_id: u32,
     pub seat_id: Option<String>,
     pub message: String,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub extra: HashMap<String, String>, // the family's passthrough CSV columns
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -58,4 +235,1288 @@ pub struct SimulationFrame {
     pub waiting_queue: Vec<CustomerConfig>,
     pub events: Vec<SimulationEvent>,
     pub logs: Vec<String>,
+    #[serde(default)]
+    pub sub_queues: WaitingSubQueues,
+    #[serde(default)]
+    pub stations: Vec<StationOccupancy>, // self-service stations, when the run has any
+}
+
+// Waiting families (by id, in arrival order) split by the seat class they need
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WaitingSubQueues {
+    pub single: Vec<u32>,
+    pub four_person: Vec<u32>,
+    pub six_person: Vec<u32>,
+    pub accessible: Vec<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionMemoryReport {
+    pub session_id: String,
+    pub frame_count: usize,
+    pub event_count: usize,
+    pub frame_bytes: usize,
+    pub event_bytes: usize,
+    pub input_bytes: usize,
+    pub checkpoint_bytes: usize, // kept by a DES re-run for the next rerun_simulation
+    pub total_bytes: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ShadowDecision {
+    pub timestamp: u64,
+    pub family_id: u32,
+    pub primary_seats: Option<Vec<String>>,
+    pub shadow_seats: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ShadowFamilyDelta {
+    pub family_id: u32,
+    pub actual_wait: u64,
+    pub shadow_sit_time: Option<u64>, // when the shadow policy's own run seated them
+    pub estimated_wait_delta: Option<i64>, // Negative = shadow policy would have seated them sooner, positive = later
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ShadowReport {
+    pub policy: String,
+    pub decisions_compared: usize,
+    pub divergences: Vec<ShadowDecision>,
+    pub families: Vec<ShadowFamilyDelta>,
+    pub total_estimated_wait_delta: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CounterfactualResult {
+    pub seat_type: String,
+    pub wheelchair_accessible: bool,
+    pub avg_wait: f64,
+    pub max_wait: u64,
+    pub seated_count: usize,
+    pub avg_wait_improvement: f64,
+    pub max_wait_improvement: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CounterfactualReport {
+    pub baseline_avg_wait: f64,
+    pub baseline_max_wait: u64,
+    pub baseline_seated_count: usize,
+    pub additions: Vec<CounterfactualResult>, // Ranked, best improvement first
+}
+
+// Seats per type and resource pools of a floor plan built with generate_seat_layout
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct CapacityConfig {
+    pub bar_seats: u32,
+    pub four_tops: u32,
+    pub six_tops: u32,
+    pub accessible_tables: u32, // the first tables of the plan; at most all of them
+    pub baby_chairs: u32,
+    pub wheelchairs: u32,
+}
+
+// One configuration tried by plan_capacity, over every roster of the batch
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CapacityEvaluation {
+    pub config: CapacityConfig,
+    pub runs: usize,
+    pub p95_wait: Option<u64>, // None when more than 5% of parties were never seated
+    pub avg_wait: f64,         // of seated parties
+    pub seated_share: f64,
+    pub meets_target: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CapacityPlan {
+    pub target_wait: u64,
+    pub recommended: CapacityConfig,
+    pub layout: Vec<SeatConfig>, // the recommended floor plan, ready for the simulation commands
+    pub evidence: CapacityEvaluation, // batch results of the recommendation
+    pub evaluations: Vec<CapacityEvaluation>, // every configuration tried, in search order
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RunMetadata {
+    pub objective: String,
+    pub strategy: String,
+    pub fairness: String,
+    pub shadow_strategy: Option<String>,
+    pub baby_chairs: i32,
+    pub wheelchairs: i32,
+    pub total_revenue: f64,
+    pub engine: String, // "threads", "des", "semaphores" or "tasks"
+    pub fingerprint: String, // hash of the canonical event log; equal runs share it
+    #[serde(default)]
+    pub resumed_from: Option<u64>, // checkpoint time an incremental re-run picked up from
+}
+
+// User-supplied cost (money or utility) of each way a party can be lost
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AbandonmentCostModel {
+    pub renege_cost: f64,      // left after their max_wait_time (LEAVE_UNSERVED)
+    pub turned_away_cost: f64, // the layout can never seat them (INFEASIBLE)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AbandonmentCostSummary {
+    pub reneged: usize,
+    pub turned_away: usize,
+    pub renege_cost_total: f64,
+    pub turned_away_cost_total: f64,
+    pub total_cost: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EventAnnotation {
+    pub sequence: usize,
+    pub timestamp: u64,
+    pub family_id: u32,
+    pub note: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Bookmark {
+    pub timestamp: u64,
+    pub label: String,
+}
+
+// Predicate over a single frame, used by the frame search API
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum FrameQuery {
+    SeatOccupied { seat_id: String },
+    SeatFree { seat_id: String },
+    FamilySeated { family_id: u32 },
+    FamilyWaiting { family_id: u32 },
+    QueueLengthOver { length: usize },
+    All { queries: Vec<FrameQuery> },
+    Any { queries: Vec<FrameQuery> },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct KpiSummary {
+    pub covers_served: u32,
+    pub families_served: usize,
+    pub avg_wait: f64,
+    pub table_turns_per_seat: f64,
+    pub peak_occupancy_pct: f64,
+    pub rejection_rate: f64, // Share of arrived families that were never seated
+    pub total_cost: f64,
+    pub starved_families: usize, // Families a smaller, later family overtook at least once
+    pub fingerprint: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConveyorPreferenceStats {
+    pub preferring_families: usize,
+    pub preferring_got_conveyor_seat: usize,
+    pub preferring_avg_wait: f64,
+    pub other_avg_wait: f64,
+}
+
+// Dedicated prep station for orders with dietary flags
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct KitchenConfig {
+    pub dietary_station_capacity: u32, // Orders the station can prepare at once
+    pub dietary_prep_time: u64,        // Extra prep time vs. a standard order
+    pub standard_prep_time: u64,       // Food wait already included in est_dining_time for walk-in orders
+    pub line_capacity: u32,            // Standard orders the line prepares at once; 0 leaves the line unmodelled
+}
+
+impl Default for KitchenConfig {
+    fn default() -> Self {
+        KitchenConfig { dietary_station_capacity: 1, dietary_prep_time: 5, standard_prep_time: 5, line_capacity: 0 }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct KitchenDelay {
+    pub family_id: u32,
+    pub dietary_flags: Vec<String>,
+    pub order_time: u64,
+    pub queue_wait: u64,
+    pub added_delay: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct KitchenReport {
+    pub config: KitchenConfig,
+    pub flagged_orders: usize,
+    pub total_added_delay: u64,
+    pub avg_added_delay: f64,
+    pub max_added_delay: u64,
+    pub max_queue_wait: u64,
+    pub orders: Vec<KitchenDelay>,
+    pub ordered_ahead_parties: usize,
+    pub total_time_saved: u64, // Occupancy saved by order-ahead parties skipping the kitchen
+    #[serde(default)]
+    pub line_delayed_orders: usize, // Standard orders that queued for the line
+    #[serde(default)]
+    pub total_line_delay: u64,
+    #[serde(default)]
+    pub couriers: Option<CourierReport>, // only when the run had courier orders
+}
+
+// A delivery order placed through an app and collected by a courier at the pickup
+// counter; couriers never take a seat. Its steps are logged under COURIER_IDS.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CourierOrder {
+    pub id: u32,
+    pub order_time: u64,
+    pub arrival_time: u64, // courier reaches the pickup counter
+    #[serde(default)]
+    pub prep_time: Option<u64>, // kitchen.standard_prep_time if None
+}
+
+// Courier orders queue for the kitchen line with dine-in orders, in the order they are
+// fired; couriers then wait for a pickup counter slot and should be gone within `sla`
+// minutes of arriving
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CourierConfig {
+    pub orders: Vec<CourierOrder>,
+    pub pickup_slots: u32, // couriers the counter serves at once
+    pub pickup_time: u64,  // minutes a handover takes
+    pub sla: u64,          // longest a courier should spend at the shop
+}
+
+impl Default for CourierConfig {
+    fn default() -> Self {
+        CourierConfig { orders: Vec::new(), pickup_slots: 1, pickup_time: 1, sla: 5 }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CourierPickup {
+    pub id: u32,
+    pub order_time: u64,
+    pub arrival_time: u64,
+    pub ready_time: u64,   // food off the line
+    pub pickup_start: u64, // courier at the counter
+    pub departure: u64,
+    pub wait: u64,         // departure - arrival_time
+    pub sla_met: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CourierReport {
+    pub pickup_slots: u32,
+    pub sla: u64,
+    pub couriers: usize,
+    pub sla_breaches: usize,
+    pub avg_wait: f64,
+    pub max_wait: u64,
+    pub pickups: Vec<CourierPickup>,
+}
+
+// Self-service station (tea, soup, ...) seated parties walk up to during their meal
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfServiceStation {
+    pub name: String,
+    pub capacity: u32,    // parties served at once
+    pub visit_time: u64,  // minutes per trip
+    pub visit_share: f64, // 0..1, share of parties (guests, if per_guest) that use it
+    #[serde(default)]
+    pub per_guest: bool,  // every guest makes their own trip (restroom) instead of one per party
+}
+
+// Restroom as a scenario setting: stalls shared by every seated guest
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RestroomConfig {
+    pub stalls: u32,
+    pub visit_time: u64,  // minutes per trip
+    pub visit_share: f64, // 0..1, share of guests who go during their meal
+}
+
+impl Default for RestroomConfig {
+    fn default() -> Self {
+        RestroomConfig { stalls: 2, visit_time: 4, visit_share: 0.3 }
+    }
+}
+
+impl RestroomConfig {
+    pub fn station(&self) -> SelfServiceStation {
+        SelfServiceStation {
+            name: "Restroom".to_string(),
+            capacity: self.stalls,
+            visit_time: self.visit_time,
+            visit_share: self.visit_share,
+            per_guest: true,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StationVisit {
+    pub station: String,
+    pub family_id: u32,
+    pub arrive: u64,
+    pub start: u64, // later than arrive when the party queued
+    pub end: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StationOccupancy {
+    pub name: String,
+    pub in_use: u32,
+    pub queued: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StationReport {
+    pub name: String,
+    pub capacity: u32,
+    pub visits: usize,
+    pub queued_visits: usize,
+    pub total_queue_wait: u64, // added to the visiting parties' stays
+    pub max_queue_wait: u64,
+    pub busy_minutes: u64,
+    pub utilization_pct: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderAheadStats {
+    pub ordered_ahead_parties: usize,
+    pub walk_in_parties: usize,
+    pub ordered_ahead_avg_occupancy: f64,
+    pub walk_in_avg_occupancy: f64,
+    pub ordered_ahead_turns_per_hour: f64, // Parties a seat could turn per hour at that occupancy
+    pub walk_in_turns_per_hour: f64,
+}
+
+// Editor diagnostics, shaped after the Language Server Protocol (0-based lines/characters)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TextPosition {
+    pub line: u32,
+    pub character: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TextRange {
+    pub start: TextPosition,
+    pub end: TextPosition,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+    Information,
+    Hint,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TextEdit {
+    pub range: TextRange,
+    pub new_text: String,
+}
+
+// A fix with no edits is only a suggestion for the user
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickFix {
+    pub title: String,
+    #[serde(default)]
+    pub edits: Vec<TextEdit>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    pub range: TextRange,
+    pub severity: DiagnosticSeverity,
+    pub code: String,   // Stable machine-readable id, e.g. "duplicate-seat-id"
+    pub source: String, // Which validator produced it ("customer-csv", "seat-layout")
+    pub message: String,
+    pub quick_fix: Option<QuickFix>,
+}
+
+// A floor plan stored with save_seat_layout
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedLayout {
+    pub name: String,
+    pub seats: usize,
+}
+
+// Id patterns for generate_seat_layout, e.g. `S{01..12}`; unset kinds keep the default ids
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SeatIdPatterns {
+    pub bar: Option<String>,
+    pub four_top: Option<String>,
+    pub six_top: Option<String>,
+}
+
+// A customer CSV problem pinned to a row and field, for a preview grid
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvRowIssue {
+    pub line: u32,              // 1-based line in the CSV text
+    pub family_id: Option<u32>, // the row's id, when readable
+    pub field: Option<String>,  // column name; None for whole-row problems
+    pub severity: DiagnosticSeverity,
+    pub code: String,
+    pub reason: String,
+}
+
+// Parsed roster plus everything wrong with it, before a run is started
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomerPreview {
+    pub customers: Vec<CustomerConfig>,
+    pub issues: Vec<CsvRowIssue>,
+    pub has_errors: bool,
+}
+
+// Optional features compiled into this build, for frontend feature gating
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EngineCapabilities {
+    pub engine_version: String,
+    pub protocol_version: String,
+    pub streaming: bool,
+    pub wasm_strategies: bool,
+    pub belt_model: bool,
+    pub rest_server: bool,
+    pub allocation_strategies: Vec<String>,
+    pub objectives: Vec<String>,
+    pub engines: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolHandshake {
+    pub protocol_version: String, // backend protocol
+    pub client_version: String,
+    pub compatible: bool,
+    pub shims: Vec<String>,       // translations applied for this client
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionedRequest {
+    pub protocol_version: String,
+    pub payload: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionedResponse {
+    pub protocol_version: String,
+    pub result: serde_json::Value,
+}
+
+// Empty lists match everything
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EventFilter {
+    pub types: Vec<String>,
+    pub family_ids: Vec<u32>,
+}
+
+// Operations mode: a party in the lobby, with the seats the engine would give it now
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WaitingParty {
+    pub family_id: u32,
+    pub party_size: u32,
+    pub name: Option<String>,
+    pub arrival_time: u64, // minutes into the shift
+    pub waited: u64,
+    pub suggested_seats: Option<Vec<String>>, // None while nothing fits
+}
+
+// One way to seat a waiting party, from recommend_seats
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SeatRecommendation {
+    pub seat_ids: Vec<String>,
+    pub score: f64, // higher is better
+    pub wasted_seats: u32,
+    pub faces_conveyor: bool,
+    pub blocked_reservations: Vec<u32>, // bookings due at these seats before the party is projected to leave
+    pub engine_choice: bool, // the seats the engine itself would pick
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SeatedParty {
+    pub family_id: u32,
+    pub party_size: u32,
+    pub name: Option<String>,
+    pub seat_ids: Vec<String>,
+    pub seated_at: u64,
+    pub expected_leave: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationsStatus {
+    pub session_id: String,
+    pub now: u64, // minutes since the shift started
+    pub waiting: Vec<WaitingParty>,
+    pub seated: Vec<SeatedParty>,
+    pub upcoming_reservations: Vec<TableReservation>, // not checked in yet, by start
+    pub baby_chairs_available: i32,
+    pub wheelchairs_available: i32,
+}
+
+// How densely generate_frames samples a run. PER_SECOND is the original one frame per
+// time unit (with 5 trailing frames); the others drop frames that add nothing, folding
+// their events into the next frame kept.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(tag = "mode", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FrameResolution {
+    #[default]
+    PerSecond,
+    PerEvent,                 // one frame per moment something happens
+    Every { seconds: u64 },   // one frame every `seconds` time units
+    SkipIdle,                 // per second, except while the floor is empty and nobody waits
+}
+
+// What a window may do: a host runs simulations, playback and the floor, a viewer (e.g.
+// a lobby display) only watches. Windows are viewers unless made hosts.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Role {
+    Host,
+    #[default]
+    Viewer,
+}
+
+// Label of the window the app opens with
+pub const MAIN_WINDOW: &str = "main";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AccessSettings {
+    pub default_role: Role,                  // for windows not listed below
+    pub window_roles: HashMap<String, Role>, // by window label
+}
+
+// Only the main window is a host out of the box
+impl Default for AccessSettings {
+    fn default() -> Self {
+        AccessSettings {
+            default_role: Role::Viewer,
+            window_roles: HashMap::from([(MAIN_WINDOW.to_string(), Role::Host)]),
+        }
+    }
+}
+
+// An operations shift that never ended, found in its crash-recovery journal
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationsJournal {
+    pub journal_id: String, // pass to restore_operations
+    pub started_at: u64,    // unix seconds
+    pub last_change: u64,   // minutes into the shift
+    pub arrivals: usize,
+    pub waiting: usize,
+    pub seated: usize,
+}
+
+// End-of-day report of an operations-mode shift
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DayReport {
+    pub session_id: String,
+    pub shift_minutes: u64,
+    pub kpis: KpiSummary,
+    pub wait_by_seat_class: Vec<SeatClassWait>,
+    pub starvation: StarvationReport,
+    pub total_revenue: f64,
+    pub unseated_family_ids: Vec<u32>,     // still waiting at close
+    pub still_dining_family_ids: Vec<u32>, // seated, no departure recorded
+    pub no_show_reservation_ids: Vec<u32>, // booked but never checked in
+    pub summary: String,                   // a few plain sentences for the report
+}
+
+// One frame of a streamed run (simulation://frame)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamedFrame {
+    pub session_id: String,
+    pub index: usize,
+    pub total: usize,
+    pub frame: SimulationFrame,
+}
+
+// End of a streamed run (simulation://done), sent on failure too
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamDone {
+    pub session_id: String,
+    pub frame_count: usize,
+    pub metadata: Option<RunMetadata>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EventBatch {
+    pub subscription_id: u32,
+    pub session_id: String,
+    pub events: Vec<SimulationEvent>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaybackStatus {
+    pub session_id: String,
+    pub current_time: f64,
+    pub playing: bool,
+    pub speed: f64,
+    pub max_time: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaybackTick {
+    pub status: PlaybackStatus,
+    pub frame_index: usize,
+    pub frame: Option<SimulationFrame>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SeatClassWait {
+    pub seat_class: String,
+    pub families: usize,
+    pub avg_wait: f64,
+    pub max_wait: u64,
+    pub peak_queue_length: usize,
+}
+
+// Service-recovery policy for parties that leave unserved
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CouponPolicy {
+    pub enabled: bool,
+    pub return_after_days: u32,
+}
+
+impl Default for CouponPolicy {
+    fn default() -> Self {
+        CouponPolicy { enabled: true, return_after_days: 1 }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Coupon {
+    pub original_family_id: u32,
+    pub issued_day: u32,
+    pub redeem_day: u32,
+    pub returned_family_id: Option<u32>, // id the party used when it came back
+    pub served_on_return: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CouponDaySummary {
+    pub day: u32,
+    pub arrivals: usize,
+    pub served: usize,
+    pub lost: usize,
+    pub coupons_issued: usize,
+    pub coupons_redeemed: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CouponReport {
+    pub days: Vec<CouponDaySummary>,
+    pub coupons: Vec<Coupon>,
+    pub outstanding: usize, // coupons due after the last simulated day
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RosterRepair {
+    pub line: u32, // 0-based line in the CSV text
+    pub family_id: i64,
+    pub message: String,
+}
+
+// Result of load_customers: the roster, plus the same roster as simulation CSV
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RosterImport {
+    pub format: String, // csv, json or xlsx (any spreadsheet)
+    pub customers: Vec<CustomerConfig>,
+    pub csv_content: String,
+    pub warnings: Vec<CsvParseWarning>, // always empty in strict mode, which fails instead
+}
+
+// A row or value the lenient parser skipped or replaced with its default
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvParseWarning {
+    pub line: u32,              // 1-based line in the CSV text
+    pub column: Option<String>, // None for whole-row problems
+    pub text: String,           // the offending text
+    pub reason: String,
+    pub skipped: bool,          // the whole row was dropped
+}
+
+// How to read a roster that doesn't follow the standard layout
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ParserOptions {
+    pub delimiter: Option<char>,  // ',' by default; e.g. ';' or '\t'
+    pub has_header: Option<bool>, // None: a first line starting with "id" is the header
+    // Standard column name (e.g. "party_size") -> the file's column. Unmapped columns
+    // are found by header name, or by standard position in a file without a header.
+    pub columns: HashMap<String, ColumnRef>,
+}
+
+// A column of the file, by header name or 0-based position
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum ColumnRef {
+    Index(usize),
+    Name(String),
+}
+
+// Roster built from a reservation export; csv_content is the same roster in the
+// positional format the simulation takes
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BookingImport {
+    pub customers: Vec<CustomerConfig>,
+    pub csv_content: String,
+    #[serde(default)]
+    pub skipped: Vec<String>, // entries that weren't bookings, with the reason
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RosterRepairResult {
+    pub csv_content: String,
+    pub repairs: Vec<RosterRepair>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FeasibilityIssue {
+    pub severity: DiagnosticSeverity,
+    pub code: String,
+    pub family_ids: Vec<u32>,
+    pub time: Option<u64>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FeasibilityReport {
+    pub feasible: bool, // false if any party can never be seated
+    pub issues: Vec<FeasibilityIssue>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkerHealth {
+    pub family_id: u32,
+    pub lock_acquisitions: u64,
+    pub lock_wait_us: u64,     // total time spent blocked on the monitor lock
+    pub max_lock_wait_us: u64,
+    pub condvar_waits: u64,
+    pub wakeups: u64,          // woken by a notification
+    pub spurious_wakeups: u64, // woken, but still could not be seated
+    pub timeouts: u64,         // shutdown-poll timeouts
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EngineHealthReport {
+    pub workers: Vec<WorkerHealth>,
+    pub total_lock_wait_us: u64,
+    pub max_lock_wait_us: u64,
+    pub condvar_waits: u64,
+    pub wakeups: u64,
+    pub spurious_wakeups: u64,
+    pub timeouts: u64,
+    pub notify_calls: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncTraceEntry {
+    pub sequence: usize,
+    pub virtual_time: u64, // simulation clock when the operation happened
+    pub worker: String,    // worker thread id
+    pub family_id: u32,
+    pub operation: String, // LOCK, WAIT, WAKE, TIMEOUT or NOTIFY_ALL
+    pub detail: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EngineDivergence {
+    pub family_id: u32,
+    pub threads: Vec<String>, // e.g. "SEATED@12[S01]"
+    pub other: Vec<String>,   // sequence on the engine compared against
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EngineComparison {
+    pub engine: String, // engine compared against the thread engine
+    pub matches: bool,
+    pub families_compared: usize,
+    pub divergences: Vec<EngineDivergence>,
+}
+
+// Occupancy of a run as last published by the engine; seats carry occupancy only
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveSnapshot {
+    pub session_id: String,
+    pub running: bool,
+    pub version: u64, // bumped on every publish, so pollers can skip unchanged states
+    pub virtual_time: u64,
+    pub seats: Vec<Seat>,
+    pub waiting_family_ids: Vec<u32>, // queue order: priority, then ticket order
+    pub baby_chairs_available: i32,
+    pub wheelchairs_available: i32,
+    pub event_count: usize,
+}
+
+// Payload of the "simulation://live" event: what changed since the previous update.
+// Updates are rate-limited, so one delta can fold several engine changes together.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveDelta {
+    pub session_id: String,
+    pub running: bool,
+    pub version: u64,
+    pub coalesced: u64, // engine changes folded into this update
+    pub virtual_time: u64,
+    pub seats: Vec<Seat>, // changed seats only; every seat in the first update
+    pub waiting_family_ids: Vec<u32>,
+    pub baby_chairs_available: i32,
+    pub wheelchairs_available: i32,
+    pub event_count: usize,
+}
+
+// manifest.json of a reproducibility bundle
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReproManifest {
+    pub engine_version: String,
+    pub protocol_version: String,
+    pub engine: String,
+    #[serde(with = "seed_text::option")]
+    pub seed: Option<u64>, // None: the run drew no random numbers
+    pub fingerprint: String,
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReproDivergence {
+    pub family_id: u32,
+    pub bundled: Vec<String>, // e.g. "SEATED@12[S01]"
+    pub rerun: Vec<String>,
+}
+
+// Outcome of re-running an imported bundle
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReproCheck {
+    pub session_id: String, // where the re-run was stored
+    pub manifest: ReproManifest,
+    pub same_engine_version: bool,
+    pub fingerprint: String, // of the re-run
+    pub reproduced: bool,
+    pub divergences: Vec<ReproDivergence>,
+}
+
+// One acquisition or release of a shared resource pool
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LedgerEntry {
+    pub sequence: usize,
+    pub time: u64,
+    pub family_id: u32,
+    pub resource: String,  // BABY_CHAIRS or WHEELCHAIRS
+    pub operation: String, // ACQUIRE or RELEASE
+    pub count: u32,
+    pub balance: i32,      // pool level after this entry
+}
+
+// Priority inversion scenario: a single shared condiment station (see condiments.rs)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CondimentConfig {
+    pub enabled: bool,
+    pub service_time: u64,          // staff time each party needs
+    pub priority_inheritance: bool, // station holder inherits the priority of blocked parties
+}
+
+impl Default for CondimentConfig {
+    fn default() -> Self {
+        CondimentConfig { enabled: false, service_time: 2, priority_inheritance: false }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CondimentEvent {
+    pub time: u64,
+    pub family_id: u32,
+    pub priority: u8, // 1 low, 2 medium, 3 high
+    pub effective_priority: u8,
+    pub event: String, // REQUEST, ACQUIRE, BLOCKED, INHERIT, SERVE, PREEMPTED, DONE, RELEASE
+    pub detail: String,
+}
+
+// A high priority party blocked on the station by a lower priority holder
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PriorityInversion {
+    pub family_id: u32,
+    pub holder_family_id: u32,
+    pub blocked_from: u64,
+    pub blocked_until: u64,
+    pub inverted_time: u64, // part of the block spent while staff served medium priority parties
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CondimentReport {
+    pub config: CondimentConfig,
+    pub inversions: Vec<PriorityInversion>,
+    pub max_inverted_time: u64,
+    pub timeline: Vec<CondimentEvent>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FamilyStarvation {
+    pub family_id: u32,
+    pub party_size: u32,
+    pub arrival_time: u64,
+    pub wait: Option<u64>,      // None if never seated
+    pub overtaken_by: Vec<u32>, // smaller, later families seated while this one waited
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StarvationReport {
+    pub objective: String,
+    pub starved_families: usize,
+    pub worst_families: Vec<u32>,
+    pub families: Vec<FamilyStarvation>, // most overtaken first
+}
+
+// Lobby screen message synthesized from the run
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Announcement {
+    pub timestamp: u64,
+    pub kind: String, // TABLE_READY or WAIT_ESTIMATE
+    pub family_id: Option<u32>,
+    pub party_size: Option<u32>,
+    pub estimated_wait: Option<u64>,
+    pub message: String,
+}
+
+// Wait quoted to a party when it started waiting, and how long it actually waited
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WaitQuote {
+    pub family_id: u32,
+    pub party_size: u32,
+    pub quoted_at: u64,
+    pub quoted_wait: u64,
+    pub actual_wait: Option<u64>, // None if the party was never seated
+    pub error: Option<i64>,       // actual - quoted; positive = waited longer than quoted
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteSizeAccuracy {
+    pub party_size: u32,
+    pub quotes: usize,
+    pub resolved: usize,
+    pub mean_absolute_error: f64,
+    pub bias: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteAccuracyReport {
+    pub quoted: usize,
+    pub resolved: usize,         // quotes whose party was later seated
+    pub tolerance: u64,
+    pub within_tolerance: f64,   // share of resolved quotes off by at most `tolerance` minutes
+    pub mean_absolute_error: f64,
+    pub bias: f64,               // mean signed error; positive = quotes run short
+    pub by_party_size: Vec<QuoteSizeAccuracy>,
+    pub quotes: Vec<WaitQuote>,
+}
+
+// Shape of the dining-time distribution a generator samples from
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum DiningDistribution {
+    Uniform,
+    Normal,
+    LogNormal,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DiningTimeModel {
+    pub distribution: DiningDistribution,
+    pub mean: f64,    // minutes; for logNormal, of ln(minutes)
+    pub std_dev: f64, // likewise
+    pub min: u64,     // samples are clamped to [min, max]
+    pub max: u64,
+}
+
+// Parties arriving in [from, to) stay `multiplier` times as long, e.g. 0.7 for lunch
+// diners who eat 30% faster than the evening crowd
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DiningTimeBand {
+    #[serde(default)]
+    pub name: Option<String>, // e.g. "lunch", for display only
+    pub from: u64,
+    pub to: u64,
+    pub multiplier: f64,
+}
+
+// Parameters of a synthetic scenario, either hand-written or fitted from past data
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratorConfig {
+    pub count: u32,
+    pub max_arrival_time: u64,
+    // Relative arrival intensity per bucket of bucket_minutes, from minute 0; empty means uniform
+    #[serde(default)]
+    pub arrival_rates: Vec<f64>,
+    #[serde(default)]
+    pub bucket_minutes: u64,
+    pub party_size_weights: Vec<f64>, // index 0 is a party of one
+    pub baby_probability: f64,
+    pub wheelchair_probability: f64,
+    pub dining_time: DiningTimeModel,
+    #[serde(default, with = "seed_text::option")]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub dining_time_bands: Vec<DiningTimeBand>, // applied to each sampled stay by arrival time
+}
+
+// Result of generate_customers
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratedRoster {
+    pub customers: Vec<CustomerConfig>,
+    pub csv_content: String,
+    #[serde(with = "seed_text")]
+    pub seed: u64, // pass back in the config to regenerate the same roster
+    pub draws: Vec<RngDraw>, // empty unless record_draws was set
+}
+
+// One random number drawn by a seeded run, tagged with what it decided
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RngDraw {
+    pub index: usize,
+    pub purpose: String, // e.g. "party size", "arrival time"
+    pub value: f64,      // booleans are 0/1, integers as is
+}
+
+// Result of fit_demand_model
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DemandFit {
+    pub generator: GeneratorConfig,
+    pub sources: usize,
+    pub parties: usize,
+    pub arrivals_per_minute: Vec<f64>, // per bucket, averaged over sources
+    pub dining_log_likelihood: f64,    // of the chosen distribution
+    pub notes: Vec<String>,
+}
+
+// Result of run_monte_carlo
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MonteCarloResult {
+    pub metric: String,
+    pub replications: u32, // runs actually made
+    pub converged: bool,   // false if max_replications ran out first
+    pub mean: f64,
+    pub std_dev: f64,
+    pub half_width: f64, // of the confidence interval around the mean
+    pub confidence: f64,
+    pub tolerance: Option<f64>,
+    #[serde(with = "seed_text")]
+    pub base_seed: u64, // replication i uses base_seed + i
+    pub values: Vec<f64>,
+    #[serde(default)]
+    pub slas: Vec<SlaBatchResult>, // only when service targets were given
+}
+
+// Service target, e.g. 95% of parties seated within 20 minutes, or (percentile 100,
+// parties "WHEELCHAIR") no wheelchair party waits more than 10
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceTarget {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub percentile: Option<f64>, // share of matching parties, 0-100, that must meet max_wait; default 100
+    pub max_wait: u64,
+    // "WHEELCHAIR", "BABY" or a seat class ("SINGLE", "4P", "6P", "ACCESSIBLE"); None is everyone
+    #[serde(default)]
+    pub parties: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SlaResult {
+    pub name: String,
+    pub passed: bool,
+    pub families: usize,           // parties the target applies to
+    pub share_within: f64,         // 0-100; unseated parties count as late
+    pub percentile_wait: Option<u64>, // observed wait at the target percentile; None if that party was never seated
+    pub violating_family_ids: Vec<u32>, // over max_wait or never seated
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SlaReport {
+    pub passed: bool,
+    pub results: Vec<SlaResult>,
+}
+
+// How often a target held across the replications of a batch
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SlaBatchResult {
+    pub name: String,
+    pub passed_runs: u32,
+    pub runs: u32,
+    pub pass_rate: f64,
+    pub violating_family_ids: Vec<Vec<u32>>, // per replication
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FamilyWait {
+    pub family_id: u32,
+    pub party_size: u32,
+    pub arrival_time: u64,
+    pub seated_at: Option<u64>, // None if never seated
+    pub wait: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SeatTypeUtilization {
+    pub seat_type: String,
+    pub seats: usize,
+    pub occupied_minutes: u64, // summed over the seats of this type
+    pub utilization_pct: f64,
+}
+
+// Returned by get_simulation_stats
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationReport {
+    pub duration: u64, // minutes from the first to the last frame
+    pub families: Vec<FamilyWait>,
+    pub avg_wait: f64, // over seated families
+    pub max_wait: u64,
+    pub seat_utilization: Vec<SeatTypeUtilization>,
+    pub baby_chair_utilization_pct: f64, // time-weighted share of the pool in use
+    pub wheelchair_utilization_pct: f64,
+    pub customers_served: usize,
+    pub customers_reneged: usize, // left after their max_wait_time (LEAVE_UNSERVED)
+    pub customers_infeasible: usize, // turned away as the layout can never seat them (INFEASIBLE)
+    pub customers_never_seated: usize, // includes all of the above
+    pub stations: Vec<StationReport>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn customer_ids_read_as_written() {
+        assert_eq!(CustomerId::parse(" 42 "), Some(CustomerId::from(42)));
+        assert_eq!(CustomerId::parse("  "), None);
+        let code = CustomerId::parse("007").unwrap();
+        assert_eq!((code.number(), code.to_string().as_str()), (None, "007"));
+        assert_eq!(CustomerId::parse("BK-7F3A").unwrap().to_string(), "BK-7F3A");
+    }
+
+    // Numbers stay numbers on the wire and codes stay strings, both ways
+    #[test]
+    fn customer_ids_round_trip_through_json() {
+        for (id, json) in [(CustomerId::from(42), "42"), (CustomerId::parse("BK-7F3A").unwrap(), "\"BK-7F3A\"")] {
+            assert_eq!(serde_json::to_string(&id).unwrap(), json);
+            assert_eq!(serde_json::from_str::<CustomerId>(json).unwrap(), id);
+            assert_eq!(CustomerId::parse(&id.to_string()), Some(id));
+        }
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Seeded {
+        #[serde(with = "seed_text")]
+        seed: u64,
+        #[serde(default, with = "seed_text::option")]
+        base_seed: Option<u64>,
+    }
+
+    // Seeds past 2^53 go out as strings so JavaScript keeps every digit
+    #[test]
+    fn seeds_round_trip_as_decimal_text() {
+        let seeded = Seeded { seed: u64::MAX, base_seed: Some(9_007_199_254_740_993) };
+        let json = serde_json::to_string(&seeded).unwrap();
+        assert_eq!(json, r#"{"seed":"18446744073709551615","base_seed":"9007199254740993"}"#);
+        assert_eq!(serde_json::from_str::<Seeded>(&json).unwrap(), seeded);
+        assert_eq!(serde_json::from_str::<Seeded>(r#"{"seed":7,"base_seed":null}"#).unwrap(), Seeded { seed: 7, base_seed: None });
+        assert!(serde_json::from_str::<Seeded>(r#"{"seed":"lucky"}"#).is_err());
+    }
 }
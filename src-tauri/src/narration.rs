@@ -0,0 +1,132 @@
+use crate::errors::{AppError, Result};
+use crate::models::{CustomerConfig, SeatConfig, SimulationEvent};
+use crate::session::{SessionRun, SessionStore};
+use std::collections::{BTreeMap, HashMap};
+use tauri::{State, Window};
+
+#[derive(Clone, Copy)]
+enum Locale {
+    En,
+    Zh,
+}
+
+impl Locale {
+    fn from_name(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "en" | "en-us" | "en-gb" => Ok(Locale::En),
+            "zh" | "zh-cn" | "zh-tw" => Ok(Locale::Zh),
+            _ => Err(AppError::ValidationError(format!("Unsupported narration locale: {}", name))),
+        }
+    }
+}
+
+// "HH:MM" the run's time 0 corresponds to
+fn parse_clock(text: &str) -> Result<u64> {
+    let invalid = || AppError::ValidationError(format!("Invalid start time '{}', expected HH:MM", text));
+    let (h, m) = text.trim().split_once(':').ok_or_else(invalid)?;
+    let (h, m): (u64, u64) = (h.parse().map_err(|_| invalid())?, m.parse().map_err(|_| invalid())?);
+    if h > 23 || m > 59 { return Err(invalid()); }
+    Ok(h * 60 + m)
+}
+
+fn clock(start: u64, t: u64) -> String {
+    let minutes = (start + t) % (24 * 60);
+    format!("{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+// Seat labels as shown on the floor plan, e.g. "T2" or "S01, S02"
+fn seat_names(ids: &str, seats: &[SeatConfig]) -> String {
+    ids.split(',')
+        .map(|id| id.trim())
+        .map(|id| seats.iter().find(|s| s.id == id).and_then(|s| s.label.clone()).unwrap_or_else(|| id.to_string()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn party(locale: Locale, customer: &CustomerConfig) -> String {
+    // A passthrough name column reads better than the id
+    if let Some(name) = customer.extra.get("name").filter(|n| !n.is_empty()) {
+        return match (locale, customer.party_size) {
+            (Locale::En, 1) => name.clone(),
+            (Locale::En, n) => format!("the {} party of {}", name, n),
+            (Locale::Zh, n) => format!("{}（{}人）", name, n),
+        };
+    }
+    match (locale, customer.party_size) {
+        (Locale::En, 1) => format!("guest {}", customer.family_id),
+        (Locale::En, n) => format!("party {} of {}", customer.family_id, n),
+        (Locale::Zh, n) => format!("{}号顾客（{}人）", customer.family_id, n),
+    }
+}
+
+// One sentence per event worth telling; None for bookkeeping events
+fn sentence(locale: Locale, e: &SimulationEvent, customer: &CustomerConfig, waited: Option<u64>, seats: &[SeatConfig]) -> Option<String> {
+    let who = party(locale, customer);
+    let at = e.seat_id.as_deref().map(|ids| seat_names(ids, seats)).unwrap_or_default();
+    let text = match (locale, e.type_.as_str()) {
+        (Locale::En, "WAITING") => format!("{} arrived and joined the queue", capitalize(&who)),
+        (Locale::En, "SEATED") => match waited {
+            Some(w) if w > 0 => format!("{} was seated at {} after waiting {} minute{}", capitalize(&who), at, w, if w == 1 { "" } else { "s" }),
+            _ => format!("{} was seated at {} on arrival", capitalize(&who), at),
+        },
+        (Locale::En, "LEFT") => format!("{} left {}", capitalize(&who), at),
+        (Locale::En, "RESOURCE_DELIVERED") => format!("Baby chairs were brought to {} at {}", who, at),
+        (Locale::En, "ERROR") => format!("{} could not be served", capitalize(&who)),
+        (Locale::En, "DEADLOCK_DETECTED") => format!("{} was caught in a circular wait", capitalize(&who)),
+
+        (Locale::Zh, "WAITING") => format!("{}到店，开始排队", who),
+        (Locale::Zh, "SEATED") => match waited {
+            Some(w) if w > 0 => format!("{}等待{}分钟后入座{}", who, w, at),
+            _ => format!("{}到店即入座{}", who, at),
+        },
+        (Locale::Zh, "LEFT") => format!("{}离开{}", who, at),
+        (Locale::Zh, "RESOURCE_DELIVERED") => format!("为{}送来儿童座椅（{}）", who, at),
+        (Locale::Zh, "ERROR") => format!("{}未能得到服务", who),
+        (Locale::Zh, "DEADLOCK_DETECTED") => format!("{}陷入循环等待", who),
+        _ => return None,
+    };
+    Some(text)
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default()
+}
+
+// The run as prose, one paragraph per minute that had something to tell
+pub fn narrate(run: &SessionRun, locale: &str, start_time: &str) -> Result<String> {
+    let locale = Locale::from_name(locale)?;
+    let start = parse_clock(start_time)?;
+
+    let mut events: Vec<&SimulationEvent> = run.frames.iter().flat_map(|f| f.events.iter()).collect();
+    events.sort_by_key(|e| (e.timestamp, e.sequence));
+
+    let mut arrivals: HashMap<u32, u64> = HashMap::new();
+    let mut minutes: BTreeMap<u64, Vec<String>> = BTreeMap::new();
+    for e in events {
+        if e.type_ == "ARRIVAL" {
+            arrivals.insert(e.family_id, e.timestamp);
+            continue;
+        }
+        let Some(customer) = run.customers.iter().find(|c| c.family_id == e.family_id) else { continue };
+        let waited = arrivals.get(&e.family_id).map(|a| e.timestamp.saturating_sub(*a));
+        if let Some(text) = sentence(locale, e, customer, waited, &run.seats_config) {
+            minutes.entry(e.timestamp).or_default().push(text);
+        }
+    }
+
+    let paragraphs: Vec<String> = minutes.into_iter().map(|(t, sentences)| match locale {
+        Locale::En => format!("At {}: {}.", clock(start, t), sentences.join(". ")),
+        Locale::Zh => format!("{}，{}。", clock(start, t), sentences.join("；")),
+    }).collect();
+    Ok(paragraphs.join("\n\n"))
+}
+
+// Narrative version of the event log for written reports
+#[tauri::command]
+pub fn export_narration(session_id: Option<String>, locale: Option<String>, start_time: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<String> {
+    let session_id = sessions.resolve(session_id, &window);
+    let locale = locale.unwrap_or_else(|| "en".to_string());
+    let start_time = start_time.unwrap_or_else(|| "00:00".to_string());
+    sessions.with_session(&session_id, |run| narrate(run, &locale, &start_time))?
+}
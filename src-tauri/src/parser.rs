@@ -1,71 +1,622 @@
-use crate::models::CustomerConfig;
+use crate::models::{ColumnRef, CsvParseWarning, CustomerConfig, CustomerId, ParserOptions};
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+
+// Columns the parser understands, by position; anything after them is passed through
+pub const KNOWN_COLUMNS: usize = 10;
+
+// Name of a passthrough column: its header, or "column_<n>" (1-based) without one
+pub(crate) fn extra_column_name(header: &[String], index: usize) -> String {
+    header.get(index)
+        .filter(|name| !name.is_empty())
+        .cloned()
+        .unwrap_or_else(|| format!("column_{}", index + 1))
+}
+
+// Whether column `index` carries data the simulation doesn't read. With a header that is
+// a cell naming neither a known column nor one of the optional named columns below;
+// without one, anything after the known columns.
+pub(crate) fn is_passthrough_column(header: &[String], index: usize) -> bool {
+    if header.is_empty() {
+        return index >= KNOWN_COLUMNS;
+    }
+    header.get(index).is_none_or(|name| {
+        header_column(name).is_none() && ![FAMILY_ID_COLUMN, MAX_WAIT_COLUMN, PRIORITY_COLUMN].contains(&normalize_header(name).as_str())
+    })
+}
+
+// Optional header-named column grouping several rows into one family
+pub const FAMILY_ID_COLUMN: &str = "family_id";
+
+// Optional header-named column: minutes the party waits before leaving unserved
+pub const MAX_WAIT_COLUMN: &str = "max_wait_time";
+
+// Optional header-named column: queue priority, a number or a label like VIP
+pub const PRIORITY_COLUMN: &str = "priority";
+
+// First family id handed to non-numeric customer ids
+pub const CODE_FAMILY_ID_BASE: u32 = 1_000_000;
+
+// Family ids handed to pre-seated rows (id -1): the base plus the row's line index
+pub const PRE_SEATED_IDS: std::ops::Range<u32> = 1000..2000;
+
+// Maps roster ids to the engine's numeric family ids. Numeric ids map to themselves;
+// each distinct code gets the next id from CODE_FAMILY_ID_BASE, so the same code always
+// lands on the same family within one namespace. Numbers in the ranges the parser hands
+// out itself are refused, so a roster id can never land on another party's family.
+#[derive(Default)]
+pub struct IdNamespace {
+    codes: HashMap<String, u32>,
+}
+
+impl IdNamespace {
+    pub fn family_id(&mut self, id: &CustomerId) -> Result<u32, String> {
+        if let Some(n) = id.number() {
+            return match n {
+                n if PRE_SEATED_IDS.contains(&n) => Err(format!("is reserved for pre-seated parties ({}-{})", PRE_SEATED_IDS.start, PRE_SEATED_IDS.end - 1)),
+                n if n >= CODE_FAMILY_ID_BASE => Err(format!("is reserved for booking codes (from {})", CODE_FAMILY_ID_BASE)),
+                n => Ok(n),
+            };
+        }
+        let next = CODE_FAMILY_ID_BASE + self.codes.len() as u32;
+        Ok(*self.codes.entry(id.to_string()).or_insert(next))
+    }
+}
+
+// How the parser treats rows and values it can't read
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ParseMode {
+    #[default]
+    Lenient, // skip bad rows, default bad values, and report each as a warning
+    Strict,  // fail on the first bad row or value
+}
+
+// Parsed roster plus everything lenient parsing skipped or defaulted
+pub struct ParsedRoster {
+    pub customers: Vec<CustomerConfig>,
+    pub warnings: Vec<CsvParseWarning>,
+}
+
+// Collects problems as warnings, or turns the first one into an error in strict mode
+struct RowIssues {
+    mode: ParseMode,
+    warnings: Vec<CsvParseWarning>,
+}
+
+impl RowIssues {
+    fn report(&mut self, line: usize, column: Option<usize>, text: &str, reason: String, skipped: bool) -> Result<(), Box<dyn Error>> {
+        let warning = CsvParseWarning {
+            line: line as u32,
+            column: column.map(|c| JSON_FIELDS[c][0].to_string()),
+            text: text.to_string(),
+            reason,
+            skipped,
+        };
+        if self.mode == ParseMode::Strict {
+            let column = warning.column.as_deref().map(|c| format!(", column {}", c)).unwrap_or_default();
+            return Err(format!("line {}{}: '{}' {}", warning.line, column, warning.text, warning.reason).into());
+        }
+        self.warnings.push(warning);
+        Ok(())
+    }
+
+    // Value of an optional column; empty or missing gives the default, unreadable text
+    // gives the default with a warning
+    fn value<T: FromStr + Display>(&mut self, line: usize, column: usize, text: Option<&str>, default: T) -> Result<T, Box<dyn Error>> {
+        let text = text.map(str::trim).unwrap_or("");
+        if text.is_empty() {
+            return Ok(default);
+        }
+        match text.parse() {
+            Ok(value) => Ok(value),
+            Err(_) => {
+                self.report(line, Some(column), text, format!("is not a valid {}; using {}", JSON_FIELDS[column][0], default), false)?;
+                Ok(default)
+            }
+        }
+    }
+}
+
+// Count column text as a number ("true" is one, "false" none)
+fn count_text(text: Option<&str>) -> Option<String> {
+    text.map(|t| match t.trim().to_lowercase().as_str() {
+        "true" => "1".to_string(),
+        "false" => "0".to_string(),
+        other => other.to_string(),
+    })
+}
 
 pub fn parse_customers(csv_content: &str) -> Result<Vec<CustomerConfig>, Box<dyn Error>> {
-    let mut customers = Vec::new();
+    Ok(parse_customers_with(csv_content, ParseMode::Lenient)?.customers)
+}
+
+pub fn parse_customers_with(csv_content: &str, mode: ParseMode) -> Result<ParsedRoster, Box<dyn Error>> {
+    // Columns in another order, named in another language or mixed with other columns are
+    // mapped by header name
+    if csv_content.lines().next().is_some_and(is_reordered_header) {
+        let options = ParserOptions { has_header: Some(true), ..Default::default() };
+        // Parsed as is from here: a header naming one column twice keeps looking reordered
+        return parse_standard(&normalize_csv(csv_content, &options)?, mode);
+    }
+    parse_standard(csv_content, mode)
+}
+
+// Parse a roster whose known columns are in their standard places
+fn parse_standard(csv_content: &str, mode: ParseMode) -> Result<ParsedRoster, Box<dyn Error>> {
+    let mut rows = Vec::new();
+    let mut header: Vec<String> = Vec::new();
+    let mut ids = IdNamespace::default();
+    let mut issues = RowIssues { mode, warnings: Vec::new() };
     
     for (i, line) in csv_content.lines().enumerate() {
         let line = line.trim();
-        if line.is_empty() || (i == 0 && line.to_lowercase().starts_with("id")) {
+        let line_no = i + 1;
+        if i == 0 && is_header_line(line) {
+            header = line.split(',').map(|h| h.trim().to_string()).collect();
+            continue;
+        }
+        if line.is_empty() {
             continue;
         }
 
         let parts: Vec<&str> = line.split(',').collect();
-        if parts.len() < 2 { continue; } // Lenient check, as long as basic fields exist
+        if parts.len() < 2 {
+            issues.report(line_no, None, line, "has no arrival time; row skipped".to_string(), true)?;
+            continue;
+        }
 
-        let id_raw = parts[0].trim().parse::<i32>().unwrap_or(0);
-        if id_raw == 0 { continue; }
-        
-        // If ID is -1, we assign a unique ID starting from 1000 
-        // to ensure they are positive (for frontend/map compatibility) 
-        // but distinct from normal IDs.
-        let id = if id_raw < 0 {
-            1000 + i as u32
-        } else {
-            id_raw as u32
+        let id_text = parts[0].trim();
+        let (id, mut family_id) = match id_text.parse::<i32>() {
+            Ok(0) => {
+                issues.report(line_no, Some(0), id_text, "is not a customer id; row skipped".to_string(), true)?;
+                continue;
+            }
+            // If ID is -1, we assign a unique ID starting from 1000 
+            // to ensure they are positive (for frontend/map compatibility) 
+            // but distinct from normal IDs.
+            Ok(n) if n < 0 => (CustomerId::from(PRE_SEATED_IDS.start + i as u32), PRE_SEATED_IDS.start + i as u32),
+            // Positive numbers and booking codes
+            _ => match CustomerId::parse(id_text) {
+                Some(id) => match ids.family_id(&id) {
+                    Ok(family_id) => (id, family_id),
+                    Err(reason) => {
+                        issues.report(line_no, Some(0), id_text, format!("{}; row skipped", reason), true)?;
+                        continue;
+                    }
+                },
+                None => {
+                    issues.report(line_no, Some(0), id_text, "is not a customer id; row skipped".to_string(), true)?;
+                    continue;
+                }
+            },
         };
 
-        let arrival_time_raw = parts.get(1).and_then(|s| s.trim().parse::<i64>().ok()).unwrap_or(0);
+        let arrival_time_raw: i64 = issues.value(line_no, 1, parts.get(1).copied(), 0)?;
         
         // Skip parts[2] (original type field)
-        let party_size = parts.get(3).and_then(|s| s.trim().parse().ok()).unwrap_or(1);
-        
-        let baby_str = parts.get(4).unwrap_or(&"0").trim().to_lowercase();
-        let baby_chair_count = if baby_str == "true" { 1 } else { baby_str.parse().unwrap_or(0) };
-
-        let wheel_str = parts.get(5).unwrap_or(&"0").trim().to_lowercase();
-        let wheelchair_count = if wheel_str == "true" { 1 } else { wheel_str.parse().unwrap_or(0) };
-
-        let est_dining_time = parts.get(6).and_then(|s| s.trim().parse::<i64>().ok()).unwrap_or(60);
+        let party_size = issues.value(line_no, 3, parts.get(3).copied(), 1)?;
+        let baby_chair_count = issues.value(line_no, 4, count_text(parts.get(4).copied()).as_deref(), 0)?;
+        let wheelchair_count = issues.value(line_no, 5, count_text(parts.get(5).copied()).as_deref(), 0)?;
+        let est_dining_time = issues.value(line_no, 6, parts.get(6).copied(), 60)?;
+        // Optional: preference (0..1) for a seat facing the conveyor belt
+        let conveyor_preference = issues.value(line_no, 7, parts.get(7).copied(), 0.0f64)?.clamp(0.0, 1.0);
+        // Optional: dietary flags separated by '|' or ';' (e.g. "GLUTEN_FREE|NUT_ALLERGY")
+        let dietary_flags: Vec<String> = parts.get(8)
+            .map(|s| s.split(['|', ';'])
+                .map(|f| f.trim().to_uppercase())
+                .filter(|f| !f.is_empty())
+                .collect())
+            .unwrap_or_default();
+        // Optional: party ordered ahead (food ready when seated)
+        let ordered_ahead = issues.value(line_no, 9, count_text(parts.get(9).copied()).as_deref(), 0u32)? > 0;
+        // Optional: extra columns (name, phone, notes, ...) carried through untouched
+        let mut extra: HashMap<String, String> = parts.iter().enumerate()
+            .skip(KNOWN_COLUMNS)
+            .filter(|(_, value)| !value.trim().is_empty())
+            .map(|(idx, value)| (extra_column_name(&header, idx), value.trim().to_string()))
+            .collect();
+        // Optional: rows sharing a family_id are one party (merged below)
+        let grouped = match extra.remove(FAMILY_ID_COLUMN).as_deref().and_then(CustomerId::parse) {
+            Some(family) => match ids.family_id(&family) {
+                Ok(id) => {
+                    family_id = id;
+                    true
+                }
+                Err(reason) => {
+                    issues.report(line_no, None, &family.to_string(), format!("{} as a {}; seated on its own", reason, FAMILY_ID_COLUMN), false)?;
+                    false
+                }
+            },
+            None => false,
+        };
+        let max_wait_time = match extra.remove(MAX_WAIT_COLUMN) {
+            Some(text) => match text.parse() {
+                Ok(minutes) => Some(minutes),
+                Err(_) => {
+                    issues.report(line_no, None, &text, format!("is not a valid {}; waiting without a limit", MAX_WAIT_COLUMN), false)?;
+                    None
+                }
+            },
+            None => None,
+        };
+        let priority = match extra.remove(PRIORITY_COLUMN) {
+            Some(text) => match priority_level(&text) {
+                Some(level) => level,
+                None => {
+                    issues.report(line_no, None, &text, format!("is not a valid {}; queueing as a walk-in", PRIORITY_COLUMN), false)?;
+                    0
+                }
+            },
+            None => 0,
+        };
         // If arrival_time is -1, we treat it as pre-occupied.
         // We map it to 0 for the struct to avoid overflow in the UI,
         // but we'll handle the priority in simulation.rs by sorting.
         let arrival_time = if arrival_time_raw < 0 { 0 } else { arrival_time_raw as u64 };
 
         // 🔥 Auto-determine type: ensure type always has a value
-        let type_ = if wheelchair_count > 0 {
-            "WHEELCHAIR".to_string()
-        } else if baby_chair_count > 0 {
-            "WITH_BABY".to_string()
-        } else if party_size > 4 {
-            "LARGE_GROUP".to_string()
-        } else if party_size > 1 {
-            "FAMILY".to_string()
-        } else {
-            "INDIVIDUAL".to_string()
-        };
+        let type_ = customer_type(party_size, baby_chair_count, wheelchair_count);
 
-        customers.push(CustomerConfig {
+        let origin = RowOrigin { grouped, pre_seated: arrival_time_raw < 0 };
+        rows.push((origin, CustomerConfig {
             id,
-            family_id: id,
+            family_id,
             arrival_time,
             type_, // Use the auto-determined result here
             party_size,
             baby_chair_count,
             wheelchair_count,
-            est_dining_time: est_dining_time as u64,
+            est_dining_time,
+            conveyor_preference,
+            dietary_flags,
+            ordered_ahead,
+            extra,
+            max_wait_time,
+            priority,
+        }));
+    }
+
+    Ok(ParsedRoster { customers: merge_family_rows(rows), warnings: issues.warnings })
+}
+
+// Priority column value: a level (0 is a walk-in) or a flag naming why the party goes first
+fn priority_level(text: &str) -> Option<u32> {
+    match text.trim().to_lowercase().as_str() {
+        "" | "no" | "false" | "normal" | "walk_in" | "walk-in" => Some(0),
+        "vip" | "elderly" | "senior" | "disability" | "disabled" | "yes" | "true" => Some(1),
+        level => level.parse().ok(),
+    }
+}
+
+// How a parsed row got its family id and arrival time
+struct RowOrigin {
+    grouped: bool,    // family id from the family_id column, not the row's own id
+    pre_seated: bool, // arrival time -1: already seated when the simulation starts
+}
+
+// Fold rows of the same family into one seating request: everyone is seated together
+// once the last member has arrived, for as long as the longest stay, and leaves together.
+// A family with a member already seated (arrival -1) is seated from the start. Only rows
+// grouped by the family_id column are merged, and only with each other: a row whose own
+// id happens to equal a family's id stays apart, as do other repeated ids.
+fn merge_family_rows(rows: Vec<(RowOrigin, CustomerConfig)>) -> Vec<CustomerConfig> {
+    let mut families: Vec<(RowOrigin, CustomerConfig)> = Vec::new();
+    for (origin, row) in rows {
+        let existing = families.iter_mut()
+            .find(|(o, f)| origin.grouped && o.grouped && f.family_id == row.family_id);
+        let Some((family_origin, family)) = existing else {
+            families.push((origin, row));
+            continue;
+        };
+        family_origin.pre_seated |= origin.pre_seated;
+        family.arrival_time = if family_origin.pre_seated { 0 } else { family.arrival_time.max(row.arrival_time) };
+        family.party_size += row.party_size;
+        family.baby_chair_count += row.baby_chair_count;
+        family.wheelchair_count += row.wheelchair_count;
+        family.est_dining_time = family.est_dining_time.max(row.est_dining_time);
+        family.conveyor_preference = family.conveyor_preference.max(row.conveyor_preference);
+        for flag in row.dietary_flags {
+            if !family.dietary_flags.contains(&flag) {
+                family.dietary_flags.push(flag);
+            }
+        }
+        family.ordered_ahead |= row.ordered_ahead;
+        // The family leaves once its least patient member has had enough
+        family.max_wait_time = family.max_wait_time.into_iter().chain(row.max_wait_time).min();
+        family.priority = family.priority.max(row.priority);
+        for (column, value) in row.extra {
+            family.extra.entry(column).or_insert(value);
+        }
+        family.type_ = customer_type(family.party_size, family.baby_chair_count, family.wheelchair_count);
+    }
+    families.into_iter().map(|(_, family)| family).collect()
+}
+
+// Names a JSON roster may use for each known CSV column, in column order; the first
+// is the CSV column's own name
+const JSON_FIELDS: [&[&str]; KNOWN_COLUMNS] = [
+    &["id"],
+    &["arrival_time", "arrivalTime"],
+    &["type"],
+    &["party_size", "partySize"],
+    &["baby_chair", "baby_chair_count", "babyChairCount"],
+    &["wheel_chair", "wheelchair_count", "wheelchairCount"],
+    &["est_dining_time", "estDiningTime"],
+    &["conveyor_preference", "conveyorPreference"],
+    &["dietary_flags", "dietaryFlags"],
+    &["ordered_ahead", "orderedAhead"],
+];
+
+// Header names recognized for each known CSV column, in column order: the standard
+// names, common English synonyms and the Chinese headers (traditional and simplified)
+// used in many scenario spreadsheets. Compared after normalize_header.
+const HEADER_NAMES: [&[&str]; KNOWN_COLUMNS] = [
+    &["id", "customer_id", "family", "no", "編號", "编号", "序號", "序号"],
+    &["arrival_time", "arrival", "arrive", "arrived", "time_in", "到店時間", "到店时间", "抵達時間", "抵达时间"],
+    &["type", "customer_type", "類型", "类型"],
+    &["party_size", "size", "party", "guests", "people", "pax", "covers", "人數", "人数"],
+    &["baby_chair", "baby_chairs", "baby_chair_count", "babies", "high_chair", "high_chairs", "嬰兒椅", "婴儿椅"],
+    &["wheel_chair", "wheelchair", "wheelchairs", "wheelchair_count", "輪椅", "轮椅"],
+    &["est_dining_time", "dining_time", "duration", "stay", "用餐時間", "用餐时间"],
+    &["conveyor_preference", "conveyor", "迴轉台偏好", "回转台偏好"],
+    &["dietary_flags", "dietary", "diet", "飲食限制", "饮食限制"],
+    &["ordered_ahead", "preorder", "pre_order", "預先點餐", "预先点餐"],
+];
+
+// Known column names a header line needs besides a leading "id"
+const HEADER_MATCHES: usize = 2;
+
+// Lowercase, with spaces and dashes as underscores ("Party Size" -> "party_size")
+fn normalize_header(name: &str) -> String {
+    name.trim().trim_start_matches('\u{feff}').to_lowercase().replace([' ', '-'], "_")
+}
+
+// Known column a header cell names, if any (also JSON key spellings like partySize)
+fn header_column(name: &str) -> Option<usize> {
+    let name = normalize_header(name);
+    HEADER_NAMES.iter().position(|names| names.contains(&name.as_str()))
+        .or_else(|| JSON_FIELDS.iter().position(|names| names.iter().any(|n| n.eq_ignore_ascii_case(&name))))
+}
+
+// Whether the first line of a roster is its header: it starts with "id" (the original
+// rule), or at least HEADER_MATCHES of its cells name different known columns. One
+// match isn't enough, as data can look like a name ("FAMILY" in the type column).
+pub fn is_header_line(line: &str) -> bool {
+    let line = normalize_header(line);
+    if line.starts_with("id") {
+        return true;
+    }
+    let columns: HashSet<usize> = line.split([',', ';', '\t']).filter_map(header_column).collect();
+    columns.len() >= HEADER_MATCHES
+}
+
+// A recognized header whose cells don't all sit where the parser reads them by position:
+// a known column away from its standard place, or another name (notes, family_id, ...)
+// in a known column's place. Such rosters are mapped by header name before parsing, so
+// only columns with unrecognized names are passed through.
+fn is_reordered_header(line: &str) -> bool {
+    is_header_line(line) && line.split(',').enumerate().any(|(i, cell)| match header_column(cell) {
+        Some(c) => c != i,
+        None => i < KNOWN_COLUMNS,
+    })
+}
+
+// One JSON value as CSV cell text. Lists become '|'-separated (dietary flags);
+// commas inside text would split the cell, so they become ';'.
+fn cell_text(value: &serde_json::Value) -> Option<String> {
+    let text = match value {
+        serde_json::Value::Null => return None,
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(items) => items.iter().filter_map(cell_text).collect::<Vec<_>>().join("|"),
+        serde_json::Value::Object(_) => return None,
+        other => other.to_string(),
+    };
+    Some(text.replace(',', ";"))
+}
+
+// The same roster as CSV from a JSON list of customer objects (or {"customers": [...]}),
+// snake_case or camelCase keys. Unknown scalar keys become extra columns, familyId the
+// family_id column.
+fn json_roster_csv(json: &str) -> Result<String, Box<dyn Error>> {
+    let value: serde_json::Value = serde_json::from_str(json)?;
+    let rows = match &value {
+        serde_json::Value::Array(rows) => rows,
+        serde_json::Value::Object(map) => match map.get("customers") {
+            Some(serde_json::Value::Array(rows)) => rows,
+            _ => return Err("expected a list of customers or an object with a \"customers\" list".into()),
+        },
+        _ => return Err("expected a list of customers".into()),
+    };
+
+    let known: Vec<&str> = JSON_FIELDS.iter().flat_map(|names| names.iter().copied()).collect();
+    let column_of = |key: &str| match key {
+        "familyId" => FAMILY_ID_COLUMN.to_string(),
+        "maxWaitTime" => MAX_WAIT_COLUMN.to_string(),
+        _ => key.to_string(),
+    };
+    let mut extra_columns: Vec<String> = Vec::new();
+    for row in rows {
+        let Some(object) = row.as_object() else { return Err("every customer must be a JSON object".into()) };
+        for (key, v) in object {
+            let column = column_of(key);
+            if !known.contains(&key.as_str()) && cell_text(v).is_some() && !extra_columns.contains(&column) {
+                extra_columns.push(column);
+            }
+        }
+    }
+
+    let mut header: Vec<String> = JSON_FIELDS.iter().map(|names| names[0].to_string()).collect();
+    header.extend(extra_columns.iter().cloned());
+    let mut lines = vec![header.join(",")];
+    for object in rows.iter().filter_map(|row| row.as_object()) {
+        let lookup = |names: &[&str]| names.iter().find_map(|n| object.get(*n).and_then(cell_text)).unwrap_or_default();
+        let mut cells: Vec<String> = JSON_FIELDS.iter().map(|names| lookup(names)).collect();
+        for column in &extra_columns {
+            let keys: Vec<&str> = object.keys().map(|k| k.as_str()).filter(|k| column_of(k) == *column).collect();
+            cells.push(lookup(&keys));
+        }
+        lines.push(cells.join(","));
+    }
+    Ok(lines.join("\n") + "\n")
+}
+
+// Line numbers in its warnings count the header, so line 2 is the first customer
+pub fn parse_customers_json(json: &str, mode: ParseMode) -> Result<ParsedRoster, Box<dyn Error>> {
+    parse_customers_with(&json_roster_csv(json)?, mode)
+}
+
+// A roster in another layout (delimiter, header, column order) rewritten as standard
+// roster CSV with a header, so the usual parser reads it. File columns no standard
+// column maps to are kept as extra columns under their header names.
+pub fn normalize_csv(content: &str, options: &ParserOptions) -> Result<String, Box<dyn Error>> {
+    let standard: Vec<&str> = JSON_FIELDS.iter().map(|names| names[0]).collect();
+    if let Some(unknown) = options.columns.keys().find(|k| !standard.contains(&k.as_str())) {
+        return Err(format!("Unknown column '{}'; use one of {}", unknown, standard.join(", ")).into());
+    }
+
+    let delimiter = options.delimiter.unwrap_or(',');
+    // Blank lines stay, so warnings point at the file's lines (one further down in a
+    // file without a header, which gains one)
+    let rows: Vec<Vec<String>> = content.lines()
+        .map(|line| match line.trim() {
+            "" => Vec::new(),
+            line => line.split(delimiter).map(|cell| cell.trim().replace(',', ";")).collect(),
+        })
+        .collect();
+    let has_header = options.has_header
+        .unwrap_or_else(|| content.lines().next().is_some_and(is_header_line));
+    let header: &[String] = if has_header { rows.first().map(Vec::as_slice).unwrap_or_default() } else { &[] };
+    let find = |name: &str| header.iter().position(|h| normalize_header(h) == normalize_header(name));
+
+    let mapped: Vec<usize> = options.columns.values().filter_map(|c| match c {
+        ColumnRef::Index(i) => Some(*i),
+        ColumnRef::Name(name) => find(name),
+    }).collect();
+    let mut sources = Vec::new();
+    for (i, names) in JSON_FIELDS.iter().enumerate() {
+        sources.push(match options.columns.get(names[0]) {
+            Some(ColumnRef::Index(n)) => Some(*n),
+            Some(ColumnRef::Name(name)) => Some(find(name).ok_or_else(|| format!("No column named '{}' in the header", name))?),
+            None if has_header => header.iter().position(|h| header_column(h) == Some(i)),
+            None => Some(i).filter(|i| !mapped.contains(i)),
         });
     }
 
-    Ok(customers)
-}
\ No newline at end of file
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let extras: Vec<usize> = (0..width).filter(|i| !sources.contains(&Some(*i))).collect();
+    let mut out_header: Vec<String> = standard.iter().map(|s| s.to_string()).collect();
+    out_header.extend(extras.iter().map(|&i| extra_column_name(header, i)));
+
+    let mut lines = vec![out_header.join(",")];
+    for row in rows.iter().skip(has_header as usize) {
+        if row.is_empty() {
+            lines.push(String::new());
+            continue;
+        }
+        let cell = |i: Option<usize>| i.and_then(|i| row.get(i)).cloned().unwrap_or_default();
+        let mut cells: Vec<String> = sources.iter().map(|&i| cell(i)).collect();
+        cells.extend(extras.iter().map(|&i| cell(Some(i))));
+        lines.push(cells.join(","));
+    }
+    Ok(lines.join("\n") + "\n")
+}
+
+// Customer type derived from the party's composition
+pub fn customer_type(party_size: u32, baby_chair_count: u32, wheelchair_count: u32) -> String {
+    if wheelchair_count > 0 {
+        "WHEELCHAIR".to_string()
+    } else if baby_chair_count > 0 {
+        "WITH_BABY".to_string()
+    } else if party_size > 4 {
+        "LARGE_GROUP".to_string()
+    } else if party_size > 1 {
+        "FAMILY".to_string()
+    } else {
+        "INDIVIDUAL".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_parse_by_position_with_defaults() {
+        let customers = parse_customers("id,arrival_time,type,party_size\n1,5,,2,true,0\n2,7,,6\n").unwrap();
+        assert_eq!(customers.len(), 2);
+        assert_eq!((customers[0].arrival_time, customers[0].party_size, customers[0].baby_chair_count), (5, 2, 1));
+        assert_eq!(customers[0].type_, "WITH_BABY");
+        assert_eq!((customers[1].type_.as_str(), customers[1].est_dining_time), ("LARGE_GROUP", 60));
+    }
+
+    #[test]
+    fn booking_codes_get_family_ids_from_the_code_base() {
+        let customers = parse_customers("BK-7F3A,0,,2\n3,1,,1\nBK-9C1D,2,,4\n007,3,,1\n").unwrap();
+        let ids: Vec<(String, u32)> = customers.iter().map(|c| (c.id.to_string(), c.family_id)).collect();
+        assert_eq!(ids, [
+            ("BK-7F3A".to_string(), CODE_FAMILY_ID_BASE),
+            ("3".to_string(), 3),
+            ("BK-9C1D".to_string(), CODE_FAMILY_ID_BASE + 1),
+            ("007".to_string(), CODE_FAMILY_ID_BASE + 2),
+        ]);
+    }
+
+    // Numbers in the ranges the parser hands out itself would collide with another party
+    #[test]
+    fn reserved_ids_are_skipped_or_refused() {
+        let parsed = parse_customers_with("1000,0,,1\n2000000,0,,1\n4,0,,1\n", ParseMode::Lenient).unwrap();
+        assert_eq!(parsed.customers.iter().map(|c| c.family_id).collect::<Vec<_>>(), [4]);
+        assert!(parsed.warnings.iter().all(|w| w.skipped && w.column.as_deref() == Some("id")));
+        assert!(parse_customers_with("1000,0,,1\n", ParseMode::Strict).is_err());
+    }
+
+    #[test]
+    fn pre_seated_rows_arrive_at_zero_with_their_own_family() {
+        let customers = parse_customers("1,3,,1\n-1,-1,,2\n").unwrap();
+        assert_eq!((customers[1].family_id, customers[1].arrival_time), (PRE_SEATED_IDS.start + 1, 0));
+    }
+
+    #[test]
+    fn bad_values_default_with_a_warning_or_fail_strictly() {
+        let parsed = parse_customers_with("1,soon,,2\n", ParseMode::Lenient).unwrap();
+        assert_eq!(parsed.customers[0].arrival_time, 0);
+        assert_eq!(parsed.warnings.len(), 1);
+        assert_eq!((parsed.warnings[0].line, parsed.warnings[0].column.as_deref(), parsed.warnings[0].skipped), (1, Some("arrival_time"), false));
+        let error = parse_customers_with("1,soon,,2\n", ParseMode::Strict).err().unwrap();
+        assert_eq!(error.to_string(), "line 1, column arrival_time: 'soon' is not a valid arrival_time; using 0");
+    }
+
+    // The family arrives with its last member, stays as long as the longest and leaves
+    // once its least patient member has had enough
+    #[test]
+    fn family_rows_merge_into_one_party() {
+        let csv = "id,arrival_time,type,party_size,baby_chair,wheel_chair,est_dining_time,conveyor_preference,dietary_flags,ordered_ahead,family_id,max_wait_time\n\
+                   1,2,,2,0,0,30,,,,F1,20\n\
+                   2,5,,1,1,0,45,,,,F1,10\n\
+                   3,4,,1,0,0,20,,,,,\n";
+        let customers = parse_customers(csv).unwrap();
+        assert_eq!(customers.len(), 2);
+        let family = &customers[0];
+        assert_eq!(family.family_id, CODE_FAMILY_ID_BASE);
+        assert_eq!((family.arrival_time, family.party_size, family.baby_chair_count, family.est_dining_time), (5, 3, 1, 45));
+        assert_eq!((family.max_wait_time, family.type_.as_str()), (Some(10), "WITH_BABY"));
+        assert_eq!(customers[1].family_id, 3);
+    }
+
+    #[test]
+    fn reordered_and_translated_headers_map_by_name() {
+        let customers = parse_customers("人數,到店時間,編號,notes\n3,12,7,window seat\n").unwrap();
+        assert_eq!((customers[0].family_id, customers[0].arrival_time, customers[0].party_size), (7, 12, 3));
+        assert_eq!(customers[0].extra.get("notes").map(String::as_str), Some("window seat"));
+    }
+
+    #[test]
+    fn priority_flags_and_levels() {
+        assert_eq!(priority_level("VIP"), Some(1));
+        assert_eq!(priority_level(" walk-in "), Some(0));
+        assert_eq!(priority_level("3"), Some(3));
+        assert_eq!(priority_level("soon"), None);
+    }
+}
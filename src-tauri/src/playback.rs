@@ -0,0 +1,186 @@
+use crate::access;
+use crate::errors::{AppError, Result};
+use crate::models::{PlaybackStatus, PlaybackTick};
+use crate::announcements;
+use crate::session::SessionStore;
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State, Window};
+
+// Event every view listens to so they all follow one playhead
+pub const PLAYBACK_EVENT: &str = "playback-frame";
+
+// Same pacing as the frontend player: every tick advances speed * 0.1 time units
+const TICK_MS: u64 = 100;
+const TIME_PER_TICK: f64 = 0.1;
+const SPEED_PRESETS: [f64; 5] = [0.25, 0.5, 1.0, 2.0, 4.0];
+
+struct Playhead {
+    current_time: f64,
+    playing: bool,
+    speed: f64,
+    // Bumped on every play so a stale timer thread knows to stop
+    generation: u64,
+}
+
+impl Default for Playhead {
+    fn default() -> Self {
+        Playhead { current_time: 0.0, playing: false, speed: 1.0, generation: 0 }
+    }
+}
+
+#[derive(Default)]
+pub struct PlaybackStore {
+    playheads: Mutex<HashMap<String, Playhead>>,
+}
+
+impl PlaybackStore {
+    // Update a session's playhead and return a snapshot of it
+    fn update(&self, session_id: &str, max_time: f64, f: impl FnOnce(&mut Playhead)) -> PlaybackStatus {
+        let mut playheads = self.playheads.lock().unwrap();
+        let playhead = playheads.entry(session_id.to_string()).or_default();
+        f(playhead);
+        playhead.current_time = playhead.current_time.clamp(0.0, max_time);
+        PlaybackStatus {
+            session_id: session_id.to_string(),
+            current_time: playhead.current_time,
+            playing: playhead.playing,
+            speed: playhead.speed,
+            max_time,
+        }
+    }
+}
+
+fn max_time(sessions: &SessionStore, session_id: &str) -> Result<f64> {
+    sessions.with_session(session_id, |run| run.frames.last().map(|f| f.timestamp).unwrap_or(0) as f64)
+}
+
+// Emit the frame under the playhead to every window
+fn emit_frame(app: &AppHandle, sessions: &SessionStore, status: PlaybackStatus) -> Result<()> {
+    let (frame_index, frame) = sessions.with_session(&status.session_id, |run| {
+        let idx = run.frames.partition_point(|f| (f.timestamp as f64) <= status.current_time).saturating_sub(1);
+        (idx, run.frames.get(idx).cloned())
+    })?;
+    app.emit(PLAYBACK_EVENT, PlaybackTick { status, frame_index, frame })
+        .map_err(|e| AppError::SessionError(format!("Failed to emit playback frame: {}", e)))
+}
+
+// Timer thread for one play; exits on pause, on a newer play, at the end or on app shutdown
+fn run_timer(app: AppHandle, session_id: String, generation: u64) {
+    loop {
+        thread::sleep(Duration::from_millis(TICK_MS));
+
+        let sessions = app.state::<SessionStore>();
+        if sessions.cancel_flag().load(Ordering::SeqCst) { return; }
+        let Ok(max_time) = max_time(&sessions, &session_id) else { return };
+
+        let mut current = true;
+        let mut from = 0.0;
+        let status = app.state::<PlaybackStore>().update(&session_id, max_time, |p| {
+            if !p.playing || p.generation != generation {
+                current = false;
+                return;
+            }
+            from = p.current_time;
+            p.current_time += p.speed * TIME_PER_TICK;
+            if p.current_time >= max_time {
+                p.playing = false;
+            }
+        });
+        if !current { return; }
+
+        let finished = !status.playing;
+        announcements::emit_due(&app, &session_id, &sessions, from, status.current_time);
+        if emit_frame(&app, &sessions, status).is_err() || finished { return; }
+    }
+}
+
+#[tauri::command]
+pub fn playback_play(session_id: Option<String>, app: AppHandle, window: Window, sessions: State<'_, SessionStore>, playback: State<'_, PlaybackStore>) -> Result<PlaybackStatus> {
+    access::require_host(&window, "control playback")?;
+    let session_id = sessions.resolve(session_id, &window);
+    let max_time = max_time(&sessions, &session_id)?;
+
+    let mut generation = 0;
+    let status = playback.update(&session_id, max_time, |p| {
+        // Restart from the beginning when already at the end, like the frontend player
+        if p.current_time >= max_time {
+            p.current_time = 0.0;
+        }
+        p.playing = true;
+        p.generation += 1;
+        generation = p.generation;
+    });
+
+    let timer_app = app.clone();
+    let timer_session = session_id.clone();
+    thread::spawn(move || run_timer(timer_app, timer_session, generation));
+
+    emit_frame(&app, &sessions, status.clone())?;
+    Ok(status)
+}
+
+#[tauri::command]
+pub fn playback_pause(session_id: Option<String>, app: AppHandle, window: Window, sessions: State<'_, SessionStore>, playback: State<'_, PlaybackStore>) -> Result<PlaybackStatus> {
+    access::require_host(&window, "control playback")?;
+    let session_id = sessions.resolve(session_id, &window);
+    let status = playback.update(&session_id, max_time(&sessions, &session_id)?, |p| p.playing = false);
+    emit_frame(&app, &sessions, status.clone())?;
+    Ok(status)
+}
+
+// Move the playhead to `t`, held within the run; NaN or infinity has no place to go
+#[tauri::command]
+pub fn playback_seek(session_id: Option<String>, t: f64, app: AppHandle, window: Window, sessions: State<'_, SessionStore>, playback: State<'_, PlaybackStore>) -> Result<PlaybackStatus> {
+    access::require_host(&window, "control playback")?;
+    if !t.is_finite() {
+        return Err(AppError::ValidationError(format!("Cannot seek to {}", t)));
+    }
+    let session_id = sessions.resolve(session_id, &window);
+    let status = playback.update(&session_id, max_time(&sessions, &session_id)?, |p| p.current_time = t);
+    emit_frame(&app, &sessions, status.clone())?;
+    Ok(status)
+}
+
+// Unsupported speeds fall back to 1x, matching the frontend presets
+#[tauri::command]
+pub fn playback_set_speed(session_id: Option<String>, speed: f64, app: AppHandle, window: Window, sessions: State<'_, SessionStore>, playback: State<'_, PlaybackStore>) -> Result<PlaybackStatus> {
+    access::require_host(&window, "control playback")?;
+    let session_id = sessions.resolve(session_id, &window);
+    let speed = if SPEED_PRESETS.contains(&speed) { speed } else { 1.0 };
+    let status = playback.update(&session_id, max_time(&sessions, &session_id)?, |p| p.speed = speed);
+    emit_frame(&app, &sessions, status.clone())?;
+    Ok(status)
+}
+
+// Step mode: pause and move the playhead `steps` frames on (back if negative; one by
+// default), so the frontend can go through a run tick by tick
+#[tauri::command]
+pub fn step_simulation(session_id: Option<String>, steps: Option<i64>, app: AppHandle, window: Window, sessions: State<'_, SessionStore>, playback: State<'_, PlaybackStore>) -> Result<PlaybackStatus> {
+    access::require_host(&window, "control playback")?;
+    let session_id = sessions.resolve(session_id, &window);
+    let max_time = max_time(&sessions, &session_id)?;
+    let from = playback.update(&session_id, max_time, |p| p.playing = false).current_time;
+    let to = sessions.with_session(&session_id, |run| {
+        let current = run.frames.partition_point(|f| (f.timestamp as f64) <= from).saturating_sub(1) as i64;
+        let last = run.frames.len().saturating_sub(1) as i64;
+        let target = (current + steps.unwrap_or(1)).min(last).max(0) as usize;
+        run.frames.get(target).map_or(0.0, |f| f.timestamp as f64)
+    })?;
+
+    let status = playback.update(&session_id, max_time, |p| p.current_time = to);
+    if to > from {
+        announcements::emit_due(&app, &session_id, &sessions, from, to);
+    }
+    emit_frame(&app, &sessions, status.clone())?;
+    Ok(status)
+}
+
+#[tauri::command]
+pub fn get_playback_state(session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>, playback: State<'_, PlaybackStore>) -> Result<PlaybackStatus> {
+    let session_id = sessions.resolve(session_id, &window);
+    Ok(playback.update(&session_id, max_time(&sessions, &session_id)?, |_| {}))
+}
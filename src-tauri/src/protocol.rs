@@ -0,0 +1,112 @@
+use crate::access;
+use crate::config::SimulationConfig;
+use crate::errors::{AppError, Result};
+use crate::models::{ProtocolHandshake, VersionedRequest, VersionedResponse};
+use crate::session::SessionStore;
+use crate::simulation::simulate;
+use serde_json::Value;
+use tauri::{State, Window};
+
+// IPC protocol version (semver). Bump the minor version for additive payload changes
+// and add a shim below so older cached frontend bundles keep working.
+//   1.0.0  original start_simulation payload, events without sequence numbers
+//   1.1.0  event sequence numbers and session ids
+//   1.2.0  SimulationConfig payload for run_simulation
+pub const PROTOCOL_VERSION: &str = "1.2.0";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Version {
+    major: u32,
+    minor: u32,
+    patch: u32,
+}
+
+impl Version {
+    fn parse(text: &str) -> Result<Self> {
+        let mut parts = text.trim().trim_start_matches('v').split('.').map(|p| p.parse::<u32>());
+        let mut next = || parts.next().unwrap_or(Ok(0))
+            .map_err(|_| AppError::ProtocolError(format!("Invalid protocol version: {}", text)));
+        Ok(Version { major: next()?, minor: next()?, patch: next()? })
+    }
+
+    const fn new(major: u32, minor: u32) -> Self {
+        Version { major, minor, patch: 0 }
+    }
+}
+
+fn server_version() -> Version {
+    Version::parse(PROTOCOL_VERSION).unwrap()
+}
+
+// Shims applied for a client version, by name, for the handshake
+fn shims_for(client: Version) -> Vec<String> {
+    let mut shims = vec!["drop-null-options".to_string()];
+    if client < Version::new(1, 1) {
+        shims.push("strip-event-sequence".to_string());
+    }
+    shims
+}
+
+fn check_compatible(client: Version) -> Result<()> {
+    let server = server_version();
+    if client.major != server.major {
+        return Err(AppError::ProtocolError(format!(
+            "Frontend protocol {}.{}.{} is incompatible with backend {}; reload the app",
+            client.major, client.minor, client.patch, PROTOCOL_VERSION)));
+    }
+    Ok(())
+}
+
+// Older bundles send null for options they don't know; let the config defaults apply instead
+fn upgrade_payload(mut payload: Value) -> Value {
+    if let Value::Object(map) = &mut payload {
+        map.retain(|_, v| !v.is_null());
+    }
+    payload
+}
+
+// Reshape a result for the client's protocol version
+fn downgrade_result(client: Version, mut result: Value) -> Value {
+    if client < Version::new(1, 1) {
+        if let Value::Array(frames) = &mut result {
+            for event in frames.iter_mut().filter_map(|f| f.get_mut("events")).filter_map(Value::as_array_mut).flatten() {
+                if let Value::Object(map) = event {
+                    map.remove("sequence");
+                }
+            }
+        }
+    }
+    result
+}
+
+#[tauri::command]
+pub fn negotiate_protocol(client_version: String) -> Result<ProtocolHandshake> {
+    let client = Version::parse(&client_version)?;
+    let compatible = check_compatible(client).is_ok();
+    Ok(ProtocolHandshake {
+        protocol_version: PROTOCOL_VERSION.to_string(),
+        client_version,
+        compatible,
+        shims: if compatible { shims_for(client) } else { Vec::new() },
+    })
+}
+
+// Versioned variant of run_simulation: the payload is translated from the client's
+// protocol version and the result is echoed back with the protocol version it follows.
+#[tauri::command]
+pub fn run_simulation_versioned(request: VersionedRequest, session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<VersionedResponse> {
+    access::require_host(&window, "run simulations")?;
+    let client = Version::parse(&request.protocol_version)?;
+    check_compatible(client)?;
+
+    let config: SimulationConfig = serde_json::from_value(upgrade_payload(request.payload))
+        .map_err(|e| AppError::JsonParseError(e.to_string()))?;
+    let frames = simulate(config, sessions.resolve(session_id, &window), &sessions, &window)?;
+    let result = serde_json::to_value(frames)
+        .map_err(|e| AppError::JsonParseError(e.to_string()))?;
+
+    Ok(VersionedResponse {
+        protocol_version: PROTOCOL_VERSION.to_string(),
+        result: downgrade_result(client, result),
+    })
+}
@@ -0,0 +1,43 @@
+use crate::models::{FrameQuery, SimulationFrame};
+use crate::errors::Result;
+use crate::session::SessionStore;
+use tauri::{State, Window};
+
+fn matches(frame: &SimulationFrame, query: &FrameQuery) -> bool {
+    match query {
+        FrameQuery::SeatOccupied { seat_id } => frame.seats.iter()
+            .any(|s| s.id == *seat_id && s.occupied_by.is_some()),
+        FrameQuery::SeatFree { seat_id } => frame.seats.iter()
+            .any(|s| s.id == *seat_id && s.occupied_by.is_none()),
+        FrameQuery::FamilySeated { family_id } => frame.seats.iter()
+            .any(|s| s.occupied_by == Some(*family_id)),
+        FrameQuery::FamilyWaiting { family_id } => frame.waiting_queue.iter()
+            .any(|c| c.family_id == *family_id),
+        FrameQuery::QueueLengthOver { length } => frame.waiting_queue.len() > *length,
+        FrameQuery::All { queries } => queries.iter().all(|q| matches(frame, q)),
+        FrameQuery::Any { queries } => queries.iter().any(|q| matches(frame, q)),
+    }
+}
+
+// Timestamps of frames matching the query, in order.
+// `after` skips frames up to and including that time ("jump to next match"),
+// `limit` caps the number of results (1 = first match only).
+#[tauri::command]
+pub fn query_frames(
+    session_id: Option<String>,
+    query: FrameQuery,
+    after: Option<u64>,
+    limit: Option<usize>,
+    window: Window,
+    sessions: State<'_, SessionStore>
+) -> Result<Vec<u64>> {
+    let session_id = sessions.resolve(session_id, &window);
+    sessions.with_session(&session_id, |run| {
+        run.frames.iter()
+            .filter(|f| after.is_none_or(|t| f.timestamp > t))
+            .filter(|f| matches(f, &query))
+            .map(|f| f.timestamp)
+            .take(limit.unwrap_or(usize::MAX))
+            .collect()
+    })
+}
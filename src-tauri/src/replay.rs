@@ -0,0 +1,137 @@
+use crate::access;
+use crate::config::SimulationConfig;
+use crate::errors::{AppError, Result};
+use crate::models::{
+    AbandonmentCostModel, Announcement, Bookmark, CondimentReport, CustomerConfig, DayReport, EngineHealthReport, EventAnnotation,
+    KitchenReport, LedgerEntry, RngDraw, RunMetadata, SeatConfig, ShadowReport, SimulationFrame, StationReport, SyncTraceEntry,
+};
+use crate::session::{SessionRun, SessionStore};
+use crate::simulation::engine_params;
+use crate::subscriptions::SubscriptionStore;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri::{Manager, State, Window};
+
+// Bumped whenever the file layout changes incompatibly
+const SAVED_RUN_VERSION: u32 = 1;
+
+// A finished run as written by save_run: inputs, every frame with its events, the
+// reports and the user's notes. Loading it needs no re-run.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SavedRun {
+    version: u32,
+    config: SimulationConfig,
+    customers: Vec<CustomerConfig>,
+    seats_config: Vec<SeatConfig>,
+    metadata: RunMetadata,
+    frames: Vec<SimulationFrame>,
+    shadow_report: Option<ShadowReport>,
+    kitchen_report: KitchenReport,
+    station_report: Vec<StationReport>,
+    engine_health: Option<EngineHealthReport>,
+    sync_trace: Vec<SyncTraceEntry>,
+    ledger: Vec<LedgerEntry>,
+    condiment_report: Option<CondimentReport>,
+    announcements: Vec<Announcement>,
+    cost_model: AbandonmentCostModel,
+    annotations: BTreeMap<usize, EventAnnotation>,
+    bookmarks: Vec<Bookmark>,
+    day_report: Option<DayReport>,
+    #[serde(default)]
+    draws: Vec<RngDraw>,
+    #[serde(default)]
+    changed_parties: Vec<CustomerConfig>,
+}
+
+fn json_error(e: serde_json::Error) -> AppError {
+    AppError::JsonParseError(e.to_string())
+}
+
+impl SavedRun {
+    fn from_run(run: &SessionRun) -> Self {
+        SavedRun {
+            version: SAVED_RUN_VERSION,
+            config: run.config.clone(),
+            customers: run.customers.clone(),
+            seats_config: run.seats_config.clone(),
+            metadata: run.metadata.clone(),
+            frames: run.frames.clone(),
+            shadow_report: run.shadow_report.clone(),
+            kitchen_report: run.kitchen_report.clone(),
+            station_report: run.station_report.clone(),
+            engine_health: run.engine_health.clone(),
+            sync_trace: run.sync_trace.clone(),
+            ledger: run.ledger.clone(),
+            condiment_report: run.condiment_report.clone(),
+            announcements: run.announcements.clone(),
+            cost_model: run.cost_model.clone(),
+            annotations: run.annotations.clone(),
+            bookmarks: run.bookmarks.clone(),
+            day_report: run.day_report.clone(),
+            draws: run.draws.clone(),
+            changed_parties: run.changed_parties.clone(),
+        }
+    }
+
+    fn into_run(self) -> Result<SessionRun> {
+        // Settings are re-derived from the config so what-if commands work on the run
+        let params = engine_params(&self.config, &self.seats_config)?;
+        Ok(SessionRun {
+            config: self.config,
+            customers: self.customers,
+            seats_config: self.seats_config,
+            params,
+            metadata: self.metadata,
+            frames: self.frames,
+            shadow_report: self.shadow_report,
+            kitchen_report: self.kitchen_report,
+            station_report: self.station_report,
+            engine_health: self.engine_health,
+            sync_trace: self.sync_trace,
+            ledger: self.ledger,
+            condiment_report: self.condiment_report,
+            announcements: self.announcements,
+            cost_model: self.cost_model,
+            annotations: self.annotations,
+            bookmarks: self.bookmarks,
+            day_report: self.day_report,
+            checkpoints: None, // not saved: the first re-run after loading runs in full and keeps new ones
+            draws: self.draws,
+            changed_parties: self.changed_parties,
+        })
+    }
+}
+
+// Write the session's run, with its annotations and bookmarks, to a versioned JSON file
+#[tauri::command]
+pub fn save_run(path: String, session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<RunMetadata> {
+    access::require_host(&window, "save runs")?;
+    let session_id = sessions.resolve(session_id, &window);
+    let (json, metadata) = sessions.with_session(&session_id, |run| {
+        serde_json::to_string(&SavedRun::from_run(run)).map(|json| (json, run.metadata.clone()))
+    })?.map_err(json_error)?;
+    std::fs::write(&path, json)?;
+    Ok(metadata)
+}
+
+// Load a file written by save_run into the session, replacing its run, and hand back
+// the frames for replay as if the run had just finished
+#[tauri::command(async)]
+pub fn load_run(path: String, session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<Vec<SimulationFrame>> {
+    access::require_host(&window, "load runs")?;
+    let content = std::fs::read_to_string(&path)?;
+    let version = serde_json::from_str::<serde_json::Value>(&content).map_err(json_error)?
+        .get("version").and_then(|v| v.as_u64());
+    if version != Some(SAVED_RUN_VERSION as u64) {
+        return Err(AppError::ValidationError(format!("{} is not a saved run of version {} (found {:?})", path, SAVED_RUN_VERSION, version)));
+    }
+    let saved: SavedRun = serde_json::from_str(&content).map_err(json_error)?;
+
+    let session_id = sessions.resolve(session_id, &window);
+    let run = saved.into_run()?;
+    let frames = run.frames.clone();
+    sessions.insert(&session_id, run);
+    window.state::<SubscriptionStore>().publish(&window, &session_id, &frames);
+    Ok(frames)
+}
@@ -0,0 +1,145 @@
+use crate::access;
+use crate::bookings;
+use crate::errors::{AppError, Result};
+use crate::config::SimulationConfig;
+use crate::models::{ReproCheck, ReproDivergence, ReproManifest, SimulationEvent};
+use crate::protocol::PROTOCOL_VERSION;
+use crate::session::{SessionRun, SessionStore};
+use crate::simulation::{logical_sequences, simulate};
+use std::fs::File;
+use std::io::{Read, Write};
+use tauri::{State, Window};
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+// Bundle layout. config.json alone is enough to re-run; the rest is for humans and diffing.
+const MANIFEST_FILE: &str = "manifest.json";
+const CONFIG_FILE: &str = "config.json";
+const CUSTOMERS_FILE: &str = "customers.csv";
+const SEATS_FILE: &str = "seats.json";
+const EVENTS_FILE: &str = "events.json";
+const LOG_FILE: &str = "log.txt";
+const DRAWS_FILE: &str = "draws.json";
+
+fn zip_error(e: zip::result::ZipError) -> AppError {
+    AppError::IoError(e.into())
+}
+
+fn json_error(e: serde_json::Error) -> AppError {
+    AppError::JsonParseError(e.to_string())
+}
+
+// Every event of the run in log order
+fn event_log(run: &SessionRun) -> Vec<SimulationEvent> {
+    let mut events: Vec<SimulationEvent> = run.frames.iter().flat_map(|f| f.events.iter().cloned()).collect();
+    events.sort_by_key(|e| (e.timestamp, e.sequence));
+    events
+}
+
+// The roster as submitted, or as the run drew it from its generator
+fn roster_csv(run: &SessionRun) -> String {
+    if run.config.csv_content.trim().is_empty() {
+        return bookings::roster_csv(&run.customers);
+    }
+    run.config.csv_content.clone()
+}
+
+fn bundle_files(run: &SessionRun) -> Result<(ReproManifest, Vec<(&'static str, String)>)> {
+    let events = event_log(run);
+    let log = events.iter().map(|e| e.message.as_str()).collect::<Vec<_>>().join("\n");
+
+    let mut files = vec![
+        (CONFIG_FILE, serde_json::to_string_pretty(&run.config).map_err(json_error)?),
+        (CUSTOMERS_FILE, roster_csv(run)),
+        (SEATS_FILE, run.config.seat_config_json.clone()),
+        (EVENTS_FILE, serde_json::to_string_pretty(&events).map_err(json_error)?),
+        (LOG_FILE, log),
+    ];
+
+    // A roster the run drew is replayed from the seed in its config; the draw log shows
+    // what each random number decided
+    let seed = if run.draws.is_empty() { None } else { run.config.generator.as_ref().and_then(|g| g.seed) };
+    if seed.is_some() {
+        files.push((DRAWS_FILE, serde_json::to_string_pretty(&run.draws).map_err(json_error)?));
+    }
+
+    let manifest = ReproManifest {
+        engine_version: env!("CARGO_PKG_VERSION").to_string(),
+        protocol_version: PROTOCOL_VERSION.to_string(),
+        engine: run.metadata.engine.clone(),
+        seed,
+        fingerprint: run.metadata.fingerprint.clone(),
+        files: files.iter().map(|(name, _)| name.to_string()).collect(),
+    };
+    files.insert(0, (MANIFEST_FILE, serde_json::to_string_pretty(&manifest).map_err(json_error)?));
+    Ok((manifest, files))
+}
+
+// Write a zip with the run's inputs, settings, engine version and event log so a bug
+// report carries everything needed to reproduce it
+#[tauri::command]
+pub fn export_repro_bundle(session_id: Option<String>, path: String, window: Window, sessions: State<'_, SessionStore>) -> Result<ReproManifest> {
+    access::require_host(&window, "export repro bundles")?;
+    let session_id = sessions.resolve(session_id, &window);
+    let (manifest, files) = sessions.with_session(&session_id, |run| bundle_files(run))??;
+
+    let mut zip = ZipWriter::new(File::create(&path)?);
+    let options = SimpleFileOptions::default();
+    for (name, content) in &files {
+        zip.start_file(*name, options).map_err(zip_error)?;
+        zip.write_all(content.as_bytes())?;
+    }
+    zip.finish().map_err(zip_error)?;
+    Ok(manifest)
+}
+
+fn read_entry(archive: &mut ZipArchive<File>, name: &str) -> Result<String> {
+    let mut entry = archive.by_name(name)
+        .map_err(|e| AppError::ValidationError(format!("Bundle has no {}: {}", name, e)))?;
+    let mut content = String::new();
+    entry.read_to_string(&mut content)?;
+    Ok(content)
+}
+
+// A stored event in the form logical_sequences takes
+fn stored_step(e: &SimulationEvent) -> (u32, &str, u64, Option<&str>) {
+    (e.family_id, e.type_.as_str(), e.timestamp, e.seat_id.as_deref())
+}
+
+// Load a bundle, re-run its config into the session and diff the new event log
+// against the bundled one
+#[tauri::command(async)]
+pub fn import_repro_bundle(path: String, session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<ReproCheck> {
+    access::require_host(&window, "run simulations")?;
+    let mut archive = ZipArchive::new(File::open(&path)?).map_err(zip_error)?;
+    let manifest: ReproManifest = serde_json::from_str(&read_entry(&mut archive, MANIFEST_FILE)?).map_err(json_error)?;
+    let config: SimulationConfig = serde_json::from_str(&read_entry(&mut archive, CONFIG_FILE)?).map_err(json_error)?;
+    let bundled_events: Vec<SimulationEvent> = serde_json::from_str(&read_entry(&mut archive, EVENTS_FILE)?).map_err(json_error)?;
+
+    let session_id = sessions.resolve(session_id, &window);
+    simulate(config, session_id.clone(), &sessions, &window)?;
+    let (fingerprint, rerun_events) = sessions.with_session(&session_id, |run| (run.metadata.fingerprint.clone(), event_log(run)))?;
+
+    let bundled = logical_sequences(bundled_events.iter().map(stored_step));
+    let rerun = logical_sequences(rerun_events.iter().map(stored_step));
+    let divergences: Vec<ReproDivergence> = bundled.keys().chain(rerun.keys())
+        .copied()
+        .collect::<std::collections::BTreeSet<u32>>()
+        .into_iter()
+        .filter(|fid| bundled.get(fid) != rerun.get(fid))
+        .map(|fid| ReproDivergence {
+            family_id: fid,
+            bundled: bundled.get(&fid).cloned().unwrap_or_default(),
+            rerun: rerun.get(&fid).cloned().unwrap_or_default(),
+        })
+        .collect();
+
+    Ok(ReproCheck {
+        session_id,
+        same_engine_version: manifest.engine_version == env!("CARGO_PKG_VERSION"),
+        reproduced: divergences.is_empty() && fingerprint == manifest.fingerprint,
+        fingerprint,
+        manifest,
+        divergences,
+    })
+}
@@ -0,0 +1,74 @@
+use crate::models::RngDraw;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::ops::RangeInclusive;
+
+// The one source of randomness. Seeded, so the same seed gives the same draws, and
+// optionally recording every draw with what it was for, so a stochastic run can be
+// replayed and stepped through draw by draw.
+pub struct SimRng {
+    rng: StdRng,
+    seed: u64,
+    draws: Option<Vec<RngDraw>>, // None: not recording
+}
+
+// Seed for a run the user didn't seed; report it so the run can be repeated
+pub fn fresh_seed() -> u64 {
+    rand::rng().random()
+}
+
+impl SimRng {
+    pub fn new(seed: u64, record: bool) -> Self {
+        SimRng { rng: StdRng::seed_from_u64(seed), seed, draws: record.then(Vec::new) }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn record(&mut self, purpose: &str, value: f64) {
+        if let Some(draws) = &mut self.draws {
+            draws.push(RngDraw { index: draws.len(), purpose: purpose.to_string(), value });
+        }
+    }
+
+    // Uniform in [0, 1)
+    pub fn unit(&mut self, purpose: &str) -> f64 {
+        let value: f64 = self.rng.random();
+        self.record(purpose, value);
+        value
+    }
+
+    // Uniform in [0, upper); upper must be positive
+    pub fn below(&mut self, purpose: &str, upper: f64) -> f64 {
+        let value = self.rng.random_range(0.0..upper);
+        self.record(purpose, value);
+        value
+    }
+
+    // Uniform integer in the range, which must not be empty
+    pub fn range(&mut self, purpose: &str, range: RangeInclusive<u64>) -> u64 {
+        let value = self.rng.random_range(range);
+        self.record(purpose, value as f64);
+        value
+    }
+
+    // Same as range for small counts (party members and the like)
+    pub fn count(&mut self, purpose: &str, range: RangeInclusive<u32>) -> u32 {
+        let value = self.rng.random_range(range);
+        self.record(purpose, value as f64);
+        value
+    }
+
+    // True with probability p
+    pub fn chance(&mut self, purpose: &str, p: f64) -> bool {
+        let value = self.rng.random_bool(p);
+        self.record(purpose, value as u8 as f64);
+        value
+    }
+
+    // Every draw so far, in order; empty when not recording
+    pub fn into_draws(self) -> Vec<RngDraw> {
+        self.draws.unwrap_or_default()
+    }
+}
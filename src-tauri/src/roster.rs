@@ -0,0 +1,73 @@
+use crate::bookings::roster_csv;
+use crate::errors::{AppError, Result};
+use crate::models::{ParserOptions, RosterImport};
+use crate::parser::{self, ParseMode, ParsedRoster};
+use calamine::{open_workbook_auto, Reader};
+
+const SPREADSHEET_EXTENSIONS: [&str; 4] = ["xlsx", "xlsm", "xls", "ods"];
+
+// First sheet of a workbook as roster CSV: one line per non-empty row, with the same
+// column layout as the CSV format (a header row starting with "id" is optional)
+fn sheet_csv(path: &str) -> Result<String> {
+    let spreadsheet_error = |e: calamine::Error| AppError::CsvParseError(format!("Cannot read {}: {}", path, e));
+    let mut workbook = open_workbook_auto(path).map_err(spreadsheet_error)?;
+    let range = workbook.worksheet_range_at(0)
+        .ok_or_else(|| AppError::CsvParseError(format!("{} has no sheets", path)))?
+        .map_err(spreadsheet_error)?;
+    let lines: Vec<String> = range.rows()
+        .map(|row| row.iter().map(|cell| cell.to_string().replace(',', ";")).collect::<Vec<_>>().join(","))
+        .filter(|line| line.chars().any(|c| c != ','))
+        .collect();
+    Ok(lines.join("\n") + "\n")
+}
+
+// CSV in the standard layout, rewritten first when the file has its own
+fn parse_csv(text: String, options: Option<&ParserOptions>, mode: ParseMode) -> Result<(String, ParsedRoster)> {
+    let csv = match options {
+        Some(options) => parser::normalize_csv(&text, options).map_err(|e| AppError::CsvParseError(e.to_string()))?,
+        None => text,
+    };
+    let roster = parser::parse_customers_with(&csv, mode).map_err(|e| AppError::CsvParseError(e.to_string()))?;
+    Ok((csv, roster))
+}
+
+// Roster text, recognized as JSON by its opening bracket and read as CSV otherwise
+fn parse_text(text: String, options: Option<&ParserOptions>, mode: ParseMode) -> Result<(&'static str, String, ParsedRoster)> {
+    if text.trim_start().starts_with(['[', '{']) {
+        let roster = parser::parse_customers_json(&text, mode).map_err(|e| AppError::JsonParseError(e.to_string()))?;
+        Ok(("json", roster_csv(&roster.customers), roster))
+    } else {
+        let (csv, roster) = parse_csv(text, options, mode)?;
+        Ok(("csv", csv, roster))
+    }
+}
+
+// Load a roster from CSV or JSON text (csv_content), or from a CSV, JSON or spreadsheet
+// (xlsx, xls, ods) file (path); spreadsheets are recognized by extension. The result's
+// csv_content is the roster in the CSV format the simulation commands take. With strict,
+// the first unreadable row or value fails the load; otherwise it is skipped or defaulted
+// and reported in warnings. options describe a CSV or sheet with its own delimiter,
+// header or column order (the delimiter doesn't apply to sheets).
+#[tauri::command]
+pub fn load_customers(csv_content: Option<String>, path: Option<String>, strict: Option<bool>, options: Option<ParserOptions>) -> Result<RosterImport> {
+    let mode = if strict.unwrap_or(false) { ParseMode::Strict } else { ParseMode::Lenient };
+    let (format, csv_content, roster) = match (csv_content, path) {
+        (Some(text), None) => parse_text(text, options.as_ref(), mode)?,
+        (None, Some(path)) => {
+            let extension = path.rsplit('.').next().unwrap_or("").to_lowercase();
+            let loaded = if SPREADSHEET_EXTENSIONS.contains(&extension.as_str()) {
+                let sheet_options = options.map(|o| ParserOptions { delimiter: None, ..o });
+                let (csv, roster) = parse_csv(sheet_csv(&path)?, sheet_options.as_ref(), mode)?;
+                ("xlsx", csv, roster)
+            } else {
+                parse_text(std::fs::read_to_string(&path)?, options.as_ref(), mode)?
+            };
+            if loaded.2.customers.is_empty() {
+                return Err(AppError::ValidationError(format!("No customers found in {}", path)));
+            }
+            loaded
+        }
+        _ => return Err(AppError::ValidationError("load_customers takes either csv_content or path".to_string())),
+    };
+    Ok(RosterImport { format: format.to_string(), customers: roster.customers, csv_content, warnings: roster.warnings })
+}
@@ -0,0 +1,338 @@
+use crate::access;
+use crate::models::{CustomerConfig, SeatConfig, SimulationFrame, SimulationEvent, Seat, SessionMemoryReport, ShadowReport, RunMetadata, AbandonmentCostModel, EventAnnotation, Bookmark, KitchenReport, StationReport, EngineHealthReport, SyncTraceEntry, LedgerEntry, CondimentReport, Announcement, DayReport, RngDraw};
+use crate::errors::{AppError, Result};
+use crate::simulation::{Checkpoints, EngineParams};
+use crate::config::SimulationConfig;
+use std::collections::{BTreeMap, HashMap};
+use std::mem::size_of;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State, WebviewUrl, WebviewWindowBuilder, Window};
+
+// A completed run kept in memory so later commands can inspect it
+pub struct SessionRun {
+    pub config: SimulationConfig, // exactly as submitted, for reproduction
+    pub customers: Vec<CustomerConfig>,
+    pub seats_config: Vec<SeatConfig>,
+    pub params: EngineParams,
+    pub metadata: RunMetadata,
+    pub frames: Vec<SimulationFrame>,
+    pub shadow_report: Option<ShadowReport>,
+    pub kitchen_report: KitchenReport,
+    pub station_report: Vec<StationReport>,
+    pub engine_health: Option<EngineHealthReport>, // only for instrumented runs
+    pub sync_trace: Vec<SyncTraceEntry>,           // only for traced runs
+    pub ledger: Vec<LedgerEntry>,                  // every pool acquisition and release
+    pub condiment_report: Option<CondimentReport>, // only when the condiment station is enabled
+    pub announcements: Vec<Announcement>,          // lobby calls, in timeline order
+    pub cost_model: AbandonmentCostModel,
+    pub annotations: BTreeMap<usize, EventAnnotation>, // keyed by event sequence
+    pub bookmarks: Vec<Bookmark>, // sorted by timestamp
+    pub day_report: Option<DayReport>, // only for shifts recorded in operations mode
+    pub checkpoints: Option<Arc<Checkpoints>>, // DES engine state along a re-run, for the next rerun_simulation
+    pub draws: Vec<RngDraw>, // random draws the run made for a generated roster, in order
+    pub changed_parties: Vec<CustomerConfig>, // parties after their requirement changes, if any changed
+}
+
+impl SessionRun {
+    // The roster with every party as it was seated, after any requirement changes
+    pub fn parties(&self) -> impl Iterator<Item = &CustomerConfig> {
+        self.customers.iter()
+            .map(|c| self.changed_parties.iter().find(|p| p.family_id == c.family_id).unwrap_or(c))
+    }
+}
+
+#[derive(Default)]
+pub struct SessionStore {
+    sessions: Mutex<HashMap<String, SessionRun>>,
+    // Set once the app starts shutting down; running simulations poll it
+    shutdown: Arc<AtomicBool>,
+    // Number of simulations currently executing, so shutdown can wait for them
+    active_runs: Mutex<usize>,
+    idle: Condvar,
+    // Window label -> session id for windows opened via open_session_window
+    window_bindings: Mutex<HashMap<String, String>>,
+    next_window: Mutex<u32>,
+}
+
+// Held by a running simulation; decrements the active count when dropped
+pub struct RunGuard<'a> {
+    store: &'a SessionStore,
+}
+
+impl Drop for RunGuard<'_> {
+    fn drop(&mut self) {
+        let mut active = self.store.active_runs.lock().unwrap();
+        *active -= 1;
+        self.store.idle.notify_all();
+    }
+}
+
+impl SessionStore {
+    // Register a new simulation run. Refused once shutdown has begun so no new workers get spawned.
+    pub fn begin_run(&self) -> Result<RunGuard<'_>> {
+        if self.shutdown.load(Ordering::SeqCst) {
+            return Err(AppError::SimulationError("Application is shutting down".to_string()));
+        }
+        *self.active_runs.lock().unwrap() += 1;
+        Ok(RunGuard { store: self })
+    }
+
+    // Flag shared with worker threads so they can bail out early
+    pub fn cancel_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.shutdown)
+    }
+
+    // Ask running simulations to stop and wait (bounded) for their workers to exit
+    pub fn shutdown(&self, timeout: Duration) {
+        self.shutdown.store(true, Ordering::SeqCst);
+
+        let deadline = Instant::now() + timeout;
+        let mut active = self.active_runs.lock().unwrap();
+        while *active > 0 {
+            let now = Instant::now();
+            if now >= deadline { break; }
+            active = self.idle.wait_timeout(active, deadline - now).unwrap().0;
+        }
+    }
+
+    // Pick the session a command operates on: an explicit id wins,
+    // otherwise the session bound to the calling window, otherwise the window label itself.
+    pub fn resolve(&self, session_id: Option<String>, window: &Window) -> String {
+        if let Some(id) = session_id {
+            return id;
+        }
+        self.window_session(window.label())
+    }
+
+    // The session a window shows: its binding, otherwise its own label
+    pub fn window_session(&self, label: &str) -> String {
+        self.window_bindings.lock().unwrap()
+            .get(label)
+            .cloned()
+            .unwrap_or_else(|| label.to_string())
+    }
+
+    // Labels of the open windows showing a session
+    pub fn windows_showing(&self, app: &AppHandle, session_id: &str) -> Vec<String> {
+        app.webview_windows().into_keys()
+            .filter(|label| self.window_session(label) == session_id)
+            .collect()
+    }
+
+    // Returns the session the closed window was showing
+    pub fn unbind_window(&self, label: &str) -> String {
+        self.window_bindings.lock().unwrap()
+            .remove(label)
+            .unwrap_or_else(|| label.to_string())
+    }
+
+    pub fn insert(&self, session_id: &str, run: SessionRun) {
+        self.sessions.lock().unwrap().insert(session_id.to_string(), run);
+    }
+
+    // Run a closure against a stored session, failing if the id is unknown
+    pub fn with_session<R>(&self, session_id: &str, f: impl FnOnce(&mut SessionRun) -> R) -> Result<R> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let run = sessions.get_mut(session_id)
+            .ok_or_else(|| AppError::SessionError(format!("Unknown session: {}", session_id)))?;
+        Ok(f(run))
+    }
+}
+
+// Approximate heap + inline size of the stored data.
+// Only counts the data we own (strings, vectors), not allocator overhead.
+fn event_bytes(e: &SimulationEvent) -> usize {
+    size_of::<SimulationEvent>()
+        + e.type_.capacity()
+        + e.message.capacity()
+        + e.seat_id.as_ref().map(|s| s.capacity()).unwrap_or(0)
+        + e.extra.iter().map(|(k, v)| k.capacity() + v.capacity()).sum::<usize>()
+}
+
+fn seat_bytes(s: &Seat) -> usize {
+    size_of::<Seat>()
+        + s.id.capacity()
+        + s.type_.capacity()
+        + s.occupant_type.as_ref().map(|t| t.capacity()).unwrap_or(0)
+}
+
+fn customer_bytes(c: &CustomerConfig) -> usize {
+    size_of::<CustomerConfig>() + c.type_.capacity()
+        + c.extra.iter().map(|(k, v)| k.capacity() + v.capacity()).sum::<usize>()
+}
+
+fn memory_report(session_id: &str, run: &SessionRun) -> SessionMemoryReport {
+    let mut frame_bytes = 0;
+    let mut event_bytes_total = 0;
+    let mut event_count = 0;
+
+    for frame in &run.frames {
+        frame_bytes += size_of::<SimulationFrame>();
+        frame_bytes += frame.seats.iter().map(seat_bytes).sum::<usize>();
+        frame_bytes += frame.waiting_queue.iter().map(customer_bytes).sum::<usize>();
+        frame_bytes += frame.logs.iter().map(|l| size_of::<String>() + l.capacity()).sum::<usize>();
+        let queues = &frame.sub_queues;
+        frame_bytes += size_of::<u32>()
+            * (queues.single.capacity() + queues.four_person.capacity() + queues.six_person.capacity() + queues.accessible.capacity());
+
+        event_count += frame.events.len();
+        event_bytes_total += frame.events.iter().map(event_bytes).sum::<usize>();
+    }
+
+    let input_bytes = run.customers.iter().chain(&run.changed_parties).map(customer_bytes).sum::<usize>()
+        + run.seats_config.iter()
+            .map(|s| size_of::<SeatConfig>() + s.id.capacity() + s.type_.capacity())
+            .sum::<usize>()
+        + run.draws.iter().map(|d| size_of::<RngDraw>() + d.purpose.capacity()).sum::<usize>();
+
+    let checkpoint_bytes = run.checkpoints.as_ref().map_or(0, |c| c.bytes());
+
+    SessionMemoryReport {
+        session_id: session_id.to_string(),
+        frame_count: run.frames.len(),
+        event_count,
+        frame_bytes,
+        event_bytes: event_bytes_total,
+        input_bytes,
+        checkpoint_bytes,
+        total_bytes: frame_bytes + event_bytes_total + input_bytes + checkpoint_bytes,
+    }
+}
+
+// Open an extra app window bound to its own session, returning the new window label
+#[tauri::command]
+pub fn open_session_window(app: AppHandle, session_id: String, title: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<String> {
+    access::require_host(&window, "open session windows")?;
+    let label = {
+        let mut next = sessions.next_window.lock().unwrap();
+        *next += 1;
+        format!("session-{}", *next)
+    };
+
+    WebviewWindowBuilder::new(&app, label.clone(), WebviewUrl::default())
+        .title(title.unwrap_or_else(|| format!("Sushi Sync - {}", session_id)))
+        .inner_size(1200.0, 800.0)
+        .build()
+        .map_err(|e| AppError::SessionError(format!("Failed to open window: {}", e)))?;
+
+    sessions.window_bindings.lock().unwrap().insert(label.clone(), session_id);
+    Ok(label)
+}
+
+// Let a window find out which session it is showing
+#[tauri::command]
+pub fn get_window_session(window: Window, sessions: State<'_, SessionStore>) -> String {
+    sessions.resolve(None, &window)
+}
+
+#[tauri::command]
+pub fn get_run_metadata(session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<RunMetadata> {
+    let session_id = sessions.resolve(session_id, &window);
+    sessions.with_session(&session_id, |run| run.metadata.clone())
+}
+
+// Attach a note to one event of the run; an empty note removes the annotation
+#[tauri::command]
+pub fn annotate_event(session_id: Option<String>, sequence: usize, note: String, window: Window, sessions: State<'_, SessionStore>) -> Result<Vec<EventAnnotation>> {
+    access::require_host(&window, "annotate events")?;
+    let session_id = sessions.resolve(session_id, &window);
+    sessions.with_session(&session_id, |run| {
+        let event = run.frames.iter()
+            .flat_map(|f| f.events.iter())
+            .find(|e| e.sequence == sequence)
+            .ok_or_else(|| AppError::SessionError(format!("No event with sequence {}", sequence)))?;
+
+        if note.trim().is_empty() {
+            run.annotations.remove(&sequence);
+        } else {
+            let annotation = EventAnnotation {
+                sequence,
+                timestamp: event.timestamp,
+                family_id: event.family_id,
+                note,
+            };
+            run.annotations.insert(sequence, annotation);
+        }
+        Ok(run.annotations.values().cloned().collect())
+    })?
+}
+
+#[tauri::command]
+pub fn list_annotations(session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<Vec<EventAnnotation>> {
+    let session_id = sessions.resolve(session_id, &window);
+    sessions.with_session(&session_id, |run| run.annotations.values().cloned().collect())
+}
+
+// Bookmark a moment of the replay (e.g. "dinner rush") so the timeline can jump to it
+#[tauri::command]
+pub fn add_bookmark(session_id: Option<String>, t: u64, label: String, window: Window, sessions: State<'_, SessionStore>) -> Result<Vec<Bookmark>> {
+    access::require_host(&window, "add bookmarks")?;
+    let session_id = sessions.resolve(session_id, &window);
+    sessions.with_session(&session_id, |run| {
+        let last_t = run.frames.last().map(|f| f.timestamp).unwrap_or(0);
+        if t > last_t {
+            return Err(AppError::SessionError(format!("Timestamp {} is past the end of the run ({})", t, last_t)));
+        }
+
+        let idx = run.bookmarks.partition_point(|b| b.timestamp <= t);
+        run.bookmarks.insert(idx, Bookmark { timestamp: t, label });
+        Ok(run.bookmarks.clone())
+    })?
+}
+
+#[tauri::command]
+pub fn remove_bookmark(session_id: Option<String>, t: u64, label: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<Vec<Bookmark>> {
+    access::require_host(&window, "remove bookmarks")?;
+    let session_id = sessions.resolve(session_id, &window);
+    sessions.with_session(&session_id, |run| {
+        run.bookmarks.retain(|b| b.timestamp != t || label.as_ref().is_some_and(|l| *l != b.label));
+        run.bookmarks.clone()
+    })
+}
+
+#[tauri::command]
+pub fn list_bookmarks(session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<Vec<Bookmark>> {
+    let session_id = sessions.resolve(session_id, &window);
+    sessions.with_session(&session_id, |run| run.bookmarks.clone())
+}
+
+#[tauri::command]
+pub fn get_session_memory(session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<SessionMemoryReport> {
+    let session_id = sessions.resolve(session_id, &window);
+    sessions.with_session(&session_id, |run| memory_report(&session_id, run))
+}
+
+// Keep every n-th frame (plus the final one) to shrink a stored run.
+// Events of dropped frames are folded into the next kept frame so the log stays complete.
+#[tauri::command]
+pub fn downsample_frames(session_id: Option<String>, keep_every_n: usize, window: Window, sessions: State<'_, SessionStore>) -> Result<SessionMemoryReport> {
+    access::require_host(&window, "downsample runs")?;
+    let session_id = sessions.resolve(session_id, &window);
+    if keep_every_n == 0 {
+        return Err(AppError::SessionError("keep_every_n must be at least 1".to_string()));
+    }
+
+    sessions.with_session(&session_id, |run| {
+        let last_idx = run.frames.len().saturating_sub(1);
+        let mut kept: Vec<SimulationFrame> = Vec::with_capacity(run.frames.len() / keep_every_n + 1);
+        let mut pending_events: Vec<SimulationEvent> = Vec::new();
+
+        for (i, mut frame) in std::mem::take(&mut run.frames).into_iter().enumerate() {
+            if i % keep_every_n == 0 || i == last_idx {
+                if !pending_events.is_empty() {
+                    pending_events.append(&mut frame.events);
+                    frame.events = std::mem::take(&mut pending_events);
+                }
+                kept.push(frame);
+            } else {
+                pending_events.append(&mut frame.events);
+            }
+        }
+
+        kept.shrink_to_fit();
+        run.frames = kept;
+        memory_report(&session_id, run)
+    })
+}
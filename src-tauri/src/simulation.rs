@@ -1,24 +1,405 @@
-use crate::models::{CustomerConfig, SeatConfig, SimulationFrame, SimulationEvent, Seat};
+mod des;
+pub mod operations;
+mod semaphore;
+mod tasks;
+mod comfort;
+pub(crate) mod merge;
+mod reservations;
+mod journal;
+mod event_store;
+mod projections;
+mod party;
+pub(crate) mod strategy;
+
+use crate::access;
+use crate::models::{CustomerConfig, SeatConfig, TableReservation, RequirementChange, SimulationFrame, WaitingSubQueues, SimulationEvent, ShadowDecision, ShadowFamilyDelta, ShadowReport, RunMetadata, KitchenConfig, KitchenReport, CourierConfig, SelfServiceStation, EngineComparison, EngineDivergence, LiveSnapshot, CustomerPreview, DiagnosticSeverity, FrameResolution, RngDraw, LedgerEntry};
+use crate::diagnostics;
+use crate::feasibility;
+use crate::validation;
+use crate::kitchen::KitchenModel;
+use crate::stations::StationModel;
+use crate::instrumentation::{Instrumentation, Instrumented, lock_timed, wait_timed, notify_all_counted};
+use crate::deadlock::{DeadlockResolution, DEADLOCK_EVENT};
+use crate::ledger::{BABY_CHAIR_POOL, WHEELCHAIR_POOL};
 use crate::parser;
+use crate::generator;
+use crate::couriers;
+use crate::rng;
+use crate::hashing;
 use crate::errors::{AppError, Result};
+use crate::session::{SessionRun, SessionStore};
+use crate::config::SimulationConfig;
+use crate::stats::RENEGE_EVENT;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex, Condvar};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use crate::subscriptions::SubscriptionStore;
+use crate::live::{self, LiveStateStore, LiveView};
+use crate::ledger::ResourceLedger;
+use strategy::AllocationStrategy;
+use event_store::EventStore;
+use party::{Turn, Waiter};
+use projections::{Projection, Queue, SeatMap};
+pub(crate) use reservations::DEFAULT_RESERVATION_GRACE;
+pub(crate) use des::Checkpoints;
+use tauri::{Manager, State, Window};
+
+// Real time per virtual minute in the thread engines (dining and giving up waiting)
+pub(super) const REALTIME_MS_PER_MINUTE: u64 = 10;
+
+// Default time a fully conveyor-minded party will wait for a belt-facing seat
+pub(crate) const DEFAULT_CONVEYOR_PATIENCE: u64 = 15;
+
+// Event type for baby chairs handed to an already seated party
+pub const DELIVERED_EVENT: &str = "RESOURCE_DELIVERED";
 
-// Default wait timeout (1 hour) to prevent premature timeout in simulation
-const WAIT_TIMEOUT_MS: u64 = 3600000; 
+// Event type for a waiting party whose size or chair needs changed
+pub const REQUIREMENTS_CHANGED_EVENT: &str = "REQUIREMENTS_CHANGED";
 
-struct SushiResources {
-    baby_chairs_available: i32,
-    wheelchairs_available: i32,
+// Event type for a priority party seated ahead of earlier walk-ins, so the queue order
+// can be audited
+pub const PRIORITY_SEATED_EVENT: &str = "PRIORITY_SEATED";
+
+// Event type for a party the layout and pools can never seat; it leaves at once
+pub const INFEASIBLE_EVENT: &str = "INFEASIBLE";
+
+// How often blocked/dining threads re-check the shutdown flag
+const CANCEL_POLL_MS: u64 = 50;
+
+#[derive(Clone)]
+pub(crate) struct SushiResources {
+    ledger: ResourceLedger, // baby chair and wheelchair pools
     seats: Vec<SeatState>,
-    events: Vec<SimEvent>,
+    events: EventStore, // the run itself; everything else is engine bookkeeping
+    shadow: Option<ShadowState>,
+    kitchen: KitchenModel,
+    stations: StationModel,
+    instrumentation: Instrumentation,
+    live: Option<LiveView>,
+    // Seated parties still owed baby chairs, served first come first served
+    pending_chairs: std::collections::VecDeque<PendingClaim>,
+    // Waiting parties in ticket order: by priority, then first come first served
+    tickets: Vec<CustomerConfig>,
+}
+
+// Baby chairs a seated party is still waiting for (deliver_baby_chairs_later)
+#[derive(Clone)]
+struct PendingClaim {
+    customer: CustomerConfig,
+    missing: u32,
+}
+
+impl SushiResources {
+    // A copy without the event log, ledger history and live view, for a DES checkpoint
+    fn without_history(&self) -> Self {
+        SushiResources {
+            ledger: self.ledger.balances_only(),
+            seats: self.seats.clone(),
+            events: self.events.without_log(),
+            shadow: self.shadow.clone(),
+            kitchen: self.kitchen.clone(),
+            stations: self.stations.clone(),
+            instrumentation: self.instrumentation.clone(),
+            live: None,
+            pending_chairs: self.pending_chairs.clone(),
+            tickets: self.tickets.clone(),
+        }
+    }
+
+    // Such a copy with the log and ledger history that led to it put back
+    fn with_history(&self, events: &[SimEvent], ledger: &[LedgerEntry]) -> Self {
+        let mut res = self.clone();
+        res.events = self.events.with_log(events);
+        res.ledger = self.ledger.with_entries(ledger);
+        res
+    }
+
+    fn take_ticket(&mut self, customer: &CustomerConfig) {
+        if !self.tickets.iter().any(|t| t.family_id == customer.family_id) {
+            let at = self.tickets.partition_point(|t| t.priority >= customer.priority);
+            self.tickets.insert(at, customer.clone());
+            // A dashboard sees the queue grow, not only seats change
+            self.publish_live();
+        }
+    }
+
+    // A waiting party's needs changed: its ticket keeps its place in line
+    fn amend_ticket(&mut self, customer: &CustomerConfig) {
+        if let Some(ticket) = self.tickets.iter_mut().find(|t| t.family_id == customer.family_id) {
+            *ticket = customer.clone();
+        }
+    }
+
+    fn drop_ticket(&mut self, family_id: u32) {
+        self.tickets.retain(|t| t.family_id != family_id);
+    }
+
+    // Whether a ticket holder ahead could be seated right now, in which case the customer
+    // waits its turn. Under FIFO that is anyone ahead of equal or higher priority; otherwise
+    // only higher priority parties go first. Each holder is judged at the time its own
+    // thread would use.
+    fn must_yield(&self, customer: &CustomerConfig, params: &EngineParams) -> bool {
+        let fifo = params.fairness == FairnessMode::Fifo;
+        if !fifo && customer.priority == 0 && self.tickets.iter().all(|t| t.priority == 0) {
+            return false;
+        }
+        let last = self.events.latest_time();
+        self.tickets.iter()
+            .take_while(|t| t.family_id != customer.family_id)
+            .filter(|t| t.priority > customer.priority || fifo && t.priority == customer.priority)
+            .any(|t| allocate_primary(self, t, params, last.max(t.arrival_time)).is_some())
+    }
+
+    // Waiting parties that arrived no later than `customer` and rank below it in the queue,
+    // but could have sat at `seat_ids` themselves. Asked before the seats are taken. Tables
+    // only come merged when merging is on, so the merge rule never rules anyone out here.
+    fn passed_over(&self, customer: &CustomerConfig, seat_ids: &[String]) -> Vec<u32> {
+        self.tickets.iter()
+            .filter(|t| t.priority < customer.priority && t.arrival_time <= customer.arrival_time)
+            .filter(|t| seating_problem(self, t, seat_ids, true).is_none())
+            .map(|t| t.family_id)
+            .collect()
+    }
+
+    // Waiting parties in queue order: ticket holders as they will be offered seats, then
+    // parties that have only just arrived
+    fn queue_order(&self, mut waiting: std::collections::BTreeSet<u32>) -> Vec<u32> {
+        let mut queue: Vec<u32> = self.tickets.iter()
+            .map(|t| t.family_id)
+            .filter(|fid| waiting.remove(fid))
+            .collect();
+        queue.extend(waiting);
+        queue
+    }
+
+    // Publish the current occupancy for get_current_state. Called with the monitor held,
+    // so the snapshot is consistent; readers never take the monitor themselves. Seats and
+    // queue are the same projections the frames are built from, kept current by the store.
+    fn publish_live(&self) {
+        let Some(view) = &self.live else { return };
+        live::publish(view, LiveSnapshot {
+            virtual_time: self.virtual_time(),
+            seats: self.events.seat_map().seats.clone(),
+            waiting_family_ids: self.queue_order(self.events.queue().waiting.clone()),
+            baby_chairs_available: self.ledger.available(BABY_CHAIR_POOL),
+            wheelchairs_available: self.ledger.available(WHEELCHAIR_POOL),
+            event_count: self.events.len(),
+            ..Default::default()
+        });
+    }
+}
+
+impl Instrumented for SushiResources {
+    fn instrumentation(&mut self) -> &mut Instrumentation {
+        &mut self.instrumentation
+    }
+
+    fn virtual_time(&self) -> u64 {
+        self.events.latest_time()
+    }
+}
+
+// Simulation backends. All share the allocation and logging code; the discrete-event
+// engine replays the same logic in virtual-time order on a single thread, and the
+// semaphore engine swaps the monitor for counting semaphores plus a seat lock.
+// The discrete-event engine is the default since identical inputs always give identical
+// event sequences; the thread-per-customer engine sleeps in wall-clock time ("realtime").
+// The tasks engine runs the monitor as async tasks, for rosters too big for a thread each.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) enum EngineBackend {
+    Threads,
+    #[default]
+    Des,
+    Semaphores,
+    Tasks,
+}
+
+impl EngineBackend {
+    pub(crate) const ALL: [EngineBackend; 4] = [EngineBackend::Threads, EngineBackend::Des, EngineBackend::Semaphores, EngineBackend::Tasks];
+
+    fn from_name(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "threads" | "monitor" | "realtime" => Ok(EngineBackend::Threads),
+            "des" => Ok(EngineBackend::Des),
+            "semaphores" | "semaphore" => Ok(EngineBackend::Semaphores),
+            "tasks" | "async" => Ok(EngineBackend::Tasks),
+            _ => Err(AppError::SimulationError(format!("Unknown engine: {}", name))),
+        }
+    }
+
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            EngineBackend::Threads => "threads",
+            EngineBackend::Des => "des",
+            EngineBackend::Semaphores => "semaphores",
+            EngineBackend::Tasks => "tasks",
+        }
+    }
+}
+
+// Seat class a waiting party queues for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum SeatClass {
+    Single,
+    FourPerson,
+    SixPerson,
+    Accessible,
+}
+
+impl SeatClass {
+    pub(crate) const ALL: [SeatClass; 4] = [SeatClass::Single, SeatClass::FourPerson, SeatClass::SixPerson, SeatClass::Accessible];
+
+    pub(crate) fn for_customer(customer: &CustomerConfig) -> Self {
+        if customer.wheelchair_count > 0 {
+            SeatClass::Accessible
+        } else if customer.party_size <= 1 {
+            SeatClass::Single
+        } else if customer.party_size <= 4 {
+            SeatClass::FourPerson
+        } else {
+            SeatClass::SixPerson
+        }
+    }
+
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            SeatClass::Single => "SINGLE",
+            SeatClass::FourPerson => "4P",
+            SeatClass::SixPerson => "6P",
+            SeatClass::Accessible => "ACCESSIBLE",
+        }
+    }
+
+    pub(crate) fn queue<'a>(&self, queues: &'a WaitingSubQueues) -> &'a Vec<u32> {
+        match self {
+            SeatClass::Single => &queues.single,
+            SeatClass::FourPerson => &queues.four_person,
+            SeatClass::SixPerson => &queues.six_person,
+            SeatClass::Accessible => &queues.accessible,
+        }
+    }
+
+    fn queue_mut<'a>(&self, queues: &'a mut WaitingSubQueues) -> &'a mut Vec<u32> {
+        match self {
+            SeatClass::Single => &mut queues.single,
+            SeatClass::FourPerson => &mut queues.four_person,
+            SeatClass::SixPerson => &mut queues.six_person,
+            SeatClass::Accessible => &mut queues.accessible,
+        }
+    }
+}
+
+// What the primary allocator optimizes for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum AllocationObjective {
+    Wait,
+    Revenue,
+}
+
+impl AllocationObjective {
+    pub(crate) const ALL: [AllocationObjective; 2] = [AllocationObjective::Wait, AllocationObjective::Revenue];
+
+    fn from_name(name: &str) -> Result<Self> {
+        match name.to_uppercase().as_str() {
+            "WAIT" => Ok(AllocationObjective::Wait),
+            "REVENUE" => Ok(AllocationObjective::Revenue),
+            _ => Err(AppError::SimulationError(format!("Unknown allocation objective: {}", name))),
+        }
+    }
+
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            AllocationObjective::Wait => "WAIT",
+            AllocationObjective::Revenue => "REVENUE",
+        }
+    }
+}
+
+// Who may take a seat that frees up on the threaded engines. Fifo: parties take a
+// ticket when they first have to wait, and nobody is seated while an earlier ticket
+// holder could use the free seats, so a later pair can't keep grabbing the table a
+// six-person family is waiting for. Opportunistic: whichever thread wakes first.
+// The DES always offers seats to waiting parties in ticket order, so it only runs Fifo.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) enum FairnessMode {
+    #[default]
+    Fifo,
+    Opportunistic,
+}
+
+impl FairnessMode {
+    fn from_name(name: &str) -> Result<Self> {
+        match name.to_uppercase().as_str() {
+            "FIFO" => Ok(FairnessMode::Fifo),
+            "OPPORTUNISTIC" => Ok(FairnessMode::Opportunistic),
+            _ => Err(AppError::SimulationError(format!("Unknown fairness mode: {}", name))),
+        }
+    }
+
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            FairnessMode::Fifo => "FIFO",
+            FairnessMode::Opportunistic => "OPPORTUNISTIC",
+        }
+    }
+}
+
+// Shadow mode: a secondary policy is asked the same allocation questions as the
+// real one, but its answers are only recorded, never applied.
+#[derive(Clone)]
+struct ShadowState {
+    policy: &'static dyn AllocationStrategy,
+    decisions_compared: usize,
+    divergences: Vec<ShadowDecision>,
 }
 
 #[derive(Clone, Debug)]
 struct SeatState {
     config: SeatConfig,
     occupied_by: Option<u32>,
+    baby_chairs: u32, // baby chairs placed at this seat by its occupant
+    wheelchairs: u32, // wheelchair users seated here
+}
+
+// Where a seated party sits: its seats and the baby chairs and wheelchairs at each of them
+#[derive(Debug, Clone)]
+struct Placement {
+    seat_ids: String,      // comma-separated, as logged
+    baby_chairs: Vec<u32>, // per seat, in seat_ids order
+    wheelchairs: Vec<u32>, // per seat, in seat_ids order
+}
+
+impl Placement {
+    // Put the baby chairs a party got and its wheelchairs at its seats in order, each seat
+    // up to what it can take. Anything left over (a booking or merge the limits don't
+    // cover) is spread by the places at each seat, and past that goes to the largest one.
+    fn new(customer: &CustomerConfig, seat_ids: &[String], seats: &[SeatState], baby_chairs: u32) -> Self {
+        let configs: Vec<Option<&SeatConfig>> = seat_ids.iter()
+            .map(|sid| seats.iter().find(|s| s.config.id == *sid).map(|s| &s.config))
+            .collect();
+        let places = |config: &SeatConfig| seat_capacity(&config.type_);
+        let spread = |count: u32, limit: fn(&SeatConfig) -> u32| {
+            let mut left = count;
+            let mut placed = vec![0; configs.len()];
+            for room in [limit, places] {
+                for (here, config) in placed.iter_mut().zip(&configs) {
+                    let more = left.min(config.map_or(0, room).saturating_sub(*here));
+                    *here += more;
+                    left -= more;
+                }
+            }
+            let largest = (0..configs.len()).max_by_key(|&i| (configs[i].map_or(0, places), std::cmp::Reverse(i)));
+            if let Some(i) = largest {
+                placed[i] += left;
+            }
+            placed
+        };
+        let baby_chairs = spread(baby_chairs, baby_chair_limit);
+        let wheelchairs = spread(customer.wheelchair_count, wheelchair_limit);
+
+        Placement { seat_ids: seat_ids.join(","), baby_chairs, wheelchairs }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -34,9 +415,22 @@ struct SimEvent {
 enum Action {
     Arrive,
     Wait,   
-    Sit(String),
+    Sit(Placement),
     Leave(String),
     Error,
+    Renege, // gave up waiting after the party's max_wait_time
+    Deadlock, // the log message describes the wait cycle
+    Deliver(String, u32), // baby chairs brought to a seated party: seat, count
+    Requirements(u32, u32, u32), // a waiting party's new size, baby chairs and wheelchairs
+    Priority, // seated ahead of lower priority families waiting since earlier (named in the log)
+    Infeasible, // can never be seated; the log message says why
+    Courier(&'static str), // a step of a courier order (family id in COURIER_IDS), named by its event type
+}
+
+// Log an engine step for the party and append it to the run
+fn log_event(res: &mut SushiResources, time: u64, customer: &CustomerConfig, action: Action, event_type: &str, result: &str) {
+    let log = generate_log(time, customer, event_type, result, res);
+    res.events.append(time, customer.family_id, action, log);
 }
 
 // Helper: Generate detailed log matching output_rule.txt
@@ -48,6 +442,14 @@ fn generate_log(
     result_str: &str, 
     res: &SushiResources
 ) -> String {
+    // Generate requirements string
+    let mut req_parts = vec![format!("{} seats", customer.party_size)];
+    if customer.baby_chair_count > 0 { req_parts.push(format!("{} baby_chair", customer.baby_chair_count)); }
+    if customer.wheelchair_count > 0 { req_parts.push(format!("{} wheelchair", customer.wheelchair_count)); }
+    format_log(time, &customer.id.to_string(), &req_parts.join(", "), event_type, result_str, res)
+}
+
+fn format_log(time: u64, id: &str, req_str: &str, event_type: &str, result_str: &str, res: &SushiResources) -> String {
     // Calculate remaining seats
     let s_cnt = res.seats.iter().filter(|s| s.config.type_ == "SINGLE" && s.occupied_by.is_none()).count();
     let p4_cnt = res.seats.iter().filter(|s| s.config.type_ == "4P" && s.occupied_by.is_none()).count();
@@ -58,51 +460,433 @@ fn generate_log(
         .replace("ThreadId(", "")
         .replace(")", "");
 
-    // Generate requirements string
-    let mut req_parts = vec![format!("{} seats", customer.party_size)];
-    if customer.baby_chair_count > 0 { req_parts.push(format!("{} baby_chair", customer.baby_chair_count)); }
-    if customer.wheelchair_count > 0 { req_parts.push(format!("{} wheelchair", customer.wheelchair_count)); }
-    let req_str = req_parts.join(", ");
-
     format!(
         "[{}] [{}] [{}] ID: {} | Requirements: {} | {} | Remaining: S={}, 4P={}, 6P={}, B={}, W={}",
         thread_id,
         time,
         event_type, 
-        customer.id,
+        id,
         req_str,
         result_str,
         s_cnt, p4_cnt, p6_cnt, 
-        res.baby_chairs_available, 
-        res.wheelchairs_available
+        res.ledger.available(BABY_CHAIR_POOL),
+        res.ledger.available(WHEELCHAIR_POOL)
     )
 }
 
+// Lets the UI freeze a running simulation between steps (pause_simulation). Workers
+// pass the gate before each step and block there while it is closed; dining clocks
+// stop too, so queued and seated parties resume exactly where they were.
+#[derive(Default)]
+pub(crate) struct PauseGate {
+    paused: Mutex<bool>,
+    resumed: Condvar,
+}
+
+impl PauseGate {
+    fn set(&self, paused: bool) {
+        *self.paused.lock().unwrap() = paused;
+        self.resumed.notify_all();
+    }
+
+    fn is_paused(&self) -> bool {
+        *self.paused.lock().unwrap()
+    }
+
+    // Block while paused (or until shutdown); returns how long that was
+    fn pass(&self, cancel: &AtomicBool) -> Duration {
+        let start = Instant::now();
+        let mut paused = self.paused.lock().unwrap();
+        while *paused && !cancel.load(Ordering::SeqCst) {
+            paused = self.resumed.wait_timeout(paused, Duration::from_millis(CANCEL_POLL_MS)).unwrap().0;
+        }
+        start.elapsed()
+    }
+}
+
+fn pass_gate(params: &EngineParams, cancel: &AtomicBool) -> Duration {
+    params.gate.as_ref().map(|g| g.pass(cancel)).unwrap_or_default()
+}
+
+// Pause gates of the simulations currently running, by session
+#[derive(Default)]
+pub struct RunControlStore {
+    gates: Mutex<std::collections::HashMap<String, Arc<PauseGate>>>,
+}
+
+impl RunControlStore {
+    fn begin(&self, session_id: &str) -> Arc<PauseGate> {
+        let gate = Arc::new(PauseGate::default());
+        self.gates.lock().unwrap().insert(session_id.to_string(), Arc::clone(&gate));
+        gate
+    }
+
+    fn end(&self, session_id: &str, gate: &Arc<PauseGate>) {
+        let mut gates = self.gates.lock().unwrap();
+        // A newer run in the same session may have replaced it already
+        if gates.get(session_id).is_some_and(|g| Arc::ptr_eq(g, gate)) {
+            gates.remove(session_id);
+        }
+    }
+
+    fn set_paused(&self, session_id: &str, paused: bool) -> Result<bool> {
+        let gates = self.gates.lock().unwrap();
+        let gate = gates.get(session_id)
+            .ok_or_else(|| AppError::SessionError(format!("No simulation is running in session {}", session_id)))?;
+        gate.set(paused);
+        Ok(paused)
+    }
+}
+
+// Freeze the session's running simulation; get_current_state keeps serving its snapshot
+#[tauri::command]
+pub fn pause_simulation(session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>, runs: State<'_, RunControlStore>) -> Result<bool> {
+    access::require_host(&window, "pause simulations")?;
+    let session_id = sessions.resolve(session_id, &window);
+    runs.set_paused(&session_id, true)
+}
+
 #[tauri::command]
-pub fn load_customers(csv_content: String) -> Result<Vec<CustomerConfig>> {
-    parser::parse_customers(&csv_content).map_err(|e| AppError::CsvParseError(e.to_string()))
+pub fn resume_simulation(session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>, runs: State<'_, RunControlStore>) -> Result<bool> {
+    access::require_host(&window, "resume simulations")?;
+    let session_id = sessions.resolve(session_id, &window);
+    runs.set_paused(&session_id, false).map(|paused| !paused)
 }
 
+// Sleep for the given duration, waking early if a shutdown was requested. Time spent
+// paused doesn't count.
+fn sleep_unless_cancelled(duration: Duration, cancel: &AtomicBool, gate: Option<&PauseGate>) {
+    let mut deadline = Instant::now() + duration;
+    loop {
+        if cancel.load(Ordering::SeqCst) { return; }
+        if let Some(gate) = gate {
+            deadline += gate.pass(cancel);
+        }
+        let now = Instant::now();
+        if now >= deadline { return; }
+        thread::sleep(std::cmp::min(deadline - now, Duration::from_millis(CANCEL_POLL_MS)));
+    }
+}
+
+// Parse a roster without running it, with per-row issues for a preview grid
 #[tauri::command]
+pub fn preview_customers(csv_content: String) -> Result<CustomerPreview> {
+    let customers = parser::parse_customers(&csv_content).map_err(|e| AppError::CsvParseError(e.to_string()))?;
+    let issues = diagnostics::customer_row_issues(&csv_content);
+    let has_errors = issues.iter().any(|i| i.severity == DiagnosticSeverity::Error);
+    Ok(CustomerPreview { customers, issues, has_errors })
+}
+
+// Original entry point, kept for existing callers; prefer run_simulation with a SimulationConfig.
+// Simulation commands run off the main thread so get_current_state is served meanwhile.
+#[tauri::command(async)]
+#[allow(clippy::too_many_arguments)]
 pub fn start_simulation(
     csv_content: String, 
     seat_config_json: String,
     baby_chairs: i32,
-    wheelchairs: i32
+    wheelchairs: i32,
+    shadow_strategy: Option<String>,
+    objective: Option<String>,
+    conveyor_patience: Option<u64>,
+    kitchen: Option<KitchenConfig>,
+    strategy: Option<String>,
+    session_id: Option<String>,
+    window: Window,
+    sessions: State<'_, SessionStore>
 ) -> Result<Vec<SimulationFrame>> {
-    let customers = parser::parse_customers(&csv_content)
-        .map_err(|e| AppError::CsvParseError(e.to_string()))?;
+    access::require_host(&window, "run simulations")?;
+    let config = SimulationConfig::builder(csv_content, seat_config_json)
+        .baby_chairs(baby_chairs)
+        .wheelchairs(wheelchairs)
+        .shadow_strategy(shadow_strategy)
+        .objective(objective)
+        .conveyor_patience(conveyor_patience.unwrap_or(DEFAULT_CONVEYOR_PATIENCE))
+        .kitchen(kitchen.unwrap_or_default())
+        .strategy(strategy)
+        .build();
+    simulate(config, sessions.resolve(session_id, &window), &sessions, &window)
+}
+
+#[tauri::command(async)]
+pub fn run_simulation(config: SimulationConfig, session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<Vec<SimulationFrame>> {
+    access::require_host(&window, "run simulations")?;
+    simulate(config, sessions.resolve(session_id, &window), &sessions, &window)
+}
+
+// Re-run the session after an edit to its roster. A DES re-run keeps checkpoints, so the
+// next one only re-simulates from the last checkpoint before the earliest party the edit
+// touches; any other change (seats, settings, engine) re-runs everything like run_simulation.
+#[tauri::command(async)]
+pub fn rerun_simulation(config: SimulationConfig, session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<Vec<SimulationFrame>> {
+    access::require_host(&window, "run simulations")?;
+    run_session(config, sessions.resolve(session_id, &window), &sessions, &window, RunKind::Incremental)
+}
+
+// Earliest arrival time at which two sorted rosters differ, or None if they match
+fn first_edit(old: &[CustomerConfig], new: &[CustomerConfig]) -> Option<u64> {
+    let same = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    [old.get(same), new.get(same)].into_iter().flatten().map(|c| c.arrival_time).min()
+}
+
+// The stored run's checkpoints and how many of them to keep, the last one kept being the
+// one to resume from for `config`, if only the roster changed and not before the first
+fn resume_point(run: &SessionRun, config: &SimulationConfig, customers: &[CustomerConfig]) -> Option<(Arc<Checkpoints>, usize)> {
+    let checkpoints = run.checkpoints.as_ref()?;
+    let settings = |c: &SimulationConfig| serde_json::to_value(SimulationConfig { csv_content: String::new(), ..c.clone() }).ok();
+    if settings(&run.config) != settings(config) {
+        return None;
+    }
+    let edit = first_edit(&run.customers, customers).unwrap_or(u64::MAX);
+    let kept = checkpoints.states.partition_point(|c| c.time <= edit);
+    (kept > 0).then(|| (Arc::clone(checkpoints), kept))
+}
+
+// Engine inputs a run config describes, besides the roster
+pub(crate) fn engine_params(config: &SimulationConfig, seats_config: &[SeatConfig]) -> Result<EngineParams> {
+    let reservations = match &config.reservations_json {
+        Some(json) => reservations::parse(json, seats_config)?,
+        None => Vec::new(),
+    };
+    // Checked up front so a repro bundle can always replay the roster's draws
+    if let Some(generator) = &config.generator {
+        generator::validate_config(generator)?;
+    }
+    couriers::validate(&config.couriers, config.kitchen.line_capacity)?;
+
+    let shadow_policy = config.shadow_strategy.as_deref().map(strategy::from_name).transpose()?;
+    let objective = config.objective.as_deref().map(AllocationObjective::from_name).transpose()?
+        .unwrap_or(AllocationObjective::Wait);
+    let strategy = config.strategy.as_deref().map(strategy::from_name).transpose()?
+        .unwrap_or(&strategy::DefaultStrategy);
+    let fairness = config.fairness.as_deref().map(FairnessMode::from_name).transpose()?.unwrap_or_default();
+    // Lock instrumentation only means something on real threads
+    let engine = match config.engine.as_deref() {
+        Some(name) => EngineBackend::from_name(name)?,
+        None if config.instrument || config.trace_sync => EngineBackend::Threads,
+        None => EngineBackend::default(),
+    };
+    let deadlock_resolution = config.deadlock_resolution.as_deref().map(DeadlockResolution::from_name).transpose()?
+        .unwrap_or_default();
+    if !config.requirement_changes.is_empty() && engine != EngineBackend::Des {
+        return Err(requirement_changes_need_des(engine));
+    }
+    if fairness == FairnessMode::Opportunistic && engine == EngineBackend::Des {
+        return Err(opportunistic_needs_threads());
+    }
+    if config.deliver_baby_chairs_later && engine == EngineBackend::Semaphores {
+        return Err(chairs_later_need_a_monitor());
+    }
+
+    Ok(EngineParams {
+        baby_chairs: config.baby_chairs,
+        wheelchairs: config.wheelchairs,
+        shadow_policy,
+        objective,
+        strategy,
+        fairness,
+        conveyor_patience: config.conveyor_patience,
+        kitchen: config.kitchen.clone(),
+        deadlock_resolution,
+        instrument: config.instrument,
+        trace_sync: config.trace_sync,
+        engine,
+        live: None,
+        gate: None,
+        deliver_baby_chairs_later: config.deliver_baby_chairs_later,
+        merge_tables: config.merge_tables,
+        reservations,
+        reservation_grace: config.reservation_grace,
+        // The restroom queues like any station, one trip per visiting guest
+        stations: config.stations.iter().cloned().chain(config.restroom.as_ref().map(|r| r.station())).collect(),
+        max_wait_time: config.max_wait_time,
+        requirement_changes: config.requirement_changes.clone(),
+        couriers: config.couriers.clone(),
+    })
+}
+
+fn requirement_changes_need_des(engine: EngineBackend) -> AppError {
+    AppError::ValidationError(format!("Requirement changes need the des engine, not {}", engine.name()))
+}
+
+fn opportunistic_needs_threads() -> AppError {
+    AppError::ValidationError("Opportunistic fairness needs a threaded engine (threads, semaphores or tasks), not des".to_string())
+}
+
+fn chairs_later_need_a_monitor() -> AppError {
+    AppError::ValidationError("Delivering baby chairs later needs the des, threads or tasks engine: the semaphores engine takes chairs before a seat".to_string())
+}
+
+// Run a simulation, store it under `session_id` and notify event subscribers
+pub(crate) fn simulate(config: SimulationConfig, session_id: String, sessions: &SessionStore, window: &Window) -> Result<Vec<SimulationFrame>> {
+    run_session(config, session_id, sessions, window, RunKind::Fresh)
+}
+
+// How run_session starts a run
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum RunKind {
+    Fresh,
+    Incremental, // resume the session's stored run from a checkpoint where possible
+    Streamed,    // push live updates on every engine, DES included
+}
+
+// simulate, optionally resuming the session's stored run from a checkpoint
+pub(crate) fn run_session(mut config: SimulationConfig, session_id: String, sessions: &SessionStore, window: &Window, kind: RunKind) -> Result<Vec<SimulationFrame>> {
+    let (mut customers, draws) = load_roster(&mut config)?;
+    // Stays depend on the time of day the party comes in
+    generator::validate_dining_bands(&config.dining_time_bands)?;
+    for c in &mut customers {
+        c.est_dining_time = generator::banded_dining_time(&config.dining_time_bands, c.arrival_time, c.est_dining_time);
+    }
     
+    let sorted_customers = sort_customers(customers);
+
+    let seats_config: Vec<SeatConfig> = serde_json::from_str(&config.seat_config_json)
+        .map_err(|e| AppError::JsonParseError(e.to_string()))?;
+
+    let params = engine_params(&config, &seats_config)?;
+    validation::validate_inputs(&sorted_customers, &seats_config, config.baby_chairs, config.wheelchairs, config.merge_tables)?;
+
+    if sorted_customers.is_empty() { return Ok(Vec::new()); }
+
+    let resume = if kind == RunKind::Incremental {
+        sessions.with_session(&session_id, |run| resume_point(run, &config, &sorted_customers)).ok().flatten()
+    } else {
+        None
+    };
+
+    // Track this run so app shutdown can cancel it and wait for its workers
+    let _run_guard = sessions.begin_run()?;
+    let cancel = sessions.cancel_flag();
+    let live_view = window.state::<LiveStateStore>().begin(&session_id);
+    let gate = window.state::<RunControlStore>().begin(&session_id);
+
+    // Wall-clock runs are watched as they happen; DES runs finish at once, so only a
+    // streamed one pushes its progress
+    let governor = match params.engine {
+        EngineBackend::Des if kind != RunKind::Streamed => None,
+        _ => live::govern(Arc::clone(&live_view), window.clone(), config.live_emit_rate),
+    };
+
+    let watched = EngineParams { live: Some(Arc::clone(&live_view)), gate: Some(Arc::clone(&gate)), ..params.clone() };
+    let resumed_from = resume.as_ref().map(|(base, kept)| base.states[kept - 1].time);
+    // DES runs are repeatable, so a re-run keeps checkpoints for the next one after a
+    // roster edit; other runs don't pay for them
+    let outcome = match resume {
+        Some((base, kept)) => des::resume(&base, kept, &sorted_customers, &watched, &cancel).map(|(res, c)| (res, Some(c))),
+        None if kind == RunKind::Incremental && params.engine == EngineBackend::Des => {
+            des::run_with_checkpoints(&sorted_customers, &seats_config, &watched, &cancel).map(|(res, c)| (res, Some(c)))
+        }
+        None => run_engine(&sorted_customers, &seats_config, &watched, &cancel).map(|res| (res, None)),
+    };
+    window.state::<RunControlStore>().end(&session_id, &gate);
+    live::finish(&live_view);
+    if let Some(governor) = governor {
+        governor.join();
+    }
+    let (res, checkpoints) = outcome?;
+    let frames = store_run(res, config, sorted_customers, seats_config, params, &session_id, sessions, window)?;
+    sessions.with_session(&session_id, |run| {
+        run.checkpoints = checkpoints.map(Arc::new);
+        run.metadata.resumed_from = resumed_from;
+        run.draws = draws;
+    })?;
+    Ok(frames)
+}
+
+// The run's roster: parsed from csv_content, or drawn by the config's generator when
+// there is no CSV. A drawn roster comes with every draw the run made for it, and an
+// unseeded generator gets its fresh seed written into the config, so the stored run
+// (and a repro bundle of it) draws the same roster again.
+fn load_roster(config: &mut SimulationConfig) -> Result<(Vec<CustomerConfig>, Vec<RngDraw>)> {
+    if config.csv_content.trim().is_empty() {
+        if let Some(generator) = &mut config.generator {
+            generator::validate_config(generator)?;
+            let seed = *generator.seed.get_or_insert_with(rng::fresh_seed);
+            return Ok(generator::recorded_roster(generator, seed));
+        }
+    }
+    let mode = if config.strict_csv { parser::ParseMode::Strict } else { parser::ParseMode::Lenient };
+    let customers = parser::parse_customers_with(&config.csv_content, mode)
+        .map_err(|e| AppError::CsvParseError(e.to_string()))?.customers;
+    Ok((customers, Vec::new()))
+}
+
+// Build the reports and frames of a finished run, keep it under `session_id` and
+// notify event subscribers
+#[allow(clippy::too_many_arguments)]
+fn store_run(mut res: SushiResources, config: SimulationConfig, sorted_customers: Vec<CustomerConfig>, seats_config: Vec<SeatConfig>, params: EngineParams, session_id: &str, sessions: &SessionStore, window: &Window) -> Result<Vec<SimulationFrame>> {
+    // Revenue and the reports downstream count each party as it was seated, after any
+    // requirement changes; the roster itself stays as submitted, for re-runs
+    let changed_parties = changed_parties(&res, &sorted_customers);
+    let parties: Vec<CustomerConfig> = sorted_customers.iter()
+        .map(|c| changed_parties.iter().find(|p| p.family_id == c.family_id).unwrap_or(c).clone())
+        .collect();
+    let shadow_report = build_shadow_report(&res, &sorted_customers, &seats_config, &params, &sessions.cancel_flag())?;
+    let kitchen_report = res.kitchen.report();
+    let station_report = res.stations.report(res.events.iter().map(|e| e.time).max().unwrap_or(0));
+    let engine_health = res.instrumentation.report();
+    let sync_trace = res.instrumentation.take_trace();
+    let ledger = res.ledger.take_entries();
+    let condiment_report = config.condiments.enabled
+        .then(|| crate::condiments::simulate(&parties, &sit_times(&res), &config.condiments));
+    let metadata = RunMetadata {
+        objective: params.objective.name().to_string(),
+        strategy: params.strategy.name().to_string(),
+        fairness: params.fairness.name().to_string(),
+        shadow_strategy: params.shadow_policy.map(|p| p.name().to_string()),
+        baby_chairs: params.baby_chairs,
+        wheelchairs: params.wheelchairs,
+        total_revenue: total_revenue(&res, &parties),
+        engine: params.engine.name().to_string(),
+        fingerprint: fingerprint(&res),
+        resumed_from: None,
+    };
+    let frames = generate_frames(&res, &seats_config, &sorted_customers, config.frame_resolution)?;
+    let announcements = crate::announcements::announcements(&frames, &parties);
+
+    // Keep the run around so session commands (memory, downsampling, ...) can use it.
+    // Without an explicit id the run belongs to the calling window's session.
+    sessions.insert(session_id, SessionRun {
+        config,
+        customers: sorted_customers,
+        seats_config,
+        params,
+        metadata,
+        frames: frames.clone(),
+        shadow_report,
+        kitchen_report,
+        station_report,
+        engine_health,
+        sync_trace,
+        ledger,
+        condiment_report,
+        announcements,
+        cost_model: Default::default(),
+        annotations: Default::default(),
+        bookmarks: Vec::new(),
+        day_report: None,
+        checkpoints: None,
+        draws: Vec::new(),
+        changed_parties,
+    });
+    window.state::<SubscriptionStore>().publish(window, session_id, &frames);
+
+    Ok(frames)
+}
+
+// Order customers for the engine (arrival time, pre-occupied first, then family id)
+pub(crate) fn sort_customers(customers: Vec<CustomerConfig>) -> Vec<CustomerConfig> {
     // Sort customers by arrival time
     // Use i64 for comparison to correctly handle -1 as being earlier than 0
     // If arrival times are equal, prioritize pre-occupied IDs (>= 1000)
-    let mut sorted_customers = customers.clone();
+    let mut sorted_customers = customers;
     sorted_customers.sort_by(|a, b| {
         let a_time = a.arrival_time as i64;
         let b_time = b.arrival_time as i64;
         if a_time == b_time {
-            let a_is_pre = a.family_id >= 1000 && a.family_id < 2000;
-            let b_is_pre = b.family_id >= 1000 && b.family_id < 2000;
+            let a_is_pre = parser::PRE_SEATED_IDS.contains(&a.family_id);
+            let b_is_pre = parser::PRE_SEATED_IDS.contains(&b.family_id);
             if a_is_pre != b_is_pre {
                 b_is_pre.cmp(&a_is_pre) // True (pre-occupied) comes first
             } else {
@@ -118,165 +902,175 @@ pub fn start_simulation(
     // but keep the sorted order which already prioritized -1
     // Also ensure pre-occupied customers (-1) have their arrival_time set to 0 
     // so they are processed at the start of the simulation timeline.
-    let mut pre_occupied_ids = std::collections::HashSet::new();
     for c in &mut sorted_customers {
         let raw_time = c.arrival_time as i64;
         if raw_time < 0 {
             c.arrival_time = 0;
-            pre_occupied_ids.insert(c.family_id);
         }
     }
 
-    let seats_config: Vec<SeatConfig> = serde_json::from_str(&seat_config_json)
-        .map_err(|e| AppError::JsonParseError(e.to_string()))?;
+    sorted_customers
 
-    if sorted_customers.is_empty() { return Ok(Vec::new()); }
+}
+
+// Inputs of a single engine run besides the customers and seats
+#[derive(Clone)]
+pub(crate) struct EngineParams {
+    pub baby_chairs: i32,
+    pub wheelchairs: i32,
+    pub shadow_policy: Option<&'static dyn AllocationStrategy>,
+    pub objective: AllocationObjective,
+    pub strategy: &'static dyn AllocationStrategy,
+    pub fairness: FairnessMode,
+    pub conveyor_patience: u64, // Longest a party with preference 1.0 waits for a conveyor seat
+    pub kitchen: KitchenConfig,
+    pub deadlock_resolution: DeadlockResolution, // semaphores engine: what a party closing a wait cycle does
+    pub instrument: bool, // collect lock/condvar counters for the engine-health report
+    pub trace_sync: bool, // record every lock, wait and notify for teaching mode
+    pub engine: EngineBackend,
+    pub live: Option<LiveView>, // only set for the run the UI is watching
+    pub gate: Option<Arc<PauseGate>>, // likewise, so the UI can pause it
+    pub deliver_baby_chairs_later: bool, // seat without free baby chairs, deliver them on release
+    pub merge_tables: bool, // parties bigger than any table may sit at adjacent tables pushed together
+    pub reservations: Vec<TableReservation>, // tables held for booked parties
+    pub reservation_grace: u64, // minutes a reserved table waits for a party that hasn't arrived
+    pub stations: Vec<SelfServiceStation>,
+    pub max_wait_time: Option<u64>, // minutes a party waits before leaving unserved, unless it sets its own
+    pub requirement_changes: Vec<RequirementChange>,
+    pub couriers: CourierConfig, // pickup orders sharing the kitchen line
+}
+
+// Run the simulation on the selected backend to completion and return the final resource state
+pub(crate) fn run_engine(customers: &[CustomerConfig], seats_config: &[SeatConfig], params: &EngineParams, cancel: &Arc<AtomicBool>) -> Result<SushiResources> {
+    // Every caller (batches, coupons, analysis, capacity plans) gets the same checks
+    validation::validate_inputs(customers, seats_config, params.baby_chairs, params.wheelchairs, params.merge_tables)?;
+    // Thread wake-up order decides who goes next there, so only the DES can keep a queue order
+    if params.strategy.needs_ordered_queue() && params.engine != EngineBackend::Des {
+        return Err(AppError::SimulationError(format!("Strategy {} needs the des engine, not {}", params.strategy.name(), params.engine.name())));
+    }
+    // Likewise, a change must reach a party at a well-defined point in its wait
+    if !params.requirement_changes.is_empty() && params.engine != EngineBackend::Des {
+        return Err(requirement_changes_need_des(params.engine));
+    }
+    // With no threads racing, the DES has no one to wake first
+    if params.fairness == FairnessMode::Opportunistic && params.engine == EngineBackend::Des {
+        return Err(opportunistic_needs_threads());
+    }
+    // A semaphores party blocks on the chair pool before it sits, so it can't sit without chairs
+    if params.deliver_baby_chairs_later && params.engine == EngineBackend::Semaphores {
+        return Err(chairs_later_need_a_monitor());
+    }
+    let mut res = match params.engine {
+        EngineBackend::Threads => run_threads(customers, seats_config, params, cancel),
+        EngineBackend::Des => des::run(customers, seats_config, params, cancel),
+        EngineBackend::Semaphores => semaphore::run(customers, seats_config, params, cancel),
+        EngineBackend::Tasks => tasks::run(customers, seats_config, params, cancel),
+    }?;
+    // Courier orders placed after the last party sat down
+    serve_couriers(&mut res, u64::MAX);
+    Ok(res)
+}
 
-    let initial_resources = SushiResources {
-        baby_chairs_available: baby_chairs,
-        wheelchairs_available: wheelchairs,
+fn initial_resources(seats_config: &[SeatConfig], params: &EngineParams) -> SushiResources {
+    SushiResources {
+        ledger: ResourceLedger::new(&[(BABY_CHAIR_POOL, params.baby_chairs), (WHEELCHAIR_POOL, params.wheelchairs)]),
         seats: seats_config.iter().map(|s| SeatState { 
             config: s.clone(), 
-            occupied_by: None 
+            occupied_by: None,
+            baby_chairs: 0,
+            wheelchairs: 0,
         }).collect(),
-        events: Vec::new(),
-    };
+        events: EventStore::new(seats_config),
+        shadow: params.shadow_policy.map(|policy| ShadowState {
+            policy,
+            decisions_compared: 0,
+            divergences: Vec::new(),
+        }),
+        kitchen: KitchenModel::new(params.kitchen.clone(), &params.couriers),
+        stations: StationModel::new(params.stations.clone()),
+        instrumentation: Instrumentation::new(params.instrument, params.trace_sync),
+        live: params.live.clone(),
+        pending_chairs: Default::default(),
+        tickets: Vec::new(),
+    }
+}
 
-    let monitor = Arc::new((Mutex::new(initial_resources), Condvar::new()));
+// Thread-per-customer monitor engine
+fn run_threads(customers: &[CustomerConfig], seats_config: &[SeatConfig], params: &EngineParams, cancel: &Arc<AtomicBool>) -> Result<SushiResources> {
+    let monitor = Arc::new((Mutex::new(initial_resources(seats_config, params)), Condvar::new()));
     let mut handles = vec![];
 
-    for customer in sorted_customers.clone() {
+    for customer in customers.iter().cloned() {
         let monitor_clone = Arc::clone(&monitor);
-        let _is_pre_occupied = pre_occupied_ids.contains(&customer.family_id);
+        let params = params.clone();
+        let cancel = Arc::clone(cancel);
         
         let handle = thread::spawn(move || {
             let (lock, cvar) = &*monitor_clone;
             
             // 1. Arrive
-            {
-                let mut res = lock.lock().unwrap();
-                let log = generate_log(customer.arrival_time, &customer, "ARRIVAL", "arrived", &res);
-                let seq = res.events.len();
-                res.events.push(SimEvent {
-                    time: customer.arrival_time, 
-                    sequence: seq,
-                    family_id: customer.family_id,
-                    action: Action::Arrive, log_message: log,
-                });
-            }
+            pass_gate(&params, &cancel);
+            let mut waiter = Waiter::arrive(&mut lock_timed(lock, customer.family_id), customer);
+            let family_id = waiter.customer.family_id;
 
             // 2. Wait & Allocate
-            let seated_seat_ids: Vec<String>;
-            let mut res = lock.lock().unwrap();
-            let mut has_logged_wait = false; // Avoid duplicate wait logging
-            
-            loop {
-                // Try to allocate resources (Atomic check and allocation)
-                if let Some(seat_ids) = try_allocate(&res, &customer) {
-                    // Allocation success: deduct resources
-                    res.baby_chairs_available -= customer.baby_chair_count as i32;
-                    res.wheelchairs_available -= customer.wheelchair_count as i32;
-                    
-                    for sid in &seat_ids {
-                        if let Some(seat) = res.seats.iter_mut().find(|s| s.config.id == *sid) {
-                            seat.occupied_by = Some(customer.family_id);
-                        }
-                    }
-                    seated_seat_ids = seat_ids;
-
-                    // Generate SEATED log immediately while holding the lock to ensure atomicity
-                    let last_time = res.events.last().map(|e| e.time).unwrap_or(0);
-                    let sit_time = std::cmp::max(last_time, customer.arrival_time);
-                    let seat_str = seated_seat_ids.join(",");
-                    let result_str = format!("seated, id:[{}]", seat_str);
-                    
-                    let log = generate_log(sit_time, &customer, "SEATED", &result_str, &res);
-                    let seq = res.events.len();
-                    res.events.push(SimEvent {
-                        time: sit_time, 
-                        sequence: seq,
-                        family_id: customer.family_id,
-                        action: Action::Sit(seat_str.clone()),
-                        log_message: log,
-                    });
-
-                    break; // Exit wait loop
+            let mut res = lock_timed(lock, family_id);
+            let (seated_seat_ids, dining_time) = loop {
+                // Don't hold the monitor while paused
+                if params.gate.as_ref().is_some_and(|g| g.is_paused()) {
+                    drop(res);
+                    pass_gate(&params, &cancel);
+                    res = lock_timed(lock, family_id);
                 }
 
-                // Pre-occupied customers MUST be seated at time 0. 
-                // If resources are unavailable, they still wait but this should not happen 
-                // if the restaurant capacity is configured correctly for the initial state.
-                
-                // Allocation failed: log WAITING event if first time
-                if !has_logged_wait {
-                    let log = generate_log(customer.arrival_time, &customer, "WAITING", "waited", &res);
-                    let seq = res.events.len();
-                    res.events.push(SimEvent {
-                        time: customer.arrival_time, 
-                        sequence: seq,
-                        family_id: customer.family_id,
-                        action: Action::Wait, log_message: log,
-                    });
-                    has_logged_wait = true;
+                match waiter.attempt(&mut res, &params, &cancel) {
+                    Turn::Seated(seat_ids, dining_time) => break (seat_ids, dining_time),
+                    Turn::Gone => {
+                        notify_all_counted(cvar, &mut res, family_id);
+                        return;
+                    }
+                    // Wait for notification (bounded so the shutdown flag is polled)
+                    Turn::Wait => {
+                        let (guard, timed_out) = wait_timed(cvar, res, family_id, Duration::from_millis(CANCEL_POLL_MS));
+                        res = guard;
+                        waiter.woke(timed_out);
+                    }
                 }
-
-                // Wait for notification
-                res = cvar.wait(res).unwrap();
-            }
+            };
 
             // 3. Dining (Lock is released here)
             drop(res); 
-            thread::sleep(Duration::from_millis(customer.est_dining_time * 10));
+            sleep_unless_cancelled(Duration::from_millis(dining_time * REALTIME_MS_PER_MINUTE), &cancel, params.gate.as_deref());
 
             // 4. Leave
-            let mut res = lock.lock().unwrap();
-            let sit_time = res.events.iter()
-                .filter(|e| e.family_id == customer.family_id)
-                .filter_map(|e| if let Action::Sit(_) = e.action { Some(e.time) } else { None })
-                .next()
-                .unwrap_or(customer.arrival_time);
-            
-            let leave_time = sit_time + customer.est_dining_time;
-            
-            // Return resources
-            res.baby_chairs_available += customer.baby_chair_count as i32;
-            res.wheelchairs_available += customer.wheelchair_count as i32;
-            
-            for sid in &seated_seat_ids {
-                if let Some(seat) = res.seats.iter_mut().find(|s| s.config.id == *sid) {
-                    seat.occupied_by = None;
-                }
-            }
-            
-            let seat_str = seated_seat_ids.join(",");
-            let result_str = format!("release, id:[{}]", seat_str);
-            let log = generate_log(leave_time, &customer, "LEFT", &result_str, &res);
-            
-            let seq = res.events.len();
-            res.events.push(SimEvent {
-                time: leave_time, 
-                sequence: seq,
-                family_id: customer.family_id,
-                action: Action::Leave(seat_str),
-                log_message: log,
-            });
-            
-            cvar.notify_all(); // Notify waiting customers
+            let mut res = lock_timed(lock, family_id);
+            waiter.leave(&mut res, &seated_seat_ids, dining_time);
+            notify_all_counted(cvar, &mut res, family_id); // Notify waiting customers
         });
         handles.push(handle);
     }
 
     for h in handles { let _ = h.join(); }
 
-    generate_frames(monitor, &seats_config, &sorted_customers)
+    // Partial results of a cancelled run are not worth keeping
+    if cancel.load(Ordering::SeqCst) {
+        return Err(AppError::SimulationError("Simulation cancelled by application shutdown".to_string()));
+    }
+
+    let (lock, _) = Arc::try_unwrap(monitor)
+        .map_err(|_| AppError::SimulationError("Worker threads still hold the monitor".to_string()))?;
+    Ok(lock.into_inner().unwrap())
 }
 
-fn try_allocate(res: &SushiResources, customer: &CustomerConfig) -> Option<Vec<String>> {
+// Whether the baby chair and wheelchair pools can cover the party
+fn pools_available(res: &SushiResources, customer: &CustomerConfig) -> bool {
+    res.ledger.available(BABY_CHAIR_POOL) >= customer.baby_chair_count as i32
+        && res.ledger.available(WHEELCHAIR_POOL) >= customer.wheelchair_count as i32
+}
+
+fn try_allocate(res: &SushiResources, open: &SeatMask, customer: &CustomerConfig) -> Option<Vec<String>> {
     // 1. Check global resources (Baby Chairs & Wheelchairs)
-    if customer.baby_chair_count > 0 && res.baby_chairs_available < customer.baby_chair_count as i32 {
-        return None;
-    }
-    if customer.wheelchair_count > 0 && res.wheelchairs_available < customer.wheelchair_count as i32 {
+    if !pools_available(res, customer) {
         return None;
     }
 
@@ -288,7 +1082,7 @@ fn try_allocate(res: &SushiResources, customer: &CustomerConfig) -> Option<Vec<S
         // Occupy the entire table, no sharing with others
         let seat = res.seats.iter()
             .find(|s| {
-                s.occupied_by.is_none() && 
+                open.is_free(s) && 
                 s.config.is_wheelchair_accessible && 
                 s.config.type_ != "SINGLE"
             });
@@ -301,7 +1095,7 @@ fn try_allocate(res: &SushiResources, customer: &CustomerConfig) -> Option<Vec<S
         // 1. Try to find a perfect match or larger sofa
         // Sort sofas to try 4P before 6P for smaller families to save larger tables
         let mut sofas: Vec<&SeatState> = res.seats.iter()
-            .filter(|s| s.occupied_by.is_none() && s.config.type_ != "SINGLE")
+            .filter(|s| open.is_free(s) && s.config.type_ != "SINGLE")
             .collect();
         
         sofas.sort_by_key(|s| if s.config.type_ == "4P" { 4 } else { 6 });
@@ -317,24 +1111,14 @@ fn try_allocate(res: &SushiResources, customer: &CustomerConfig) -> Option<Vec<S
         } else {
             // 2. ONLY if NO sofas are available, try to downgrade to bar
             // Check if there are enough consecutive bar seats
-            let single_seats: Vec<&SeatState> = res.seats.iter()
-                .filter(|s| s.config.type_ == "SINGLE")
-                .collect();
-            
-            if customer.party_size <= single_seats.len() as u32 {
-                for i in 0..=single_seats.len().saturating_sub(customer.party_size as usize) {
-                    let window = &single_seats[i..i+customer.party_size as usize];
-                    if window.iter().all(|s| s.occupied_by.is_none()) {
-                        chosen_seats = window.iter().map(|s| s.config.id.clone()).collect();
-                        break;
-                    }
-                }
+            if let Some(run) = free_bar_run(res, open, customer.party_size as usize) {
+                chosen_seats = run;
             }
         }
     } else {
         // Individuals: MUST use bar (SINGLE) first
         let bar_seat = res.seats.iter()
-            .find(|s| s.occupied_by.is_none() && s.config.type_ == "SINGLE");
+            .find(|s| open.is_free(s) && s.config.type_ == "SINGLE");
             
         if let Some(s) = bar_seat {
             chosen_seats.push(s.config.id.clone());
@@ -342,7 +1126,7 @@ fn try_allocate(res: &SushiResources, customer: &CustomerConfig) -> Option<Vec<S
              // Fallback: Only use sofa if NO bar seats are available (Lowest priority)
              // This is strictly for when the bar is completely full
              let mut sofas: Vec<&SeatState> = res.seats.iter()
-                .filter(|s| s.occupied_by.is_none() && s.config.type_ != "SINGLE")
+                .filter(|s| open.is_free(s) && s.config.type_ != "SINGLE")
                 .collect();
              
              // For individuals, try 4P before 6P
@@ -361,127 +1145,804 @@ fn try_allocate(res: &SushiResources, customer: &CustomerConfig) -> Option<Vec<S
     }
 }
 
-// Generate Frames needed for frontend
-fn generate_frames(monitor: Arc<(Mutex<SushiResources>, Condvar)>, seats_config: &Vec<SeatConfig>, customers: &Vec<CustomerConfig>) -> Result<Vec<SimulationFrame>> {
-    let result_lock = monitor.0.lock().unwrap();
-    let mut sorted_events = result_lock.events.clone();
-    // Use stable sort considering sequence to ensure correct order
-    sorted_events.sort_by(|a, b| a.time.cmp(&b.time).then(a.sequence.cmp(&b.sequence)));
+// Take the chosen seats and pools for a party and log it; returns the dining time
+fn seat_party(res: &mut SushiResources, customer: &CustomerConfig, seat_ids: &[String], sit_time: u64) -> u64 {
+    // Audit trail for the queue order: who could have had these seats instead
+    let passed = res.passed_over(customer, seat_ids);
 
-    let max_time = sorted_events.last().map(|e| e.time).unwrap_or(0);
-    let mut frames = Vec::new();
-    
-    let mut current_seats: Vec<Seat> = seats_config.iter().map(|s| Seat {
-        id: s.id.clone(), type_: s.type_.clone(), occupied_by: None,
-        occupant_type: None,
-        baby_chair_count: 0, 
-        is_wheelchair_accessible: s.is_wheelchair_accessible,
+    // Only short when deliver_baby_chairs_later let the party sit without them
+    let chairs = customer.baby_chair_count.min(res.ledger.available(BABY_CHAIR_POOL).max(0) as u32);
+    let missing = customer.baby_chair_count - chairs;
+    res.ledger.acquire(BABY_CHAIR_POOL, chairs, customer.family_id, sit_time);
+    res.ledger.acquire(WHEELCHAIR_POOL, customer.wheelchair_count, customer.family_id, sit_time);
+
+    let placement = Placement::new(customer, seat_ids, &res.seats, chairs);
+    for (i, sid) in seat_ids.iter().enumerate() {
+        if let Some(seat) = res.seats.iter_mut().find(|s| s.config.id == *sid) {
+            seat.occupied_by = Some(customer.family_id);
+            seat.baby_chairs = placement.baby_chairs[i];
+            seat.wheelchairs = placement.wheelchairs[i];
+        }
+    }
+
+    let mut result_str = format!("seated, id:[{}]", placement.seat_ids);
+    for (label, needed, per_seat) in [("baby_chair", customer.baby_chair_count, &placement.baby_chairs), ("wheelchair", customer.wheelchair_count, &placement.wheelchairs)] {
+        if needed == 0 { continue; }
+        let at: Vec<String> = seat_ids.iter().zip(per_seat)
+            .filter(|(_, &n)| n > 0)
+            .map(|(sid, n)| format!("{}x{}", sid, n))
+            .collect();
+        result_str.push_str(&format!(", {}:[{}]", label, at.join(",")));
+    }
+    if missing > 0 {
+        result_str.push_str(&format!(", baby_chair pending x{}", missing));
+    }
+    if !passed.is_empty() {
+        let ids: Vec<String> = passed.iter().map(u32::to_string).collect();
+        let result = format!("priority {}, ahead of waiting families [{}]", customer.priority, ids.join(","));
+        let log = generate_log(sit_time, customer, PRIORITY_SEATED_EVENT, &result, res);
+        res.events.append(sit_time, customer.family_id, Action::Priority, log);
+    }
+    let log = generate_log(sit_time, customer, "SEATED", &result_str, res);
+    res.events.append(sit_time, customer.family_id, Action::Sit(placement), log);
+
+    res.drop_ticket(customer.family_id);
+    if missing > 0 {
+        res.pending_chairs.push_back(PendingClaim { customer: customer.clone(), missing });
+    }
+
+    res.publish_live();
+
+    // Kitchen adjusts the stay: dietary orders queue at the dedicated
+    // station, ordered-ahead food is ready on seating, and standard orders
+    // share the line with the courier orders fired before them. Queues at
+    // the self-service stations stretch it further.
+    serve_couriers(res, sit_time);
+    res.kitchen.dining_time(sit_time, customer) + res.stations.visit(sit_time, customer)
+}
+
+// Fire the courier orders placed up to `until` and log each courier's visit: the order,
+// the arrival at the pickup counter and the departure with the food
+fn serve_couriers(res: &mut SushiResources, until: u64) {
+    let fired = res.kitchen.fire_couriers(until).to_vec();
+    for p in fired {
+        let id = format!("C{}", p.id);
+        let family_id = couriers::COURIER_IDS.start + p.id;
+        let waited = if p.sla_met { format!("after {} minutes", p.wait) } else { format!("after {} minutes, over the SLA", p.wait) };
+        let steps = [
+            (p.order_time, couriers::COURIER_ORDER_EVENT, format!("order fired, ready at {}", p.ready_time)),
+            (p.arrival_time, couriers::COURIER_ARRIVAL_EVENT, "arrived at the pickup counter".to_string()),
+            (p.departure, couriers::COURIER_LEFT_EVENT, format!("picked up at {} {}", p.pickup_start, waited)),
+        ];
+        for (time, event_type, result) in steps {
+            let log = format_log(time, &id, "pickup", event_type, &result, res);
+            res.events.append(time, family_id, Action::Courier(event_type), log);
+        }
+    }
+}
+
+// Return a party's seats and pools and log the departure
+fn release_party(res: &mut SushiResources, customer: &CustomerConfig, seat_ids: &[String], leave_time: u64) {
+    res.ledger.release(WHEELCHAIR_POOL, customer.wheelchair_count, customer.family_id, leave_time);
+
+    // Return the baby chairs actually at the table; an unmet claim lapses
+    for sid in seat_ids {
+        if let Some(seat) = res.seats.iter_mut().find(|s| s.config.id == *sid) {
+            res.ledger.release(BABY_CHAIR_POOL, seat.baby_chairs, customer.family_id, leave_time);
+            seat.occupied_by = None;
+            seat.baby_chairs = 0;
+            seat.wheelchairs = 0;
+        }
+    }
+    res.pending_chairs.retain(|claim| claim.customer.family_id != customer.family_id);
+
+    let seat_str = seat_ids.join(",");
+    let result_str = format!("release, id:[{}]", seat_str);
+    let log = generate_log(leave_time, customer, "LEFT", &result_str, res);
+    res.events.append(leave_time, customer.family_id, Action::Leave(seat_str), log);
+    deliver_pending_chairs(res, leave_time);
+    res.publish_live();
+}
+
+// Minutes a party waits for a seat before leaving unserved: its own max_wait_time, else
+// the run's; None waits for as long as it takes
+pub(crate) fn wait_limit(customer: &CustomerConfig, params: &EngineParams) -> Option<u64> {
+    customer.max_wait_time.or(params.max_wait_time)
+}
+
+// A waiting party gives up at `at` without being seated and stops queueing for seats
+fn renege(res: &mut SushiResources, customer: &CustomerConfig, at: u64) {
+    res.drop_ticket(customer.family_id);
+    let result = format!("left unserved after {} min", at.saturating_sub(customer.arrival_time));
+    let log = generate_log(at, customer, RENEGE_EVENT, &result, res);
+    res.events.append(at, customer.family_id, Action::Renege, log);
+    res.publish_live();
+}
+
+// Why no state of the run could ever seat the party, if so: the layout's seats and the
+// chair pools don't add up to its needs. Parties that may sit before their baby chairs
+// arrive don't need the chair pool to be seated.
+fn infeasibility(res: &SushiResources, customer: &CustomerConfig, params: &EngineParams) -> Option<String> {
+    let seats: Vec<SeatConfig> = res.seats.iter().map(|s| s.config.clone()).collect();
+    let baby_chairs = if params.deliver_baby_chairs_later { i32::MAX } else { params.baby_chairs };
+    feasibility::party_infeasibility(customer, &seats, baby_chairs, params.wheelchairs, params.merge_tables)
+        .map(|(_, message)| message)
+}
+
+// Turn away a party that can never be seated rather than let it wait forever
+fn refuse(res: &mut SushiResources, customer: &CustomerConfig, at: u64, reason: &str) {
+    res.drop_ticket(customer.family_id);
+    let log = generate_log(at, customer, INFEASIBLE_EVENT, reason, res);
+    res.events.append(at, customer.family_id, Action::Infeasible, log);
+    res.publish_live();
+}
+
+// Hand freed baby chairs to seated parties still owed them, oldest claim first
+fn deliver_pending_chairs(res: &mut SushiResources, now: u64) {
+    while res.ledger.available(BABY_CHAIR_POOL) > 0 {
+        let Some(claim) = res.pending_chairs.front_mut() else { return };
+        let count = claim.missing.min(res.ledger.available(BABY_CHAIR_POOL) as u32);
+        claim.missing -= count;
+        let customer = claim.customer.clone();
+        if claim.missing == 0 {
+            res.pending_chairs.pop_front();
+        }
+        res.ledger.acquire(BABY_CHAIR_POOL, count, customer.family_id, now);
+
+        // Bring them to the party's seat with room and the fewest chairs
+        let Some(seat) = res.seats.iter_mut()
+            .filter(|s| s.occupied_by == Some(customer.family_id))
+            .min_by_key(|s| (s.baby_chairs >= baby_chair_limit(&s.config), s.baby_chairs)) else { continue };
+        seat.baby_chairs += count;
+        let seat_id = seat.config.id.clone();
+
+        let log = generate_log(now, &customer, DELIVERED_EVENT, &format!("baby_chair x{} to id:[{}]", count, seat_id), res);
+        res.events.append(now, customer.family_id, Action::Deliver(seat_id, count), log);
+    }
+}
+
+// First SEATED time per family (families that never got a seat are absent)
+pub(crate) fn sit_times(res: &SushiResources) -> std::collections::HashMap<u32, u64> {
+    res.events.seatings().seated.iter()
+        .map(|(family_id, (time, _))| (*family_id, *time))
+        .collect()
+}
+
+// Alternative policy: pick the option that wastes the fewest seats.
+// Unlike the default heuristic, small families may take contiguous bar seats
+// while a sofa is free, and wheelchair users get the smallest accessible table that fits.
+fn try_allocate_best_fit(res: &SushiResources, open: &SeatMask, customer: &CustomerConfig) -> Option<Vec<String>> {
+    if !pools_available(res, customer) {
+        return None;
+    }
+
+    let party = customer.party_size;
+    // (wasted seats, seat ids)
+    let mut candidates: Vec<(u32, Vec<String>)> = Vec::new();
+
+    for s in res.seats.iter().filter(|s| open.is_free(s)) {
+        let capacity = seat_capacity(&s.config.type_);
+        if capacity < party { continue; }
+        if customer.wheelchair_count > 0 && (!s.config.is_wheelchair_accessible || s.config.type_ == "SINGLE") { continue; }
+        candidates.push((capacity - party, vec![s.config.id.clone()]));
+    }
+
+    if customer.wheelchair_count == 0 && party > 1 {
+        if let Some(run) = free_bar_run(res, open, party as usize) {
+            candidates.push((0, run));
+        }
+    }
+
+    if candidates.is_empty() && customer.wheelchair_count > 0 {
+        // Match the default policy's leniency: any free accessible table
+        return try_allocate(res, open, customer);
+    }
+
+    // min_by_key keeps the first of equal candidates, i.e. seat config order
+    candidates.into_iter().min_by_key(|(waste, _)| *waste).map(|(_, ids)| ids)
+}
+
+// Baby chairs a seat can take: none at the bar and one per place at a table, unless the
+// layout says otherwise
+pub(crate) fn baby_chair_limit(seat: &SeatConfig) -> u32 {
+    seat.max_baby_chairs.unwrap_or(if seat.type_ == "SINGLE" { 0 } else { seat_capacity(&seat.type_) })
+}
+
+// Wheelchair spaces a seat has: one at an accessible table unless the layout says
+// otherwise, none anywhere else
+pub(crate) fn wheelchair_limit(seat: &SeatConfig) -> u32 {
+    if !seat.is_wheelchair_accessible || seat.type_ == "SINGLE" { return 0; }
+    seat.wheelchair_capacity.unwrap_or(1)
+}
+
+// Parties with tolerance columns get the most comfortable seats on offer: seats within
+// every tolerance first, then the least uncomfortable ones. Comfort is a preference, so
+// a party never waits for it.
+fn allocate_by_objective(res: &SushiResources, open: &SeatMask, customer: &CustomerConfig, params: &EngineParams) -> Option<Vec<String>> {
+    let tolerances = comfort::tolerances(customer);
+    if tolerances.is_empty() {
+        return allocate_with_baby_chairs(res, open, customer, params);
+    }
+    let mut levels: Vec<f64> = res.seats.iter()
+        .filter(|s| open.is_free(s))
+        .map(|s| comfort::discomfort(&s.config, &tolerances))
+        .collect();
+    levels.sort_by(f64::total_cmp);
+    levels.dedup();
+    levels.into_iter()
+        .find_map(|level| {
+            let bearable = open.closing(res, |s| comfort::discomfort(&s.config, &tolerances) > level);
+            allocate_with_baby_chairs(res, &bearable, customer, params)
+        })
+}
+
+// Parties with baby chairs only get seats that can take them: seats without room are
+// closed, and a choice that still can't hold them all is ruled out and the next tried
+fn allocate_with_baby_chairs(res: &SushiResources, open: &SeatMask, customer: &CustomerConfig, params: &EngineParams) -> Option<Vec<String>> {
+    if customer.baby_chair_count == 0 {
+        return allocate_with_wheelchairs(res, open, customer, params);
+    }
+    let mut ruled_out: Vec<String> = Vec::new();
+    loop {
+        let roomy = open.closing(res, |s| baby_chair_limit(&s.config) == 0 || ruled_out.contains(&s.config.id));
+        let chosen = allocate_with_wheelchairs(res, &roomy, customer, params)?;
+        let room: u32 = res.seats.iter()
+            .filter(|s| chosen.contains(&s.config.id))
+            .map(|s| baby_chair_limit(&s.config))
+            .sum();
+        if room >= customer.baby_chair_count {
+            return Some(chosen);
+        }
+        ruled_out.extend(chosen);
+    }
+}
+
+// Likewise for wheelchairs: a choice without a space for every wheelchair is ruled out.
+// When no table is roomy enough, merge_tables may push accessible tables together.
+fn allocate_with_wheelchairs(res: &SushiResources, open: &SeatMask, customer: &CustomerConfig, params: &EngineParams) -> Option<Vec<String>> {
+    if customer.wheelchair_count == 0 {
+        return choose_by_objective(res, open, customer, params);
+    }
+    let mut ruled_out: Vec<String> = Vec::new();
+    loop {
+        let accessible = open.closing(res, |s| wheelchair_limit(&s.config) == 0 || ruled_out.contains(&s.config.id));
+        let Some(chosen) = choose_by_objective(res, &accessible, customer, params) else { break };
+        let room: u32 = res.seats.iter()
+            .filter(|s| chosen.contains(&s.config.id))
+            .map(|s| wheelchair_limit(&s.config))
+            .sum();
+        if room >= customer.wheelchair_count {
+            return Some(chosen);
+        }
+        ruled_out.extend(chosen);
+    }
+    if params.merge_tables { merge::try_merge_tables(res, open, customer) } else { None }
+}
+
+fn choose_by_objective(res: &SushiResources, open: &SeatMask, customer: &CustomerConfig, params: &EngineParams) -> Option<Vec<String>> {
+    let chosen = match params.objective {
+        AllocationObjective::Wait => params.strategy.choose_seats(res, open, customer),
+        AllocationObjective::Revenue => try_allocate_revenue(res, open, customer),
+    };
+    // Only parties no single table can hold get tables pushed together
+    if chosen.is_none() && params.merge_tables && customer.party_size > merge::largest_table(res.seats.iter().map(|s| &s.config)) {
+        return merge::try_merge_tables(res, open, customer);
+    }
+    chosen
+}
+
+// Why a party can't take these particular seats, picked by a host or booked ahead, or
+// None if it can. The rules are the allocator's: every seat free and named once, enough
+// baby chairs and wheelchairs, and either one table, adjacent tables pushed together
+// when merging is on, or bar seats side by side; wheelchair users only at accessible
+// tables with a space for each.
+pub(crate) fn seating_problem(res: &SushiResources, customer: &CustomerConfig, seat_ids: &[String], merge_tables: bool) -> Option<String> {
+    if seat_ids.is_empty() {
+        return Some(format!("No seats given for party {}", customer.family_id));
+    }
+    let mut seats: Vec<&SeatState> = Vec::new();
+    for id in seat_ids {
+        if seat_ids.iter().filter(|other| *other == id).count() > 1 {
+            return Some(format!("Seat {} is named more than once", id));
+        }
+        match res.seats.iter().find(|s| s.config.id == *id) {
+            None => return Some(format!("There is no seat {}", id)),
+            Some(s) if s.occupied_by.is_some() => return Some(format!("Seat {} is not free", id)),
+            Some(s) => seats.push(s),
+        }
+    }
+    if !pools_available(res, customer) {
+        return Some(format!("Not enough baby chairs or wheelchairs free for party {}", customer.family_id));
+    }
+
+    let bar = seats.iter().filter(|s| s.config.type_ == "SINGLE").count();
+    if bar == seats.len() {
+        let side_by_side = bar_runs(&res.seats, |s| &s.config).iter()
+            .any(|run| run.windows(seats.len()).any(|w| w.iter().all(|s| seat_ids.contains(&s.config.id))));
+        if !side_by_side {
+            return Some("Bar seats for one party must be side by side".to_string());
+        }
+    } else if bar > 0 {
+        return Some("A party sits at tables or at the bar, not both".to_string());
+    } else if seats.len() > 1 {
+        let tables: Vec<&SeatConfig> = seats.iter().map(|s| &s.config).collect();
+        if !merge_tables {
+            return Some("A party gets one table unless tables may be merged".to_string());
+        }
+        if !merge::connected(&tables) {
+            return Some("Merged tables must be adjacent".to_string());
+        }
+    }
+
+    let capacity: u32 = seats.iter().map(|s| seat_capacity(&s.config.type_)).sum();
+    if capacity < customer.party_size {
+        return Some(format!("The seats hold {} but party {} has {}", capacity, customer.family_id, customer.party_size));
+    }
+    let room: u32 = seats.iter().map(|s| wheelchair_limit(&s.config)).sum();
+    if room < customer.wheelchair_count {
+        return Some(format!("Party {} needs {} wheelchair spaces at accessible tables; the seats have {}", customer.family_id, customer.wheelchair_count, room));
+    }
+    None
+}
+
+// Primary allocation decision. Parties that want a conveyor-facing seat turn down
+// other seats until they have waited preference x patience, trading wait for the view.
+fn allocate_primary(res: &SushiResources, customer: &CustomerConfig, params: &EngineParams, now: u64) -> Option<Vec<String>> {
+    allocate_within(res, &SeatMask::default(), customer, params, now)
+}
+
+// The same decision among the seats `open` leaves free, so a caller can ask what else
+// the rules would allow once some seats are ruled out
+fn allocate_within(res: &SushiResources, open: &SeatMask, customer: &CustomerConfig, params: &EngineParams, now: u64) -> Option<Vec<String>> {
+    // A missing baby chair doesn't hold up the table; seat_party files a claim for it
+    if params.deliver_baby_chairs_later && customer.baby_chair_count > 0 {
+        let without_chairs = CustomerConfig { baby_chair_count: 0, ..customer.clone() };
+        return allocate_within(res, open, &without_chairs, params, now);
+    }
+
+    if !params.reservations.is_empty() {
+        let booked = reservations::booked_seats(res, customer, params, now)
+            .filter(|ids| ids.iter().all(|id| !open.closed.contains(id.as_str())));
+        if booked.is_some() {
+            return booked;
+        }
+        let held = reservations::held_seats(res, customer, params, now);
+        if !held.is_empty() {
+            let open = open.closing(res, |s| held.contains(&s.config.id));
+            return allocate_for_preference(res, &open, customer, params, now);
+        }
+    }
+    allocate_for_preference(res, open, customer, params, now)
+}
+
+// Seats an allocation step leaves alone besides the occupied ones: held for a booking,
+// beyond a party's tolerances, without room for its chairs, ... A narrower choice is a
+// bigger mask over the same resources, not a copy of them.
+#[derive(Clone, Default)]
+pub(crate) struct SeatMask<'a> {
+    closed: std::collections::HashSet<&'a str>,
+}
+
+impl<'a> SeatMask<'a> {
+    // This mask with the seats matching `close` closed as well
+    fn closing(&self, res: &'a SushiResources, close: impl Fn(&SeatState) -> bool) -> Self {
+        let mut closed = self.closed.clone();
+        closed.extend(res.seats.iter().filter(|s| close(s)).map(|s| s.config.id.as_str()));
+        SeatMask { closed }
+    }
+
+    fn is_free(&self, seat: &SeatState) -> bool {
+        seat.occupied_by.is_none() && !self.closed.contains(seat.config.id.as_str())
+    }
+}
+
+fn allocate_for_preference(res: &SushiResources, open: &SeatMask, customer: &CustomerConfig, params: &EngineParams, now: u64) -> Option<Vec<String>> {
+    let allocation = allocate_by_objective(res, open, customer, params)?;
+
+    let faces_conveyor = |id: &String| res.seats.iter().any(|s| s.config.id == *id && s.config.faces_conveyor);
+    let willing_wait = (customer.conveyor_preference * params.conveyor_patience as f64) as u64;
+    let waited = now.saturating_sub(customer.arrival_time);
+    // With nobody dining no seat will ever free up, so holding out would wait forever
+    let nobody_dining = res.seats.iter().all(|s| s.occupied_by.is_none());
+    if customer.conveyor_preference <= 0.0 || allocation.iter().all(faces_conveyor) || waited >= willing_wait || nobody_dining {
+        return Some(allocation);
+    }
+
+    // Retry as if only conveyor-facing seats existed; otherwise keep waiting
+    let conveyor_only = open.closing(res, |s| !s.config.faces_conveyor);
+    allocate_by_objective(res, &conveyor_only, customer, params)
+}
+
+fn seat_weight(seat: &SeatState) -> f64 {
+    seat.config.revenue_weight.unwrap_or(1.0)
+}
+
+// Expected revenue per unit of capacity if this party takes the seat:
+// premium tables score higher, but only when the party actually fills them.
+fn seat_yield(seat: &SeatState, party_size: u32) -> f64 {
+    let capacity = seat_capacity(&seat.config.type_).max(1);
+    seat_weight(seat) * party_size.min(capacity) as f64 / capacity as f64
+}
+
+// Highest-yield seat, keeping seat config order on ties
+fn best_yield<'a>(seats: impl Iterator<Item = &'a SeatState>, party_size: u32) -> Option<&'a SeatState> {
+    seats.fold(None, |best: Option<&SeatState>, s| match best {
+        Some(b) if seat_yield(b, party_size) >= seat_yield(s, party_size) => Some(b),
+        _ => Some(s),
+    })
+}
+
+// Revenue objective. Fairness constraints: it considers exactly the seat classes the
+// default heuristic would (so nobody waits who would otherwise be seated, and
+// individuals still go to the bar first); it only changes which seat within that class.
+fn try_allocate_revenue(res: &SushiResources, open: &SeatMask, customer: &CustomerConfig) -> Option<Vec<String>> {
+    if !pools_available(res, customer) {
+        return None;
+    }
+
+    let free = || res.seats.iter().filter(|s| open.is_free(s));
+    let party = customer.party_size;
+
+    let chosen = if customer.wheelchair_count > 0 {
+        best_yield(free().filter(|s| s.config.is_wheelchair_accessible && s.config.type_ != "SINGLE"), party)
+    } else if party > 1 {
+        best_yield(free().filter(|s| s.config.type_ != "SINGLE" && seat_capacity(&s.config.type_) >= party), party)
+    } else {
+        best_yield(free().filter(|s| s.config.type_ == "SINGLE"), party)
+            .or_else(|| best_yield(free().filter(|s| s.config.type_ != "SINGLE"), party))
+    };
+
+    match chosen {
+        Some(s) => Some(vec![s.config.id.clone()]),
+        // Families with no sofa left fall back to the bar exactly like the default policy
+        None => try_allocate(res, open, customer),
+    }
+}
+
+// The parties whose needs changed during the run, as they were in the end
+fn changed_parties(res: &SushiResources, customers: &[CustomerConfig]) -> Vec<CustomerConfig> {
+    let changes: Vec<&SimEvent> = res.events.iter().filter(|e| matches!(e.action, Action::Requirements(..))).collect();
+    if changes.is_empty() {
+        return Vec::new();
+    }
+    let mut queue = Queue::new(customers);
+    for e in &changes {
+        queue.apply(e);
+    }
+    queue.customers.into_iter().filter(|c| changes.iter().any(|e| e.family_id == c.family_id)).collect()
+}
+
+// Revenue of all seated families: covers x average weight of the seats they used
+fn total_revenue(res: &SushiResources, customers: &[CustomerConfig]) -> f64 {
+    res.events.seatings().seated.iter().filter_map(|(family_id, (_, placement))| {
+        let party = customers.iter().find(|c| c.family_id == *family_id)?.party_size;
+        let weights: Vec<f64> = placement.seat_ids.split(',')
+            .filter_map(|id| res.seats.iter().find(|s| s.config.id == id.trim()))
+            .map(seat_weight)
+            .collect();
+        if weights.is_empty() { return None; }
+        Some(party as f64 * weights.iter().sum::<f64>() / weights.len() as f64)
+    }).sum()
+}
+
+// Bar seats that count as side by side, each run in seating order. Seats naming a
+// contiguity_group form one run per group, by group_position; bar seats without a
+// group form one more run in config order, as in layouts from before groups.
+pub(crate) fn bar_runs<T>(seats: &[T], config: impl Fn(&T) -> &SeatConfig) -> Vec<Vec<&T>> {
+    let mut runs: Vec<(Option<&str>, Vec<&T>)> = Vec::new();
+    for seat in seats.iter().filter(|s| config(s).type_ == "SINGLE") {
+        let group = config(seat).contiguity_group.as_deref();
+        match runs.iter_mut().find(|(g, _)| *g == group) {
+            Some((_, run)) => run.push(seat),
+            None => runs.push((group, vec![seat])),
+        }
+    }
+    runs.into_iter()
+        .map(|(_, mut run)| {
+            run.sort_by_key(|s| config(s).group_position);
+            run
+        })
+        .collect()
+}
+
+// First run of `party` free bar seats next to each other, never across groups
+fn free_bar_run(res: &SushiResources, open: &SeatMask, party: usize) -> Option<Vec<String>> {
+    bar_runs(&res.seats, |s| &s.config).iter()
+        .flat_map(|run| run.windows(party))
+        .find(|w| w.iter().all(|s| open.is_free(s)))
+        .map(|w| w.iter().map(|s| s.config.id.clone()).collect())
+}
+
+pub(crate) fn seat_capacity(seat_type: &str) -> u32 {
+    match seat_type {
+        "SINGLE" => 1,
+        "4P" => 4,
+        "6P" => 6,
+        _ => 0,
+    }
+}
+
+// Evaluate the shadow policy against the current state and record where it disagrees
+fn record_shadow_decision(res: &mut SushiResources, now: u64, customer: &CustomerConfig, primary: Option<&[String]>) {
+    let policy = match &res.shadow {
+        Some(shadow) => shadow.policy,
+        None => return,
+    };
+    let shadow_choice = policy.choose_seats(res, &SeatMask::default(), customer);
+
+    if let Some(shadow) = res.shadow.as_mut() {
+        shadow.decisions_compared += 1;
+        if primary != shadow_choice.as_deref() {
+            shadow.divergences.push(ShadowDecision {
+                timestamp: now,
+                family_id: customer.family_id,
+                primary_seats: primary.map(|p| p.to_vec()),
+                shadow_seats: shadow_choice,
+            });
+        }
+    }
+}
+
+// Compare when each family was actually seated with when the shadow policy seats it in
+// a run of its own over the same roster, on the DES. Only an estimate: a realtime run
+// or opportunistic fairness (replayed first come first served) may order parties
+// differently.
+fn build_shadow_report(res: &SushiResources, customers: &[CustomerConfig], seats_config: &[SeatConfig], params: &EngineParams, cancel: &Arc<AtomicBool>) -> Result<Option<ShadowReport>> {
+    let Some(shadow) = res.shadow.as_ref() else { return Ok(None) };
+    let seatings = res.events.seatings();
+    let shadow_params = EngineParams {
+        strategy: shadow.policy,
+        shadow_policy: None,
+        fairness: FairnessMode::Fifo,
+        engine: EngineBackend::Des,
+        instrument: false,
+        trace_sync: false,
+        live: None,
+        gate: None,
+        ..params.clone()
+    };
+    let shadow_sit_times = sit_times(&run_engine(customers, seats_config, &shadow_params, cancel)?);
+
+    let families: Vec<ShadowFamilyDelta> = customers.iter().filter_map(|c| {
+        let sit_time = seatings.seated.get(&c.family_id).map(|(time, _)| *time)?;
+        let shadow_sit_time = shadow_sit_times.get(&c.family_id).copied();
+        Some(ShadowFamilyDelta {
+            family_id: c.family_id,
+            actual_wait: sit_time.saturating_sub(c.arrival_time),
+            shadow_sit_time,
+            estimated_wait_delta: shadow_sit_time.map(|t| t as i64 - sit_time as i64),
+        })
     }).collect();
-    
-    // Used for visual markers (does not affect logic)
-    // Store family special needs and total baby chairs
-    let mut family_info = std::collections::HashMap::new();
 
-    for customer in customers {
-        family_info.insert(customer.family_id, (
-            customer.baby_chair_count,
-            customer.wheelchair_count,
-            customer.party_size
-        ));
+    Ok(Some(ShadowReport {
+        policy: shadow.policy.name().to_string(),
+        decisions_compared: shadow.decisions_compared,
+        divergences: shadow.divergences.clone(),
+        total_estimated_wait_delta: families.iter().filter_map(|f| f.estimated_wait_delta).sum(),
+        families,
+    }))
+}
+
+#[tauri::command]
+pub fn get_shadow_report(session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<Option<ShadowReport>> {
+    let session_id = sessions.resolve(session_id, &window);
+    sessions.with_session(&session_id, |run| run.shadow_report.clone())
+}
+
+#[tauri::command]
+pub fn get_kitchen_report(session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<KitchenReport> {
+    let session_id = sessions.resolve(session_id, &window);
+    sessions.with_session(&session_id, |run| run.kitchen_report.clone())
+}
+
+// Event type shown to the frontend for an engine action
+fn action_type(action: &Action) -> &'static str {
+    match action {
+        Action::Arrive => "ARRIVAL",
+        Action::Wait => "WAITING",
+        Action::Sit(_) => "SEATED",
+        Action::Leave(_) => "LEFT",
+        Action::Error => "ERROR",
+        Action::Renege => RENEGE_EVENT,
+        Action::Deadlock => DEADLOCK_EVENT,
+        Action::Deliver(..) => DELIVERED_EVENT,
+        Action::Requirements(..) => REQUIREMENTS_CHANGED_EVENT,
+        Action::Priority => PRIORITY_SEATED_EVENT,
+        Action::Infeasible => INFEASIBLE_EVENT,
+        Action::Courier(event_type) => event_type,
+    }
+}
+
+// Seats an event names, if any
+fn action_seats(action: &Action) -> Option<&String> {
+    match action {
+        Action::Sit(Placement { seat_ids: ids, .. }) | Action::Leave(ids) | Action::Deliver(ids, _) => Some(ids),
+        _ => None,
     }
+}
+
+// Per-family event sequence (type, virtual time, seats), ignoring log text and how
+// the engine happened to interleave families. Takes (family, type, time, seats) in log
+// order, from an engine run or from a stored event log.
+pub(crate) fn logical_sequences<'a>(events: impl IntoIterator<Item = (u32, &'a str, u64, Option<&'a str>)>) -> std::collections::BTreeMap<u32, Vec<String>> {
+    let mut sequences: std::collections::BTreeMap<u32, Vec<String>> = std::collections::BTreeMap::new();
+    for (family_id, event_type, time, seats) in events {
+        let seats = seats.map(|ids| format!("[{}]", ids)).unwrap_or_default();
+        sequences.entry(family_id).or_default().push(format!("{}@{}{}", event_type, time, seats));
+    }
+    sequences
+}
+
+// The same for a finished engine run
+pub(crate) fn run_sequences(res: &SushiResources) -> std::collections::BTreeMap<u32, Vec<String>> {
+    logical_sequences(res.events.ordered().into_iter()
+        .map(|e| (e.family_id, action_type(&e.action), e.time, action_seats(&e.action).map(String::as_str))))
+}
+
+// Determinism fingerprint: FNV-1a over the canonical event log (one line per family,
+// in family order, from logical_sequences). Equal fingerprints mean the same outcome
+// regardless of engine, thread interleaving or log wording.
+pub(crate) fn fingerprint(res: &SushiResources) -> String {
+    let log: String = run_sequences(res).into_iter()
+        .map(|(family_id, sequence)| format!("{}:{}\n", family_id, sequence.join(";")))
+        .collect();
+    format!("{:016x}", hashing::fnv1a(log.bytes()))
+}
+
+// Run the session's scenario on the thread engine and on `against` (default "des") and
+// report families whose logical event sequences differ. On deterministic inputs (no two
+// parties competing for the same seat at the same instant) the engines must agree.
+#[tauri::command]
+pub fn compare_engines(session_id: Option<String>, against: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<EngineComparison> {
+    access::require_host(&window, "run simulations")?;
+    let session_id = sessions.resolve(session_id, &window);
+    let against = against.as_deref().map(EngineBackend::from_name).transpose()?.unwrap_or(EngineBackend::Des);
+    let (customers, seats, params) = sessions.with_session(&session_id, |run| {
+        (run.customers.clone(), run.seats_config.clone(), EngineParams {
+            shadow_policy: None,
+            instrument: false,
+            trace_sync: false,
+            ..run.params.clone()
+        })
+    })?;
+
+    let _run_guard = sessions.begin_run()?;
+    let cancel = sessions.cancel_flag();
+
+    let threads = run_sequences(&run_engine(&customers, &seats, &EngineParams { engine: EngineBackend::Threads, ..params.clone() }, &cancel)?);
+    let other = run_sequences(&run_engine(&customers, &seats, &EngineParams { engine: against, ..params }, &cancel)?);
+
+    let divergences: Vec<EngineDivergence> = customers.iter()
+        .map(|c| c.family_id)
+        .filter(|fid| threads.get(fid) != other.get(fid))
+        .map(|fid| EngineDivergence {
+            family_id: fid,
+            threads: threads.get(&fid).cloned().unwrap_or_default(),
+            other: other.get(&fid).cloned().unwrap_or_default(),
+        })
+        .collect();
+
+    Ok(EngineComparison {
+        engine: against.name().to_string(),
+        matches: divergences.is_empty(),
+        families_compared: customers.len(),
+        divergences,
+    })
+}
+
+// Generate Frames needed for frontend: the seat map and queue projections replayed
+// second by second
+fn generate_frames(res: &SushiResources, seats_config: &[SeatConfig], customers: &[CustomerConfig], resolution: FrameResolution) -> Result<Vec<SimulationFrame>> {
+    let sorted_events = res.events.ordered();
+
+    let max_time = sorted_events.last().map(|e| e.time).unwrap_or(0);
+    // The trailing frames only pad the per-second timeline
+    let end_time = if resolution == FrameResolution::PerSecond { max_time + 5 } else { max_time };
+    let mut frames = Vec::new();
+
+    let mut seat_map = SeatMap::new(seats_config);
+    // Later frames show each party as it is after its requirement changes
+    let mut queue = Queue::new(customers);
 
     let mut event_idx = 0;
-    let mut waiting_family_ids = std::collections::HashSet::new();
-    
-    // Generate Frame for every second
-    for t in 0..=max_time + 5 {
+    let mut sent = 0; // events up to here are on a kept frame
+
+    // Walk every second, keeping the frames the resolution asks for
+    for t in 0..=end_time {
+        let first_event = event_idx;
         while event_idx < sorted_events.len() && sorted_events[event_idx].time <= t {
-            let evt = &sorted_events[event_idx];
-            
-            match &evt.action {
-                Action::Arrive | Action::Wait => {
-                    waiting_family_ids.insert(evt.family_id);
-                },
-                Action::Sit(ids) => {
-                    waiting_family_ids.remove(&evt.family_id);
-                    let seat_ids: Vec<&str> = ids.split(',').map(|s| s.trim()).collect();
-                    let num_seats = seat_ids.len();
-                    
-                    if let Some(&(baby_cnt, wheel_cnt, _party_size)) = family_info.get(&evt.family_id) {
-                        // Calculate baby chairs assigned to each seat (distributed)
-                        let base_baby = baby_cnt / num_seats as u32;
-                        let mut extra_baby = baby_cnt % num_seats as u32;
-
-                        for (i, id) in seat_ids.iter().enumerate() {
-                            if let Some(s) = current_seats.iter_mut().find(|seat| seat.id == *id) {
-                                s.occupied_by = Some(evt.family_id);
-                                
-                                // Assign baby chairs
-                                let mut my_baby = base_baby;
-                                if extra_baby > 0 {
-                                    my_baby += 1;
-                                    extra_baby -= 1;
-                                }
-                                s.baby_chair_count = my_baby;
-
-                                // Set occupant type
-                                if wheel_cnt > 0 && i == 0 {
-                                    s.occupant_type = Some("WHEELCHAIR".to_string());
-                                } else {
-                                    s.occupant_type = Some("NORMAL".to_string());
-                                }
-                            }
-                        }
-                    }
-                },
-                Action::Leave(ids) => {
-                    for id in ids.split(',') {
-                        if let Some(s) = current_seats.iter_mut().find(|seat| seat.id == id.trim()) {
-                            s.occupied_by = None;
-                            s.occupant_type = None;
-                            s.baby_chair_count = 0;
-                        }
-                    }
-                },
-                Action::Error => {
-                    waiting_family_ids.remove(&evt.family_id);
-                }
-            }
+            seat_map.apply(sorted_events[event_idx]);
+            queue.apply(sorted_events[event_idx]);
             event_idx += 1;
         }
-        
-        // Filter events occurring at this moment for frontend LogTerminal
-        let current_events: Vec<SimulationEvent> = sorted_events.iter()
-            .filter(|e| e.time == t)
+        let keep = t == end_time || match resolution {
+            FrameResolution::PerSecond => true,
+            FrameResolution::PerEvent => event_idx > first_event,
+            FrameResolution::Every { seconds } => t % seconds.max(1) == 0,
+            FrameResolution::SkipIdle => event_idx > first_event
+                || !queue.waiting.is_empty()
+                || seat_map.any_occupied(),
+        };
+        if !keep { continue; }
+
+        // Events since the last kept frame, for the frontend LogTerminal
+        let unsent = sent;
+        sent = event_idx;
+        let current_events: Vec<SimulationEvent> = sorted_events[unsent..event_idx].iter()
             .map(|e| SimulationEvent {
                 timestamp: e.time,
-                type_: match e.action {
-                    Action::Arrive => "ARRIVAL".into(),
-                    Action::Wait => "WAITING".into(), 
-                    Action::Sit(_) => "SEATED".into(),
-                    Action::Leave(_) => "LEFT".into(),
-                    Action::Error => "ERROR".into(),
-                },
-                customer_id: e.family_id,
+                sequence: e.sequence,
+                type_: action_type(&e.action).into(),
+                customer_id: queue.customer(e.family_id)
+                    .map(|c| c.id.clone())
+                    .unwrap_or_else(|| e.family_id.into()),
                 family_id: e.family_id,
-                seat_id: match &e.action {
-                    Action::Sit(s) | Action::Leave(s) => Some(s.clone()),
-                    _ => None,
-                },
+                seat_id: action_seats(&e.action).cloned(),
                 message: e.log_message.clone(),
+                extra: queue.customer(e.family_id)
+                    .map(|c| c.extra.clone())
+                    .unwrap_or_default(),
             }).collect();
 
-        let waiting_customers: Vec<CustomerConfig> = waiting_family_ids.iter()
-            .filter_map(|fid| customers.iter().find(|c| c.family_id == *fid).cloned())
-            .collect();
-
         frames.push(SimulationFrame {
             timestamp: t,
-            seats: current_seats.clone(),
-            waiting_queue: waiting_customers, 
+            seats: seat_map.seats.clone(),
+            waiting_queue: queue.waiting_customers().cloned().collect(),
             events: current_events,
             logs: vec![],
+            // Customers are in arrival order, so each sub-queue is too
+            sub_queues: queue.sub_queues(),
+            stations: res.stations.occupancy(t),
         });
     }
     Ok(frames)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The realtime engines let every party in at once and sleep out the dining time, so
+    // departures are spaced apart in virtual-clock order and nobody arrives the minute a
+    // seat frees up
+    const SEATS: &str = r#"[{"id":"B1","type":"SINGLE","isWheelchairAccessible":false},{"id":"T1","type":"4P","isWheelchairAccessible":true}]"#;
+    const ROSTER: &str = "1,0,,4,0,0,9\n2,1,,1,0,0,2\n3,2,,3,0,1,4\n4,2,,1,0,0,3\n";
+
+    #[test]
+    fn engines_agree_on_a_deterministic_roster() {
+        let seats: Vec<SeatConfig> = serde_json::from_str(SEATS).unwrap();
+        let customers = sort_customers(parser::parse_customers(ROSTER).unwrap());
+        let params = engine_params(&SimulationConfig::default(), &seats).unwrap();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let run = |engine| run_sequences(&run_engine(&customers, &seats, &EngineParams { engine, ..params.clone() }, &cancel).unwrap());
+
+        let des = run(EngineBackend::Des);
+        assert_eq!(des.len(), 4);
+        assert_eq!(run(EngineBackend::Threads), des);
+        assert_eq!(run(EngineBackend::Tasks), des);
+    }
+
+    // Parties 3 and 4 run out of patience at minute 5; the clock only moves past it when
+    // party 2 leaves at minute 7, and every engine logs them leaving at 5
+    #[test]
+    fn engines_agree_on_when_parties_leave_unserved() {
+        let seats: Vec<SeatConfig> = serde_json::from_str(SEATS).unwrap();
+        let customers = sort_customers(parser::parse_customers("1,0,,4,0,0,9\n2,1,,1,0,0,6\n3,2,,3,0,0,4\n4,2,,1,0,0,3\n").unwrap());
+        let config = SimulationConfig { max_wait_time: Some(3), ..SimulationConfig::default() };
+        let params = engine_params(&config, &seats).unwrap();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let reneges = |engine| {
+            let res = run_engine(&customers, &seats, &EngineParams { engine, ..params.clone() }, &cancel).unwrap();
+            res.events.ordered().into_iter()
+                .filter(|e| matches!(e.action, Action::Renege))
+                .map(|e| (e.family_id, e.time))
+                .collect::<std::collections::BTreeMap<_, _>>()
+        };
+
+        let des = reneges(EngineBackend::Des);
+        assert_eq!(des, [(3, 5), (4, 5)].into());
+        assert_eq!(reneges(EngineBackend::Threads), des);
+        assert_eq!(reneges(EngineBackend::Tasks), des);
+        assert_eq!(reneges(EngineBackend::Semaphores), des);
+    }
+}
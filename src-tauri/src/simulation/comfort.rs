@@ -0,0 +1,26 @@
+use crate::models::{CustomerConfig, SeatConfig};
+
+// Roster columns named "<attribute>_tolerance" (e.g. noise_tolerance) give the highest
+// value of that seat attribute the party is happy with
+const TOLERANCE_SUFFIX: &str = "_tolerance";
+
+// The party's tolerances by attribute name, from its extra CSV columns
+pub(crate) fn tolerances(customer: &CustomerConfig) -> Vec<(String, f64)> {
+    customer.extra.iter()
+        .filter_map(|(column, value)| {
+            let attribute = column.to_lowercase().strip_suffix(TOLERANCE_SUFFIX)?.to_string();
+            Some((attribute, value.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+// How far the seat goes past the party's tolerances, summed over its attributes.
+// Attributes the seat doesn't declare don't bother anyone.
+pub(crate) fn discomfort(seat: &SeatConfig, tolerances: &[(String, f64)]) -> f64 {
+    tolerances.iter()
+        .filter_map(|(attribute, limit)| {
+            let value = seat.attributes.iter().find(|(name, _)| name.to_lowercase() == *attribute)?.1;
+            Some((value - limit).max(0.0))
+        })
+        .sum()
+}
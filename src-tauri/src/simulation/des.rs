@@ -0,0 +1,372 @@
+use super::{allocate_primary, log_event, infeasibility, initial_resources, record_shadow_decision, refuse, release_party, renege, reservations, seat_party, serve_couriers, wait_limit, Action, EngineParams, SimEvent, SushiResources, REQUIREMENTS_CHANGED_EVENT};
+use crate::errors::{AppError, Result};
+use crate::models::{CustomerConfig, LedgerEntry, RequirementChange, SeatConfig};
+use crate::parser;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::mem::size_of;
+use std::sync::Arc;
+
+// A run keeps its state at the first event at or after every this many virtual minutes,
+// at first; past MAX_CHECKPOINTS every other one goes and the interval doubles
+const CHECKPOINT_INTERVAL: u64 = 30;
+const MAX_CHECKPOINTS: usize = 16;
+
+// Departures sort before arrivals at the same instant, so seats freed at t are
+// available to parties arriving at t
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Kind {
+    Departure,
+    // A waiting party's needs change (index into requirement_changes); before arrivals,
+    // so the changed party gets its chance at seats freed at the same instant first
+    Change,
+    Arrival,
+    // A party holding out for a conveyor seat re-checks once its patience runs out
+    Retry,
+    // A reserved table may have been released to walk-ins (customer index unused)
+    HoldLapsed,
+    // A waiting party's max_wait_time is up; after the other kinds, so a seat freed at
+    // the same instant still goes to it
+    Renege,
+}
+
+// Discrete-event engine: one thread, events processed in virtual-time order.
+// Waiting parties are offered freed seats in arrival order, unless the strategy
+// reorders the queue. It sits under simulation with the realtime backends rather than
+// in an engine module of its own, as all of them share the allocator and the event
+// logging through super:: and EngineBackend picks one per run.
+struct Des<'a> {
+    customers: Vec<CustomerConfig>, // own copy, as requirement changes edit it
+    params: &'a EngineParams,
+    res: SushiResources,
+    // (time, kind, insertion order, customer index)
+    queue: BinaryHeap<Reverse<(u64, Kind, usize, usize)>>,
+    next_order: usize,
+    waiting: Vec<usize>,
+    seats_held: Vec<Vec<String>>,
+    // Requirement changes (indices) due before their party arrived, applied on arrival
+    early_changes: Vec<usize>,
+}
+
+// Engine state before any event at `time` was handled, so a run whose inputs only
+// differ from `time` on can pick up from here. The event log and ledger history only
+// grow, so a checkpoint just counts how far they had got; Checkpoints keeps them once.
+pub(crate) struct Checkpoint {
+    pub time: u64,
+    events: usize,
+    ledger_entries: usize,
+    customers: Vec<CustomerConfig>,
+    res: SushiResources, // without the event log, ledger history and live view
+    queue: BinaryHeap<Reverse<(u64, Kind, usize, usize)>>,
+    next_order: usize,
+    waiting: Vec<usize>,
+    seats_held: Vec<Vec<String>>,
+    early_changes: Vec<usize>,
+}
+
+// The checkpoints of one run with the log and ledger history they share
+pub(crate) struct Checkpoints {
+    pub states: Vec<Arc<Checkpoint>>, // by time
+    events: Vec<SimEvent>,
+    ledger: Vec<LedgerEntry>,
+}
+
+impl Checkpoints {
+    fn new(states: Vec<Arc<Checkpoint>>, res: &SushiResources) -> Self {
+        Checkpoints { states, events: res.events.iter().cloned().collect(), ledger: res.ledger.entries().to_vec() }
+    }
+
+    // Approximate heap + inline size, for the session memory report
+    pub(crate) fn bytes(&self) -> usize {
+        let events = self.events.iter().map(|e| size_of::<SimEvent>() + e.log_message.capacity()).sum::<usize>();
+        let ledger = self.ledger.iter().map(|e| size_of::<LedgerEntry>() + e.resource.capacity() + e.operation.capacity()).sum::<usize>();
+        let states = self.states.iter().map(|c| {
+            // The roster twice: the engine's own copy and the queue projection's
+            size_of::<Checkpoint>() + 2 * c.customers.len() * size_of::<CustomerConfig>()
+                + c.queue.len() * size_of::<(u64, Kind, usize, usize)>()
+                + c.early_changes.len() * size_of::<usize>()
+                + c.seats_held.iter().map(|s| size_of::<Vec<String>>() + s.iter().map(|id| size_of::<String>() + id.capacity()).sum::<usize>()).sum::<usize>()
+        }).sum::<usize>();
+        events + ledger + states
+    }
+}
+
+impl Des<'_> {
+    fn checkpoint(&self, time: u64) -> Checkpoint {
+        Checkpoint {
+            time,
+            events: self.res.events.len(),
+            ledger_entries: self.res.ledger.entries().len(),
+            customers: self.customers.clone(),
+            res: self.res.without_history(),
+            queue: self.queue.clone(),
+            next_order: self.next_order,
+            waiting: self.waiting.clone(),
+            seats_held: self.seats_held.clone(),
+            early_changes: self.early_changes.clone(),
+        }
+    }
+
+    fn schedule(&mut self, time: u64, kind: Kind, customer: usize) {
+        self.queue.push(Reverse((time, kind, self.next_order, customer)));
+        self.next_order += 1;
+    }
+
+    fn log(&mut self, time: u64, customer: usize, action: Action, event_type: &str, result: &str) {
+        log_event(&mut self.res, time, &self.customers[customer], action, event_type, result);
+    }
+
+    // Same allocation step as a worker thread of the monitor engine
+    fn try_seat(&mut self, customer: usize, now: u64) -> bool {
+        let c = &self.customers[customer];
+        let allocation = allocate_primary(&self.res, c, self.params, now);
+        if self.res.shadow.is_some() {
+            record_shadow_decision(&mut self.res, now, c, allocation.as_deref());
+        }
+        let Some(seat_ids) = allocation else { return false };
+
+        let dining_time = seat_party(&mut self.res, c, &seat_ids, now);
+        self.seats_held[customer] = seat_ids;
+        self.schedule(now + dining_time, Kind::Departure, customer);
+        true
+    }
+
+    fn seat_waiting(&mut self, now: u64) {
+        let mut order = self.waiting.clone();
+        self.params.strategy.order_waiting(&mut order, &self.customers);
+        // Whatever the strategy's order, priority parties are offered seats first
+        order.sort_by_key(|&i| Reverse(self.customers[i].priority));
+        for customer in order {
+            if self.try_seat(customer, now) {
+                self.waiting.retain(|&w| w != customer);
+            } else if self.params.strategy.strict_queue() {
+                break;
+            }
+        }
+    }
+
+    // Apply a change to a party's needs, on its ticket too, and log it
+    fn amend(&mut self, customer: usize, change: &RequirementChange, now: u64) {
+        let c = &mut self.customers[customer];
+        c.party_size = change.party_size.unwrap_or(c.party_size);
+        c.baby_chair_count = change.baby_chair_count.unwrap_or(c.baby_chair_count);
+        c.wheelchair_count = change.wheelchair_count.unwrap_or(c.wheelchair_count);
+        c.type_ = parser::customer_type(c.party_size, c.baby_chair_count, c.wheelchair_count);
+        let (size, babies, wheelchairs) = (c.party_size, c.baby_chair_count, c.wheelchair_count);
+        self.res.amend_ticket(&self.customers[customer]);
+        let result = format!("now {} people, {} baby chairs, {} wheelchairs", size, babies, wheelchairs);
+        self.log(now, customer, Action::Requirements(size, babies, wheelchairs), REQUIREMENTS_CHANGED_EVENT, &result);
+    }
+
+    // Apply the changes that came in before the party did, in the order they came
+    fn amend_on_arrival(&mut self, customer: usize, now: u64) {
+        let family_id = self.customers[customer].family_id;
+        let params = self.params;
+        let (due, rest): (Vec<usize>, Vec<usize>) = self.early_changes.iter()
+            .partition(|&&i| params.requirement_changes[i].family_id == family_id);
+        self.early_changes = rest;
+        for i in due {
+            self.amend(customer, &params.requirement_changes[i], now);
+        }
+    }
+
+    // Under a strict queue only the head of the line may be seated
+    fn may_seat(&self, customer: usize) -> bool {
+        !self.params.strategy.strict_queue() || self.waiting.first().is_none_or(|&head| head == customer)
+    }
+}
+
+// A change has to name a party of the roster and leave it a sensible size
+fn validate_change(change: &RequirementChange, customers: &[CustomerConfig]) -> Result<()> {
+    let Some(c) = customers.iter().find(|c| c.family_id == change.family_id) else {
+        return Err(AppError::ValidationError(format!("Requirement change for unknown family {}", change.family_id)));
+    };
+    let party_size = change.party_size.unwrap_or(c.party_size);
+    let wheelchairs = change.wheelchair_count.unwrap_or(c.wheelchair_count);
+    if party_size == 0 || wheelchairs > party_size {
+        return Err(AppError::ValidationError(format!("Requirement change for family {} leaves {} people with {} wheelchairs", change.family_id, party_size, wheelchairs)));
+    }
+    Ok(())
+}
+
+pub(super) fn run(customers: &[CustomerConfig], seats_config: &[SeatConfig], params: &EngineParams, cancel: &Arc<AtomicBool>) -> Result<SushiResources> {
+    let mut des = start(customers, seats_config, params)?;
+    drive(&mut des, cancel, None)?;
+    Ok(des.res)
+}
+
+// Like run, also returning checkpoints along the run, for rerun_simulation
+pub(crate) fn run_with_checkpoints(customers: &[CustomerConfig], seats_config: &[SeatConfig], params: &EngineParams, cancel: &Arc<AtomicBool>) -> Result<(SushiResources, Checkpoints)> {
+    let mut des = start(customers, seats_config, params)?;
+    let mut states = Vec::new();
+    drive(&mut des, cancel, Some(&mut states))?;
+    let checkpoints = Checkpoints::new(states, &des.res);
+    Ok((des.res, checkpoints))
+}
+
+// Pick up a run from its checkpoint `states[kept - 1]` with an edited roster. The parties
+// that arrived before the checkpoint and every other input must be unchanged; the rest
+// of the roster is taken from `customers`. The new run keeps the earlier checkpoints.
+pub(crate) fn resume(base: &Checkpoints, kept: usize, customers: &[CustomerConfig], params: &EngineParams, cancel: &Arc<AtomicBool>) -> Result<(SushiResources, Checkpoints)> {
+    for change in &params.requirement_changes {
+        validate_change(change, customers)?;
+    }
+    let Some(from) = kept.checked_sub(1).and_then(|last| base.states.get(last)) else {
+        return Err(AppError::SimulationError("No checkpoint to resume from".to_string()));
+    };
+    let mut des = Des {
+        customers: from.customers.clone(),
+        params,
+        res: from.res.with_history(&base.events[..from.events], &base.ledger[..from.ledger_entries]),
+        queue: from.queue.clone(),
+        next_order: from.next_order,
+        waiting: from.waiting.clone(),
+        seats_held: from.seats_held.clone(),
+        early_changes: from.early_changes.clone(),
+    };
+    des.res.live = params.live.clone();
+    let mut states = base.states[..kept].to_vec();
+
+    // Parties yet to arrive come from the edited roster, scheduled in roster order
+    // like a fresh run would
+    let arrived = customers.partition_point(|c| c.arrival_time < from.time);
+    des.customers.truncate(arrived);
+    des.customers.extend_from_slice(&customers[arrived..]);
+    des.seats_held.truncate(arrived);
+    des.seats_held.resize(customers.len(), Vec::new());
+    des.queue.retain(|Reverse((_, kind, _, _))| *kind != Kind::Arrival);
+    for (i, c) in customers.iter().enumerate().skip(arrived) {
+        des.schedule(c.arrival_time, Kind::Arrival, i);
+    }
+
+    drive(&mut des, cancel, Some(&mut states))?;
+    let checkpoints = Checkpoints::new(states, &des.res);
+    Ok((des.res, checkpoints))
+}
+
+fn start<'a>(customers: &[CustomerConfig], seats_config: &[SeatConfig], params: &'a EngineParams) -> Result<Des<'a>> {
+    for change in &params.requirement_changes {
+        validate_change(change, customers)?;
+    }
+    let mut des = Des {
+        customers: customers.to_vec(),
+        params,
+        res: initial_resources(seats_config, params),
+        queue: BinaryHeap::new(),
+        next_order: 0,
+        waiting: Vec::new(),
+        seats_held: vec![Vec::new(); customers.len()],
+        early_changes: Vec::new(),
+    };
+    for (i, c) in customers.iter().enumerate() {
+        des.schedule(c.arrival_time, Kind::Arrival, i);
+    }
+    for time in reservations::expiry_times(params) {
+        des.schedule(time, Kind::HoldLapsed, 0);
+    }
+    for (i, change) in params.requirement_changes.iter().enumerate() {
+        des.schedule(change.at, Kind::Change, i);
+    }
+    Ok(des)
+}
+
+// Handle events until the queue runs dry
+fn drive(des: &mut Des, cancel: &Arc<AtomicBool>, mut checkpoints: Option<&mut Vec<Arc<Checkpoint>>>) -> Result<()> {
+    let params = des.params;
+    let mut interval = CHECKPOINT_INTERVAL;
+    while let Some(&Reverse((now, ..))) = des.queue.peek() {
+        if let Some(checkpoints) = checkpoints.as_deref_mut() {
+            if checkpoints.last().is_none_or(|c| now >= c.time + interval) {
+                checkpoints.push(Arc::new(des.checkpoint(now)));
+                if checkpoints.len() > MAX_CHECKPOINTS {
+                    let mut index = 0;
+                    checkpoints.retain(|_| { index += 1; index % 2 == 1 });
+                    interval *= 2;
+                }
+            }
+        }
+        let Some(Reverse((now, kind, _, customer))) = des.queue.pop() else { break };
+        if let Some(gate) = &params.gate {
+            gate.pass(cancel);
+        }
+        if cancel.load(Ordering::SeqCst) {
+            return Err(AppError::SimulationError("Simulation cancelled by application shutdown".to_string()));
+        }
+
+        match kind {
+            Kind::Arrival => {
+                des.log(now, customer, Action::Arrive, "ARRIVAL", "arrived");
+                des.amend_on_arrival(customer, now);
+                if !(des.may_seat(customer) && des.try_seat(customer, now)) {
+                    if let Some(reason) = infeasibility(&des.res, &des.customers[customer], params) {
+                        refuse(&mut des.res, &des.customers[customer], now, &reason);
+                        continue;
+                    }
+                    des.log(now, customer, Action::Wait, "WAITING", "waited");
+                    // Priority parties queue ahead of walk-ins, arrival order among equals
+                    let c = &des.customers[customer];
+                    let at = des.waiting.partition_point(|&w| des.customers[w].priority >= c.priority);
+                    des.waiting.insert(at, customer);
+                    des.res.take_ticket(c);
+
+                    let patience = (c.conveyor_preference * params.conveyor_patience as f64) as u64;
+                    let (arrival, limit) = (c.arrival_time, wait_limit(c, params));
+                    if patience > 0 {
+                        des.schedule(arrival + patience, Kind::Retry, customer);
+                    }
+                    if let Some(limit) = limit {
+                        des.schedule(arrival + limit, Kind::Renege, customer);
+                    }
+                }
+            }
+            Kind::Departure => {
+                let seat_ids = std::mem::take(&mut des.seats_held[customer]);
+                release_party(&mut des.res, &des.customers[customer], &seat_ids, now);
+                des.seat_waiting(now);
+            }
+            Kind::HoldLapsed => des.seat_waiting(now),
+            Kind::Change => {
+                // A party still waiting changes now and the allocator looks at it afresh; one
+                // yet to arrive changes when it does. Seated or gone, the change is moot.
+                let change = &params.requirement_changes[customer];
+                let Some(party) = des.customers.iter().position(|c| c.family_id == change.family_id) else { continue };
+                if !des.res.events.queue().arrived.contains(&change.family_id) {
+                    des.early_changes.push(customer);
+                } else if let Some(pos) = des.waiting.iter().position(|&w| w == party) {
+                    des.amend(party, change, now);
+                    // The new needs may be more than the layout can ever meet
+                    if let Some(reason) = infeasibility(&des.res, &des.customers[party], params) {
+                        des.waiting.remove(pos);
+                        refuse(&mut des.res, &des.customers[party], now, &reason);
+                        if params.strategy.strict_queue() {
+                            des.seat_waiting(now);
+                        }
+                    } else if des.may_seat(party) && des.try_seat(party, now) {
+                        des.waiting.remove(pos);
+                    }
+                }
+            }
+            Kind::Retry => {
+                if let Some(pos) = des.waiting.iter().position(|&w| w == customer) {
+                    if des.may_seat(customer) && des.try_seat(customer, now) {
+                        des.waiting.remove(pos);
+                    }
+                }
+            }
+            Kind::Renege => {
+                if let Some(pos) = des.waiting.iter().position(|&w| w == customer) {
+                    des.waiting.remove(pos);
+                    renege(&mut des.res, &des.customers[customer], now);
+                    // Under a strict queue the party behind may now be seated
+                    if params.strategy.strict_queue() {
+                        des.seat_waiting(now);
+                    }
+                }
+            }
+        }
+    }
+    // Courier orders placed after the last party sat down
+    serve_couriers(&mut des.res, u64::MAX);
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use super::projections::{Projection, Queue, SeatMap, Seatings};
+use super::{Action, SimEvent};
+use crate::models::SeatConfig;
+
+// Append-only log of everything the engines did: the one source of truth of a run.
+// Engines only ever append; frames, the live view and run statistics are projections
+// of it (see projections.rs), so they can never disagree. The seat map, queue and
+// seatings are kept up to date on every append, so reading them costs nothing.
+#[derive(Clone, Default)]
+pub(super) struct EventStore {
+    events: Vec<SimEvent>,
+    seat_map: SeatMap,
+    queue: Queue,
+    seatings: Seatings,
+    latest_time: u64,
+}
+
+impl EventStore {
+    pub(super) fn new(seats_config: &[SeatConfig]) -> Self {
+        EventStore { seat_map: SeatMap::new(seats_config), ..Default::default() }
+    }
+
+    pub(super) fn append(&mut self, time: u64, family_id: u32, action: Action, log_message: String) {
+        let sequence = self.events.len();
+        let event = SimEvent { time, sequence, family_id, action, log_message };
+        self.seat_map.apply(&event);
+        self.queue.apply(&event);
+        self.seatings.apply(&event);
+        self.latest_time = self.latest_time.max(time);
+        self.events.push(event);
+    }
+
+    // Same projections without the log, for a checkpoint that keeps only its length
+    pub(super) fn without_log(&self) -> Self {
+        EventStore { events: Vec::new(), ..self.clone_projections() }
+    }
+
+    // Put back the log that led to these projections
+    pub(super) fn with_log(&self, events: &[SimEvent]) -> Self {
+        EventStore { events: events.to_vec(), ..self.clone_projections() }
+    }
+
+    fn clone_projections(&self) -> Self {
+        EventStore {
+            events: Vec::new(),
+            seat_map: self.seat_map.clone(),
+            queue: self.queue.clone(),
+            seatings: self.seatings.clone(),
+            latest_time: self.latest_time,
+        }
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    // Virtual time of the latest event so far. Not the one appended last: a party that
+    // ran out of patience leaves at its deadline, which may be behind the clock.
+    pub(super) fn latest_time(&self) -> u64 {
+        self.latest_time
+    }
+
+    // In append order
+    pub(super) fn iter(&self) -> std::slice::Iter<'_, SimEvent> {
+        self.events.iter()
+    }
+
+    // In virtual-time order; events at the same time keep their append order
+    pub(super) fn ordered(&self) -> Vec<&SimEvent> {
+        let mut events: Vec<&SimEvent> = self.events.iter().collect();
+        events.sort_by(|a, b| a.time.cmp(&b.time).then(a.sequence.cmp(&b.sequence)));
+        events
+    }
+
+    // Seats as of the last append
+    pub(super) fn seat_map(&self) -> &SeatMap {
+        &self.seat_map
+    }
+
+    // Waiting and arrived parties as of the last append
+    pub(super) fn queue(&self) -> &Queue {
+        &self.queue
+    }
+
+    pub(super) fn seatings(&self) -> &Seatings {
+        &self.seatings
+    }
+}
@@ -0,0 +1,174 @@
+use crate::errors::{AppError, Result};
+use crate::models::{CustomerConfig, OperationsJournal};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{Manager, Window};
+
+// Folder under the app data dir holding one journal per open shift
+const JOURNAL_DIR: &str = "journals";
+const JOURNAL_EXTENSION: &str = "jsonl";
+
+// One change to a shift, in the order it happened. Replaying the entries on a fresh
+// floor gives back the shift's state, waitlist included.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "SCREAMING_SNAKE_CASE", rename_all_fields = "camelCase")]
+pub(crate) enum JournalEntry {
+    Start {
+        started_at: u64, // unix seconds, so the shift clock keeps running across a restart
+        seat_config_json: String,
+        baby_chairs: i32,
+        wheelchairs: i32,
+        objective: Option<String>,
+        merge_tables: bool,
+        #[serde(default)]
+        reservations_json: Option<String>, // tables held for booked parties (TableReservation list)
+    },
+    Arrival { customer: CustomerConfig, reservation_id: Option<u32> },
+    Seated { family_id: u32, seat_ids: Vec<String>, at: u64 },
+    Departed { family_id: u32, at: u64 },
+}
+
+// Append-only journal file of one shift. Each entry is written through before the
+// command that made the change returns, so a crash loses nothing the host confirmed.
+pub(crate) struct Journal {
+    path: PathBuf,
+    file: File,
+}
+
+fn json_error(e: serde_json::Error) -> AppError {
+    AppError::JsonParseError(e.to_string())
+}
+
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn journal_dir(window: &Window) -> Result<PathBuf> {
+    let dir = window.path().app_data_dir()
+        .map_err(|e| AppError::IoError(std::io::Error::other(e.to_string())))?
+        .join(JOURNAL_DIR);
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+// Journals are named after the session the shift was opened in, with anything that
+// can't go in a file name replaced
+pub(crate) fn journal_path(window: &Window, journal_id: &str) -> Result<PathBuf> {
+    let stem: String = journal_id.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    Ok(journal_dir(window)?.join(format!("{}.{}", stem, JOURNAL_EXTENSION)))
+}
+
+impl Journal {
+    // Start the journal of a new shift. An existing file under the same id belongs to a
+    // shift that never ended and is never overwritten; restore or discard it first.
+    pub(crate) fn create(path: PathBuf, entries: &[JournalEntry]) -> Result<Self> {
+        let file = match File::options().append(true).create_new(true).open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                return Err(AppError::SessionError(format!("{} holds a shift that never ended; restore or discard it first", path.display())));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let mut journal = Journal { path, file };
+        for entry in entries {
+            journal.append(entry)?;
+        }
+        Ok(journal)
+    }
+
+    // Rewrite a restored shift's journal with the entries read back, so a line cut short
+    // by the crash is gone for good. Written aside and renamed over the old file, so a
+    // second crash meanwhile still leaves one whole journal.
+    pub(crate) fn rewrite(path: PathBuf, entries: &[JournalEntry]) -> Result<Self> {
+        let aside = path.with_extension(format!("{}.tmp", JOURNAL_EXTENSION));
+        let _ = std::fs::remove_file(&aside);
+        let mut journal = Journal::create(aside, entries)?;
+        std::fs::rename(&journal.path, &path)?;
+        journal.path = path;
+        Ok(journal)
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub(crate) fn append(&mut self, entry: &JournalEntry) -> Result<()> {
+        let mut line = serde_json::to_string(entry).map_err(json_error)?;
+        line.push('\n');
+        self.file.write_all(line.as_bytes())?;
+        self.file.sync_data()?;
+        Ok(())
+    }
+
+    // The shift ended normally, so there is nothing to recover
+    pub(crate) fn remove(self) -> Result<()> {
+        drop(self.file);
+        std::fs::remove_file(&self.path)?;
+        Ok(())
+    }
+}
+
+// Entries of a journal file. A last line cut short by the crash is dropped.
+pub(crate) fn read(path: &Path) -> Result<Vec<JournalEntry>> {
+    let content = std::fs::read_to_string(path)?;
+    let lines: Vec<&str> = content.lines().filter(|l| !l.trim().is_empty()).collect();
+    let mut entries = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        match serde_json::from_str(line) {
+            Ok(entry) => entries.push(entry),
+            Err(_) if i + 1 == lines.len() => break,
+            Err(e) => return Err(json_error(e)),
+        }
+    }
+    if !matches!(entries.first(), Some(JournalEntry::Start { .. })) {
+        return Err(AppError::ValidationError(format!("{} does not start with a shift", path.display())));
+    }
+    Ok(entries)
+}
+
+// Summary of a journal for the restore prompt
+fn summarize(journal_id: String, entries: &[JournalEntry]) -> OperationsJournal {
+    let mut summary = OperationsJournal { journal_id, started_at: 0, last_change: 0, arrivals: 0, waiting: 0, seated: 0 };
+    for entry in entries {
+        match entry {
+            JournalEntry::Start { started_at, .. } => summary.started_at = *started_at,
+            JournalEntry::Arrival { customer, .. } => {
+                summary.arrivals += 1;
+                summary.waiting += 1;
+                summary.last_change = summary.last_change.max(customer.arrival_time);
+            }
+            JournalEntry::Seated { at, .. } => {
+                summary.waiting = summary.waiting.saturating_sub(1);
+                summary.seated += 1;
+                summary.last_change = summary.last_change.max(*at);
+            }
+            JournalEntry::Departed { at, .. } => {
+                summary.seated = summary.seated.saturating_sub(1);
+                summary.last_change = summary.last_change.max(*at);
+            }
+        }
+    }
+    summary
+}
+
+// Journals of shifts that never ended, oldest first; unreadable files and the journals
+// of shifts open right now (`open`) are skipped
+pub(crate) fn list(window: &Window, open: &[PathBuf]) -> Result<Vec<OperationsJournal>> {
+    let mut journals = Vec::new();
+    for file in std::fs::read_dir(journal_dir(window)?)? {
+        let path = file?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some(JOURNAL_EXTENSION) { continue; }
+        if open.contains(&path) { continue; }
+        let Some(journal_id) = path.file_stem().and_then(|s| s.to_str()).map(str::to_string) else { continue };
+        if let Ok(entries) = read(&path) {
+            journals.push(summarize(journal_id, &entries));
+        }
+    }
+    journals.sort_by_key(|j| (j.started_at, j.journal_id.clone()));
+    Ok(journals)
+}
@@ -0,0 +1,96 @@
+use super::{pools_available, seat_capacity, wheelchair_limit, SeatMask, SeatState, SushiResources};
+use crate::models::{CustomerConfig, SeatConfig};
+
+// Table merging: parties too big for any one table sit at adjacent 4P/6P tables pushed
+// together. Adjacency is declared with SeatConfig::adjacent_to and works both ways.
+
+fn is_table(seat: &SeatConfig) -> bool {
+    matches!(seat.type_.as_str(), "4P" | "6P")
+}
+
+fn adjacent(a: &SeatConfig, b: &SeatConfig) -> bool {
+    a.adjacent_to.contains(&b.id) || b.adjacent_to.contains(&a.id)
+}
+
+// Whether the tables form one group that can be pushed together
+pub(crate) fn connected(tables: &[&SeatConfig]) -> bool {
+    let mut reached = vec![false; tables.len()];
+    let mut stack = vec![0];
+    while let Some(i) = stack.pop() {
+        if reached.get(i).is_none_or(|r| *r) { continue; }
+        reached[i] = true;
+        stack.extend((0..tables.len()).filter(|&j| !reached[j] && adjacent(tables[i], tables[j])));
+    }
+    reached.iter().all(|r| *r)
+}
+
+// Largest party one table can hold
+pub(crate) fn largest_table<'a>(seats: impl IntoIterator<Item = &'a SeatConfig>) -> u32 {
+    seats.into_iter().filter(|s| is_table(s)).map(|s| seat_capacity(&s.type_)).max().unwrap_or(0)
+}
+
+// Seats of the largest group of tables that can all be pushed together; with
+// `accessible`, only groups with an accessible table count (wheelchair parties)
+pub(crate) fn largest_merged_capacity(seats: &[SeatConfig], accessible: bool) -> u32 {
+    let tables: Vec<&SeatConfig> = seats.iter().filter(|s| is_table(s)).collect();
+    let mut visited = vec![false; tables.len()];
+    let mut best = 0;
+    for start in 0..tables.len() {
+        if visited[start] { continue; }
+        visited[start] = true;
+        let (mut stack, mut capacity, mut reachable) = (vec![start], 0, !accessible);
+        while let Some(i) = stack.pop() {
+            capacity += seat_capacity(&tables[i].type_);
+            reachable |= wheelchair_limit(tables[i]) > 0;
+            for j in 0..tables.len() {
+                if !visited[j] && adjacent(tables[i], tables[j]) {
+                    visited[j] = true;
+                    stack.push(j);
+                }
+            }
+        }
+        if reachable {
+            best = best.max(capacity);
+        }
+    }
+    best
+}
+
+// Free adjacent tables that together seat the party: grown from each free table by
+// adding the biggest free neighbour until the party fits, then the group with the
+// fewest tables and least spare room wins. A wheelchair party's group starts from an
+// accessible table and also grows until it has a space for every wheelchair.
+pub(crate) fn try_merge_tables(res: &SushiResources, open: &SeatMask, customer: &CustomerConfig) -> Option<Vec<String>> {
+    if !pools_available(res, customer) {
+        return None;
+    }
+    let party = customer.party_size.max(1);
+    let needed = customer.wheelchair_count;
+    let free: Vec<&SeatState> = res.seats.iter()
+        .filter(|s| open.is_free(s) && is_table(&s.config))
+        .collect();
+
+    let mut best: Option<(usize, u32, Vec<usize>)> = None;
+    for start in 0..free.len() {
+        if needed > 0 && wheelchair_limit(&free[start].config) == 0 { continue; }
+        let mut group = vec![start];
+        let mut capacity = seat_capacity(&free[start].config.type_);
+        let mut room = wheelchair_limit(&free[start].config);
+        while capacity < party || room < needed {
+            // Short of wheelchair spaces, accessible neighbours come first
+            let next = (0..free.len())
+                .filter(|j| !group.contains(j) && group.iter().any(|&g| adjacent(&free[g].config, &free[*j].config)))
+                .max_by_key(|&j| (room < needed && wheelchair_limit(&free[j].config) > 0, seat_capacity(&free[j].config.type_)));
+            let Some(j) = next else { break };
+            group.push(j);
+            capacity += seat_capacity(&free[j].config.type_);
+            room += wheelchair_limit(&free[j].config);
+        }
+        if capacity < party || room < needed { continue; }
+        let key = (group.len(), capacity - party);
+        if best.as_ref().is_none_or(|(len, spare, _)| key < (*len, *spare)) {
+            best = Some((key.0, key.1, group));
+        }
+    }
+    best.map(|(_, _, group)| group.into_iter().map(|i| free[i].config.id.clone()).collect())
+}
@@ -0,0 +1,593 @@
+use super::journal::{self, Journal, JournalEntry};
+use super::{allocate_primary, allocate_within, initial_resources, log_event, release_party, reservations, seat_capacity, seat_party, seating_problem, store_run, strategy, Action, AllocationObjective, EngineBackend, EngineParams, SeatMask, SushiResources};
+use crate::access;
+use crate::bookings::roster_csv;
+use crate::config::SimulationConfig;
+use crate::ledger::{BABY_CHAIR_POOL, WHEELCHAIR_POOL};
+use crate::errors::{AppError, Result};
+use crate::generator::enforce_composition;
+use crate::live::{self, Governor, LiveStateStore, LiveView};
+use crate::models::{CustomerConfig, DayReport, OperationsJournal, OperationsStatus, SeatConfig, SeatRecommendation, SeatedParty, TableReservation, WaitingParty};
+use crate::parser;
+use crate::session::SessionStore;
+use crate::stats;
+use crate::validation;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{State, Window};
+
+const DEFAULT_DINING_TIME: u64 = 60;
+
+// recommend_seats: how many candidates to return and how they are scored
+const RECOMMENDATIONS: usize = 3;
+const BLOCKED_RESERVATION_PENALTY: f64 = 10.0;
+const WASTED_SEAT_PENALTY: f64 = 1.0;
+const CONVEYOR_BONUS: f64 = 2.0;
+
+// Operations mode: no roster, the clock is the wall clock (minutes since the shift
+// started) and the host enters parties as they walk in. The engine suggests seats with
+// the same allocation code as a simulated run, the host confirms them, and the shift's
+// event log builds up exactly like one. Ending the shift stores it as a regular run.
+// Every change is journaled to disk first, so a crash mid-service can be recovered.
+struct Shift {
+    started: Instant,
+    seat_config_json: String,
+    reservations_json: Option<String>,
+    seats_config: Vec<SeatConfig>,
+    params: EngineParams, // its reservations hold booked tables, as in a simulated run
+    live: LiveView,
+    governor: Option<Governor>, // pushes the live view to the window that opened the shift
+    res: SushiResources,
+    customers: Vec<CustomerConfig>,
+    waiting: Vec<u32>, // family ids in arrival order
+    seated: HashMap<u32, (Vec<String>, u64, u64)>, // family id -> (seats, seated at, expected leave)
+    journal: Journal,
+}
+
+#[derive(Default)]
+pub struct OperationsStore {
+    shifts: Mutex<HashMap<String, Shift>>,
+}
+
+impl Shift {
+    fn now(&self) -> u64 {
+        self.started.elapsed().as_secs() / 60
+    }
+
+    fn customer(&self, family_id: u32) -> Result<&CustomerConfig> {
+        self.customers.iter().find(|c| c.family_id == family_id)
+            .ok_or_else(|| AppError::SimulationError(format!("Unknown party {}", family_id)))
+    }
+
+    // Apply one change to the floor. Commands check it first; restoring replays it.
+    fn apply(&mut self, entry: &JournalEntry) -> Result<()> {
+        match entry {
+            JournalEntry::Start { .. } => {}
+            JournalEntry::Arrival { customer, .. } => {
+                let now = customer.arrival_time;
+                self.log(now, customer, Action::Arrive, "ARRIVAL", "arrived");
+                self.log(now, customer, Action::Wait, "WAITING", "waited");
+                self.waiting.push(customer.family_id);
+                self.customers.push(customer.clone());
+            }
+            JournalEntry::Seated { family_id, seat_ids, at } => {
+                let customer = self.customer(*family_id)?.clone();
+                let dining_time = seat_party(&mut self.res, &customer, seat_ids, *at);
+                self.waiting.retain(|f| f != family_id);
+                self.seated.insert(*family_id, (seat_ids.clone(), *at, at + dining_time));
+            }
+            JournalEntry::Departed { family_id, at } => {
+                let customer = self.customer(*family_id)?.clone();
+                if let Some((seat_ids, _, _)) = self.seated.remove(family_id) {
+                    release_party(&mut self.res, &customer, &seat_ids, *at);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Journal a change, then apply it
+    fn record(&mut self, entry: JournalEntry) -> Result<()> {
+        self.journal.append(&entry)?;
+        self.apply(&entry)
+    }
+
+    fn log(&mut self, time: u64, customer: &CustomerConfig, action: Action, event_type: &str, result: &str) {
+        log_event(&mut self.res, time, customer, action, event_type, result);
+        self.res.publish_live();
+    }
+
+    // Copy of the floor for what-if allocation, without history or live publishing
+    fn scratch(&self) -> SushiResources {
+        SushiResources {
+            ledger: self.res.ledger.balances_only(),
+            seats: self.res.seats.clone(),
+            live: None,
+            ..initial_resources(&[], &self.params)
+        }
+    }
+
+    // Seats for each waiting party, priority parties first and then in arrival order, as
+    // if the earlier suggestions were taken, so two parties are never offered the same table
+    fn suggestions(&self, now: u64) -> HashMap<u32, Vec<String>> {
+        let mut scratch = self.scratch();
+        let mut out = HashMap::new();
+        let mut waiting: Vec<&CustomerConfig> = self.waiting.iter()
+            .filter_map(|fid| self.customers.iter().find(|c| c.family_id == *fid))
+            .collect();
+        waiting.sort_by_key(|c| Reverse(c.priority));
+        for c in waiting {
+            let Some(seat_ids) = allocate_primary(&scratch, c, &self.params, now) else { continue };
+            hold(&mut scratch, c, &seat_ids, now);
+            out.insert(c.family_id, seat_ids);
+        }
+        out
+    }
+
+    // Bookings whose party hasn't checked in yet, by start
+    fn upcoming_reservations(&self) -> Vec<TableReservation> {
+        let mut upcoming: Vec<TableReservation> = self.params.reservations.iter()
+            .filter(|r| !self.customers.iter().any(|c| c.family_id == r.family_id))
+            .cloned()
+            .collect();
+        upcoming.sort_by_key(|r| (r.start, r.family_id));
+        upcoming
+    }
+
+    // When a party seated now is projected to leave: its own estimate stretched by the
+    // orders already in the kitchen and the queues at the stations
+    fn projected_departure(&self, party: &CustomerConfig, now: u64) -> u64 {
+        now + self.res.kitchen.clone().dining_time(now, party) + self.res.stations.clone().visit(now, party)
+    }
+
+    // Upcoming bookings still held at one of `seat_ids` and due before `departure`. The
+    // allocator keeps the stay a party announced clear of bookings, but a backed-up
+    // kitchen can stretch it into the next one.
+    fn blocked_reservations(&self, seat_ids: &[String], now: u64, departure: u64) -> Vec<u32> {
+        self.upcoming_reservations().into_iter()
+            .filter(|r| now < (r.start + self.params.reservation_grace).min(r.end) && r.start < departure)
+            .filter(|r| r.seat_ids.iter().any(|id| seat_ids.contains(id)))
+            .map(|r| r.family_id)
+            .collect()
+    }
+
+    fn status(&self, session_id: &str) -> OperationsStatus {
+        let now = self.now();
+        let mut suggestions = self.suggestions(now);
+        let name = |c: &CustomerConfig| c.extra.get("name").cloned();
+        OperationsStatus {
+            session_id: session_id.to_string(),
+            now,
+            waiting: self.waiting.iter()
+                .filter_map(|fid| self.customers.iter().find(|c| c.family_id == *fid))
+                .map(|c| WaitingParty {
+                    family_id: c.family_id,
+                    party_size: c.party_size,
+                    name: name(c),
+                    arrival_time: c.arrival_time,
+                    waited: now.saturating_sub(c.arrival_time),
+                    suggested_seats: suggestions.remove(&c.family_id),
+                })
+                .collect(),
+            seated: self.customers.iter()
+                .filter_map(|c| self.seated.get(&c.family_id).map(|s| (c, s)))
+                .map(|(c, (seat_ids, seated_at, expected_leave))| SeatedParty {
+                    family_id: c.family_id,
+                    party_size: c.party_size,
+                    name: name(c),
+                    seat_ids: seat_ids.clone(),
+                    seated_at: *seated_at,
+                    expected_leave: *expected_leave,
+                })
+                .collect(),
+            upcoming_reservations: self.upcoming_reservations(),
+            baby_chairs_available: self.res.ledger.available(BABY_CHAIR_POOL),
+            wheelchairs_available: self.res.ledger.available(WHEELCHAIR_POOL),
+        }
+    }
+}
+
+// Take seats and pools on a what-if floor
+fn hold(floor: &mut SushiResources, c: &CustomerConfig, seat_ids: &[String], now: u64) {
+    for seat in floor.seats.iter_mut().filter(|s| seat_ids.contains(&s.config.id)) {
+        seat.occupied_by = Some(c.family_id);
+    }
+    floor.ledger.acquire(BABY_CHAIR_POOL, c.baby_chair_count, c.family_id, now);
+    floor.ledger.acquire(WHEELCHAIR_POOL, c.wheelchair_count, c.family_id, now);
+}
+
+// Every seating the allocator would give a party right now: its own pick first, then
+// what it picks with the earlier picks ruled out, until nothing is left. Booked tables
+// stay out of reach as in a simulated run.
+fn candidate_seatings(res: &SushiResources, c: &CustomerConfig, params: &EngineParams, now: u64) -> Vec<Vec<String>> {
+    let mut candidates: Vec<Vec<String>> = Vec::new();
+    let mut open = SeatMask::default();
+    while let Some(seat_ids) = allocate_within(res, &open, c, params, now) {
+        open = open.closing(res, |s| seat_ids.contains(&s.config.id));
+        candidates.push(seat_ids);
+    }
+    candidates
+}
+
+impl OperationsStore {
+    fn with_shift<T>(&self, session_id: &str, f: impl FnOnce(&mut Shift) -> Result<T>) -> Result<T> {
+        let mut shifts = self.shifts.lock().unwrap();
+        let shift = shifts.get_mut(session_id)
+            .ok_or_else(|| AppError::SessionError(format!("No shift is open in session {}", session_id)))?;
+        f(shift)
+    }
+
+    // Journal files written by the shifts open right now
+    fn open_journals(&self) -> Vec<PathBuf> {
+        self.shifts.lock().unwrap().values().map(|s| s.journal.path().to_path_buf()).collect()
+    }
+
+    // Stop pushing live updates to a window that closed; its shifts stay open
+    pub fn drop_window(&self, label: &str) {
+        for shift in self.shifts.lock().unwrap().values_mut() {
+            if shift.governor.as_ref().is_some_and(|g| g.window_label() == label) {
+                shift.governor = None;
+            }
+        }
+    }
+}
+
+fn refuse_open_shift(shifts: &HashMap<String, Shift>, session_id: &str) -> Result<()> {
+    if shifts.contains_key(session_id) {
+        return Err(AppError::SessionError(format!("A shift is already open in session {}; end it first", session_id)));
+    }
+    Ok(())
+}
+
+// Open a shift on a seat layout; get_current_state follows it like a running simulation.
+// `reservations_json` lists the day's booked tables (TableReservation, minutes after the
+// shift starts); they are held for their parties exactly as in a simulated run.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn start_operations(
+    seat_config_json: String,
+    baby_chairs: i32,
+    wheelchairs: i32,
+    objective: Option<String>,
+    merge_tables: Option<bool>,
+    reservations_json: Option<String>,
+    live_emit_rate: Option<u32>,
+    session_id: Option<String>,
+    window: Window,
+    sessions: State<'_, SessionStore>,
+    live: State<'_, LiveStateStore>,
+    operations: State<'_, OperationsStore>,
+) -> Result<OperationsStatus> {
+    access::require_host(&window, "open a shift")?;
+    let session_id = sessions.resolve(session_id, &window);
+    // Checked before anything is written, so a bad layout leaves no journal behind
+    let seats_config: Vec<SeatConfig> = serde_json::from_str(&seat_config_json).map_err(|e| AppError::JsonParseError(e.to_string()))?;
+    objective.as_deref().map(AllocationObjective::from_name).transpose()?;
+    reservations_json.as_deref().map(|json| reservations::parse(json, &seats_config)).transpose()?;
+    validation::validate_inputs(&[], &seats_config, baby_chairs, wheelchairs, merge_tables.unwrap_or(false))?;
+    // Held until the shift is in, so two starts in one session can't both get through
+    let mut shifts = operations.shifts.lock().unwrap();
+    refuse_open_shift(&shifts, &session_id)?;
+    let start = JournalEntry::Start {
+        started_at: journal::unix_now(),
+        seat_config_json,
+        baby_chairs,
+        wheelchairs,
+        objective,
+        merge_tables: merge_tables.unwrap_or(false),
+        reservations_json,
+    };
+
+    let journal = Journal::create(journal::journal_path(&window, &session_id)?, std::slice::from_ref(&start))?;
+    let view = live.begin(&session_id);
+    let mut shift = open_shift(&start, Instant::now(), view, journal)?;
+    shift.governor = live::govern(shift.live.clone(), window.clone(), live_emit_rate.unwrap_or(live::DEFAULT_LIVE_EMIT_RATE));
+    shift.res.publish_live();
+    let status = shift.status(&session_id);
+    shifts.insert(session_id, shift);
+    Ok(status)
+}
+
+// Empty floor for a shift's Start entry, its clock running since `started`
+fn open_shift(start: &JournalEntry, started: Instant, view: LiveView, journal: Journal) -> Result<Shift> {
+    let JournalEntry::Start { seat_config_json, baby_chairs, wheelchairs, objective, merge_tables, reservations_json, .. } = start else {
+        return Err(AppError::ValidationError("A shift journal must begin with the shift's start".to_string()));
+    };
+    let seats_config: Vec<SeatConfig> = serde_json::from_str(seat_config_json)
+        .map_err(|e| AppError::JsonParseError(e.to_string()))?;
+    let defaults = SimulationConfig::default();
+    let params = EngineParams {
+        baby_chairs: *baby_chairs,
+        wheelchairs: *wheelchairs,
+        shadow_policy: None,
+        objective: objective.as_deref().map(AllocationObjective::from_name).transpose()?.unwrap_or(AllocationObjective::Wait),
+        strategy: &strategy::DefaultStrategy,
+        fairness: Default::default(),
+        conveyor_patience: defaults.conveyor_patience,
+        kitchen: defaults.kitchen,
+        deadlock_resolution: Default::default(),
+        instrument: false,
+        trace_sync: false,
+        engine: EngineBackend::Threads, // wall-clock time, like the realtime engine
+        live: None,
+        gate: None,
+        deliver_baby_chairs_later: false,
+        merge_tables: *merge_tables,
+        reservations: match reservations_json {
+            Some(json) => reservations::parse(json, &seats_config)?,
+            None => Vec::new(),
+        },
+        reservation_grace: defaults.reservation_grace,
+        stations: defaults.stations,
+        max_wait_time: None, // a host decides when a waiting party has left
+        requirement_changes: Vec::new(),
+        couriers: defaults.couriers, // walk-ins only
+    };
+
+    Ok(Shift {
+        started,
+        seat_config_json: seat_config_json.clone(),
+        reservations_json: reservations_json.clone(),
+        res: initial_resources(&seats_config, &EngineParams { live: Some(view.clone()), ..params.clone() }),
+        seats_config,
+        params,
+        live: view,
+        governor: None,
+        customers: Vec::new(),
+        waiting: Vec::new(),
+        seated: HashMap::new(),
+        journal,
+    })
+}
+
+// Shifts that were still open when the app last stopped, for a restore prompt at startup
+#[tauri::command]
+pub fn list_operations_journals(window: Window, operations: State<'_, OperationsStore>) -> Result<Vec<OperationsJournal>> {
+    journal::list(&window, &operations.open_journals())
+}
+
+// Rebuild a shift from its journal into the session: the waitlist, seated parties and
+// event log come back as they were, and the clock carries on from the original start
+// (the time the app was down counts). The journal keeps recording from there.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn restore_operations(
+    journal_id: String,
+    live_emit_rate: Option<u32>,
+    session_id: Option<String>,
+    window: Window,
+    sessions: State<'_, SessionStore>,
+    live: State<'_, LiveStateStore>,
+    operations: State<'_, OperationsStore>,
+) -> Result<OperationsStatus> {
+    access::require_host(&window, "restore a shift")?;
+    let session_id = sessions.resolve(session_id, &window);
+    let path = journal::journal_path(&window, &journal_id)?;
+    let mut shifts = operations.shifts.lock().unwrap();
+    refuse_open_shift(&shifts, &session_id)?;
+    // A live shift's journal has its writer already
+    if shifts.values().any(|s| s.journal.path() == path) {
+        return Err(AppError::SessionError(format!("Journal {} belongs to a shift that is still open", journal_id)));
+    }
+    let entries = journal::read(&path)?;
+    let JournalEntry::Start { started_at, .. } = &entries[0] else { unreachable!("journal::read checks the first entry") };
+    let elapsed = Duration::from_secs(journal::unix_now().saturating_sub(*started_at));
+    let started = Instant::now().checked_sub(elapsed).unwrap_or_else(Instant::now);
+
+    let journal = Journal::rewrite(path, &entries)?;
+    let view = live.begin(&session_id);
+    let mut shift = open_shift(&entries[0], started, view, journal)?;
+    shift.governor = live::govern(shift.live.clone(), window.clone(), live_emit_rate.unwrap_or(live::DEFAULT_LIVE_EMIT_RATE));
+    for entry in &entries[1..] {
+        shift.apply(entry)?;
+    }
+    shift.res.publish_live();
+    let status = shift.status(&session_id);
+    shifts.insert(session_id, shift);
+    Ok(status)
+}
+
+// Drop a journal the host chose not to restore
+#[tauri::command]
+pub fn discard_operations_journal(journal_id: String, window: Window, operations: State<'_, OperationsStore>) -> Result<()> {
+    access::require_host(&window, "discard a shift journal")?;
+    let path = journal::journal_path(&window, &journal_id)?;
+    if operations.open_journals().contains(&path) {
+        return Err(AppError::SessionError(format!("Journal {} belongs to a shift that is still open", journal_id)));
+    }
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+// A party walked in. It is queued with a seat suggestion; nobody is seated until the
+// host confirms with seat_walk_in. A booked party checks in with its reservation id.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn inject_arrival(
+    party_size: u32,
+    baby_chair_count: Option<u32>,
+    wheelchair_count: Option<u32>,
+    est_dining_time: Option<u64>,
+    name: Option<String>,
+    reservation_id: Option<u32>,
+    session_id: Option<String>,
+    window: Window,
+    sessions: State<'_, SessionStore>,
+    operations: State<'_, OperationsStore>,
+) -> Result<OperationsStatus> {
+    access::require_host(&window, "add parties")?;
+    let session_id = sessions.resolve(session_id, &window);
+    operations.with_shift(&session_id, |shift| {
+        let ((party_size, baby_chair_count, wheelchair_count), _) =
+            enforce_composition(party_size, baby_chair_count.unwrap_or(0), wheelchair_count.unwrap_or(0));
+        let now = shift.now();
+        // A checked-in booking arrives as the family its table is held for
+        let id = match reservation_id {
+            Some(rid) if shift.upcoming_reservations().iter().any(|r| r.family_id == rid) => rid,
+            Some(rid) => return Err(AppError::SimulationError(format!("No open reservation {}", rid))),
+            None => shift.customers.iter().map(|c| c.family_id)
+                .chain(shift.params.reservations.iter().map(|r| r.family_id))
+                .max().unwrap_or(0) + 1,
+        };
+        let mut extra = HashMap::new();
+        if let Some(name) = name.filter(|n| !n.trim().is_empty()) {
+            extra.insert("name".to_string(), name);
+        }
+        let customer = CustomerConfig {
+            id: id.into(),
+            family_id: id,
+            arrival_time: now,
+            type_: parser::customer_type(party_size, baby_chair_count, wheelchair_count),
+            party_size,
+            baby_chair_count,
+            wheelchair_count,
+            est_dining_time: est_dining_time.unwrap_or(DEFAULT_DINING_TIME),
+            conveyor_preference: 0.0,
+            dietary_flags: Vec::new(),
+            ordered_ahead: false,
+            extra,
+            max_wait_time: None,
+            priority: 0,
+        };
+        shift.record(JournalEntry::Arrival { customer, reservation_id })?;
+        Ok(shift.status(&session_id))
+    })
+}
+
+// Seat a waiting party, at the suggested seats unless the host picked others
+#[tauri::command]
+pub fn seat_walk_in(family_id: u32, seat_ids: Option<Vec<String>>, session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>, operations: State<'_, OperationsStore>) -> Result<OperationsStatus> {
+    access::require_host(&window, "seat parties")?;
+    let session_id = sessions.resolve(session_id, &window);
+    operations.with_shift(&session_id, |shift| {
+        if !shift.waiting.contains(&family_id) {
+            return Err(AppError::SimulationError(format!("Party {} is not waiting", family_id)));
+        }
+        let now = shift.now();
+        let seat_ids = match seat_ids {
+            Some(ids) => {
+                let party = shift.customer(family_id)?;
+                if let Some(problem) = seating_problem(&shift.res, party, &ids, shift.params.merge_tables) {
+                    return Err(AppError::SimulationError(problem));
+                }
+                ids
+            }
+            None => shift.suggestions(now).remove(&family_id)
+                .ok_or_else(|| AppError::SimulationError(format!("No seats free for party {} yet", family_id)))?,
+        };
+
+        shift.record(JournalEntry::Seated { family_id, seat_ids, at: now })?;
+        Ok(shift.status(&session_id))
+    })
+}
+
+// A seated party left; its seats and pools are free for the next suggestion
+#[tauri::command]
+pub fn record_departure(family_id: u32, session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>, operations: State<'_, OperationsStore>) -> Result<OperationsStatus> {
+    access::require_host(&window, "record departures")?;
+    let session_id = sessions.resolve(session_id, &window);
+    operations.with_shift(&session_id, |shift| {
+        if !shift.seated.contains_key(&family_id) {
+            return Err(AppError::SimulationError(format!("Party {} is not seated", family_id)));
+        }
+        let now = shift.now();
+        shift.record(JournalEntry::Departed { family_id, at: now })?;
+        Ok(shift.status(&session_id))
+    })
+}
+
+// Up to three ways to seat a waiting party now, best first. Each is scored on the
+// bookings it would run into, empty chairs and the conveyor view the party asked for;
+// tables held for bookings are never offered. The host makes the final call with
+// seat_walk_in.
+#[tauri::command]
+pub fn recommend_seats(family_id: u32, session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>, operations: State<'_, OperationsStore>) -> Result<Vec<SeatRecommendation>> {
+    let session_id = sessions.resolve(session_id, &window);
+    operations.with_shift(&session_id, |shift| {
+        if !shift.waiting.contains(&family_id) {
+            return Err(AppError::SimulationError(format!("Party {} is not waiting", family_id)));
+        }
+        let now = shift.now();
+        let party = shift.customer(family_id)?;
+        let engine_choice = shift.suggestions(now).remove(&family_id);
+        let departure = shift.projected_departure(party, now);
+
+        let mut recommendations: Vec<SeatRecommendation> = candidate_seatings(&shift.res, party, &shift.params, now).into_iter().map(|seat_ids| {
+            let capacity: u32 = seat_ids.iter()
+                .filter_map(|id| shift.res.seats.iter().find(|s| s.config.id == *id))
+                .map(|s| seat_capacity(&s.config.type_))
+                .sum();
+            let wasted_seats = capacity.saturating_sub(party.party_size);
+            let faces_conveyor = seat_ids.iter().all(|id| shift.res.seats.iter().any(|s| s.config.id == *id && s.config.faces_conveyor));
+            let blocked_reservations = shift.blocked_reservations(&seat_ids, now, departure);
+            let score = -(blocked_reservations.len() as f64) * BLOCKED_RESERVATION_PENALTY
+                - wasted_seats as f64 * WASTED_SEAT_PENALTY
+                + if faces_conveyor { party.conveyor_preference * CONVEYOR_BONUS } else { 0.0 };
+            SeatRecommendation {
+                engine_choice: engine_choice.as_ref() == Some(&seat_ids),
+                seat_ids,
+                score,
+                wasted_seats,
+                faces_conveyor,
+                blocked_reservations,
+            }
+        }).collect();
+
+        // Ties go to the engine's own pick, then layout order
+        recommendations.sort_by(|a, b| b.score.total_cmp(&a.score).then(b.engine_choice.cmp(&a.engine_choice)));
+        recommendations.truncate(RECOMMENDATIONS);
+        Ok(recommendations)
+    })
+}
+
+#[tauri::command]
+pub fn get_operations_status(session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>, operations: State<'_, OperationsStore>) -> Result<OperationsStatus> {
+    let session_id = sessions.resolve(session_id, &window);
+    operations.with_shift(&session_id, |shift| Ok(shift.status(&session_id)))
+}
+
+// Close the shift and keep it in the session as a run, so every report and the replay
+// work on it, with its end-of-day report. Parties still waiting or seated stay
+// open-ended in the log. With `report_path` the report is also written there as JSON.
+#[tauri::command]
+pub fn end_operations(report_path: Option<String>, session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>, operations: State<'_, OperationsStore>) -> Result<DayReport> {
+    access::require_host(&window, "end the shift")?;
+    let session_id = sessions.resolve(session_id, &window);
+    let shift = operations.shifts.lock().unwrap().remove(&session_id)
+        .ok_or_else(|| AppError::SessionError(format!("No shift is open in session {}", session_id)))?;
+    let status = shift.status(&session_id);
+    live::finish(&shift.live);
+
+    let Shift { seat_config_json, reservations_json, seats_config, params, mut res, customers, journal, governor, .. } = shift;
+    // Let the final state go out
+    if let Some(governor) = governor {
+        governor.join();
+    }
+    res.live = None;
+    let config = SimulationConfig::builder(roster_csv(&customers), seat_config_json)
+        .baby_chairs(params.baby_chairs)
+        .wheelchairs(params.wheelchairs)
+        .objective(Some(params.objective.name().to_string()))
+        .strategy(Some(params.strategy.name().to_string()))
+        .fairness(Some(params.fairness.name().to_string()))
+        .merge_tables(params.merge_tables)
+        .reservations_json(reservations_json)
+        .build();
+    store_run(res, config, customers, seats_config, params, &session_id, &sessions, &window)?;
+    // The shift is safely stored as a run; nothing is left to recover
+    journal.remove()?;
+
+    let report = sessions.with_session(&session_id, |run| {
+        let report = stats::day_report(run, &status);
+        run.day_report = Some(report.clone());
+        report
+    })?;
+    if let Some(path) = report_path {
+        let json = serde_json::to_string_pretty(&report).map_err(|e| AppError::JsonParseError(e.to_string()))?;
+        std::fs::write(path, json)?;
+    }
+    Ok(report)
+}
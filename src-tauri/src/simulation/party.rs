@@ -0,0 +1,91 @@
+use super::{allocate_primary, infeasibility, log_event, record_shadow_decision, refuse, release_party, renege, reservations, seat_party, wait_limit, Action, EngineParams, SushiResources};
+use crate::models::CustomerConfig;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// The arrive / wait / dine / leave steps of one party on a monitor engine. The thread
+// and tasks engines share them and only differ in how a party blocks: a Condvar on an
+// OS thread, or a Notify in a tokio task. Everything here runs with the monitor held.
+
+// What one attempt to seat a waiting party came to
+pub(super) enum Turn {
+    Seated(Vec<String>, u64), // seats and dining time
+    Gone,                     // left the queue for good; tell the other waiters
+    Wait,                     // nothing fits yet: block until something changes
+}
+
+pub(super) struct Waiter {
+    pub customer: CustomerConfig,
+    has_logged_wait: bool,
+    woke_by_notify: bool,
+}
+
+impl Waiter {
+    // Log the arrival; the party then waits for its turn
+    pub(super) fn arrive(res: &mut SushiResources, customer: CustomerConfig) -> Self {
+        log_event(res, customer.arrival_time, &customer, Action::Arrive, "ARRIVAL", "arrived");
+        Waiter { customer, has_logged_wait: false, woke_by_notify: false }
+    }
+
+    pub(super) fn attempt(&mut self, res: &mut SushiResources, params: &EngineParams, cancel: &AtomicBool) -> Turn {
+        let customer = &self.customer;
+        let mut now = std::cmp::max(res.events.latest_time(), customer.arrival_time);
+        let allocation = loop {
+            // Out of patience by the virtual clock: leave at the moment patience ran out,
+            // as the des engine does, not when whatever moved the clock along happened
+            if let Some(deadline) = wait_limit(customer, params).map(|limit| customer.arrival_time + limit).filter(|&deadline| now > deadline) {
+                renege(res, customer, deadline);
+                return Turn::Gone;
+            }
+            let allocation = if res.must_yield(customer, params) { None } else { allocate_primary(res, customer, params, now) };
+            match reservations::next_lapse(res, params, now) {
+                Some(lapse) if allocation.is_none() => now = lapse,
+                _ => break allocation,
+            }
+        };
+        if res.shadow.is_some() {
+            record_shadow_decision(res, now, customer, allocation.as_deref());
+        }
+        if let Some(seat_ids) = allocation {
+            let dining_time = seat_party(res, customer, &seat_ids, now);
+            return Turn::Seated(seat_ids, dining_time);
+        }
+
+        if self.woke_by_notify {
+            res.instrumentation.spurious_wakeup(customer.family_id);
+        }
+
+        // Allocation failed: a party no state of the run could seat leaves now
+        if !self.has_logged_wait {
+            if let Some(reason) = infeasibility(res, customer, params) {
+                refuse(res, customer, now, &reason);
+                return Turn::Gone;
+            }
+        }
+        res.take_ticket(customer);
+        if !self.has_logged_wait {
+            log_event(res, customer.arrival_time, customer, Action::Wait, "WAITING", "waited");
+            self.has_logged_wait = true;
+        }
+
+        // App is shutting down: give up waiting so the worker can exit
+        if cancel.load(Ordering::SeqCst) {
+            log_event(res, customer.arrival_time, customer, Action::Error, "CANCELLED", "simulation cancelled");
+            return Turn::Gone;
+        }
+
+        Turn::Wait
+    }
+
+    // The wait after Turn::Wait ended, by a notification or the poll timeout
+    pub(super) fn woke(&mut self, timed_out: bool) {
+        self.woke_by_notify = !timed_out;
+    }
+
+    // Done dining: leave the seats `dining_time` after sitting down
+    pub(super) fn leave(&self, res: &mut SushiResources, seat_ids: &[String], dining_time: u64) {
+        let sit_time = res.events.seatings().seated.get(&self.customer.family_id)
+            .map(|(time, _)| *time)
+            .unwrap_or(self.customer.arrival_time);
+        release_party(res, &self.customer, seat_ids, sit_time + dining_time);
+    }
+}
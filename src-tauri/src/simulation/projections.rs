@@ -0,0 +1,149 @@
+use super::{Action, Placement, SeatClass, SimEvent};
+use crate::models::{CustomerConfig, OccupancyState, Seat, SeatConfig, WaitingSubQueues};
+use crate::parser;
+use std::collections::{BTreeMap, BTreeSet};
+
+// Read models of the event store. The store keeps the ones engines read up to date as
+// events are appended; frames replay them in virtual-time order. A new view of a run is
+// a new Projection and leaves the engines alone.
+pub(super) trait Projection {
+    fn apply(&mut self, event: &SimEvent);
+}
+
+// Every seat with its occupant and the baby chairs and wheelchairs placed at it
+#[derive(Clone, Default)]
+pub(super) struct SeatMap {
+    pub seats: Vec<Seat>,
+}
+
+impl SeatMap {
+    pub(super) fn new<'a>(seats_config: impl IntoIterator<Item = &'a SeatConfig>) -> Self {
+        SeatMap {
+            seats: seats_config.into_iter().map(|s| Seat {
+                id: s.id.clone(),
+                type_: s.type_.clone(),
+                occupied_by: None,
+                occupant_type: None,
+                baby_chair_count: 0,
+                wheelchair_count: 0,
+                is_wheelchair_accessible: s.is_wheelchair_accessible,
+                state: OccupancyState::Free,
+            }).collect(),
+        }
+    }
+
+    fn seat(&mut self, id: &str) -> Option<&mut Seat> {
+        self.seats.iter_mut().find(|s| s.id == id.trim())
+    }
+
+    pub(super) fn any_occupied(&self) -> bool {
+        self.seats.iter().any(|s| s.occupied_by.is_some())
+    }
+}
+
+impl Projection for SeatMap {
+    fn apply(&mut self, event: &SimEvent) {
+        match &event.action {
+            Action::Sit(placement) => {
+                for (i, id) in placement.seat_ids.split(',').enumerate() {
+                    if let Some(s) = self.seat(id) {
+                        s.occupied_by = Some(event.family_id);
+                        s.state = OccupancyState::for_occupant(s.occupied_by);
+                        // Baby chairs and wheelchairs where the engine placed them
+                        s.baby_chair_count = placement.baby_chairs.get(i).copied().unwrap_or(0);
+                        s.wheelchair_count = placement.wheelchairs.get(i).copied().unwrap_or(0);
+                        s.occupant_type = Some(if s.wheelchair_count > 0 { "WHEELCHAIR" } else { "NORMAL" }.to_string());
+                    }
+                }
+            }
+            Action::Leave(ids) => {
+                for id in ids.split(',') {
+                    if let Some(s) = self.seat(id) {
+                        s.occupied_by = None;
+                        s.state = OccupancyState::for_occupant(None);
+                        s.occupant_type = None;
+                        s.baby_chair_count = 0;
+                        s.wheelchair_count = 0;
+                    }
+                }
+            }
+            Action::Deliver(id, count) => {
+                if let Some(s) = self.seat(id) {
+                    s.baby_chair_count += count;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// Parties waiting for a seat, those that ever arrived, and every party's needs as of its
+// latest requirement change
+#[derive(Clone, Default)]
+pub(super) struct Queue {
+    pub customers: Vec<CustomerConfig>,
+    pub waiting: BTreeSet<u32>,
+    pub arrived: BTreeSet<u32>,
+}
+
+impl Queue {
+    pub(super) fn new(customers: &[CustomerConfig]) -> Self {
+        Queue { customers: customers.to_vec(), ..Default::default() }
+    }
+
+    pub(super) fn customer(&self, family_id: u32) -> Option<&CustomerConfig> {
+        self.customers.iter().find(|c| c.family_id == family_id)
+    }
+
+    // Waiting parties in roster (arrival) order
+    pub(super) fn waiting_customers(&self) -> impl Iterator<Item = &CustomerConfig> {
+        self.customers.iter().filter(|c| self.waiting.contains(&c.family_id))
+    }
+
+    pub(super) fn sub_queues(&self) -> WaitingSubQueues {
+        let mut sub_queues = WaitingSubQueues::default();
+        for c in self.waiting_customers() {
+            SeatClass::for_customer(c).queue_mut(&mut sub_queues).push(c.family_id);
+        }
+        sub_queues
+    }
+}
+
+impl Projection for Queue {
+    fn apply(&mut self, event: &SimEvent) {
+        match &event.action {
+            Action::Arrive | Action::Wait => {
+                self.waiting.insert(event.family_id);
+                self.arrived.insert(event.family_id);
+            }
+            Action::Sit(_) | Action::Error | Action::Renege | Action::Infeasible => { self.waiting.remove(&event.family_id); }
+            Action::Requirements(party_size, baby_chairs, wheelchairs) => {
+                if let Some(c) = self.customers.iter_mut().find(|c| c.family_id == event.family_id) {
+                    c.party_size = *party_size;
+                    c.baby_chair_count = *baby_chairs;
+                    c.wheelchair_count = *wheelchairs;
+                    c.type_ = parser::customer_type(c.party_size, c.baby_chair_count, c.wheelchair_count);
+                }
+            }
+            Action::Leave(_) | Action::Deliver(..) | Action::Deadlock | Action::Priority | Action::Courier(_) => {}
+        }
+    }
+}
+
+// When and where each family was first seated, the basis of wait statistics
+#[derive(Clone, Default)]
+pub(super) struct Seatings {
+    pub seated: BTreeMap<u32, (u64, Placement)>, // by family, so sums over it are repeatable
+}
+
+impl Projection for Seatings {
+    fn apply(&mut self, event: &SimEvent) {
+        if let Action::Sit(placement) = &event.action {
+            // Threads may append a little out of virtual-time order; the earliest counts
+            let first = self.seated.entry(event.family_id).or_insert_with(|| (event.time, placement.clone()));
+            if event.time < first.0 {
+                *first = (event.time, placement.clone());
+            }
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use super::{seating_problem, EngineParams, SushiResources};
+use crate::errors::{AppError, Result};
+use crate::models::{CustomerConfig, SeatConfig, TableReservation};
+use std::collections::HashSet;
+
+// Minutes a reserved table is held for a booked party that hasn't shown up yet
+pub(crate) const DEFAULT_RESERVATION_GRACE: u64 = 15;
+
+// Parse and check the reservations list against the layout
+pub(crate) fn parse(json: &str, seats: &[SeatConfig]) -> Result<Vec<TableReservation>> {
+    let reservations: Vec<TableReservation> = serde_json::from_str(json)
+        .map_err(|e| AppError::JsonParseError(e.to_string()))?;
+    let mut booked = HashSet::new();
+    for r in &reservations {
+        if !booked.insert(r.family_id) {
+            return Err(AppError::ValidationError(format!("Family {} has more than one reservation", r.family_id)));
+        }
+        if r.seat_ids.is_empty() {
+            return Err(AppError::ValidationError(format!("Reservation of family {} has no seats", r.family_id)));
+        }
+        if let Some(id) = r.seat_ids.iter().find(|id| !seats.iter().any(|s| s.id == **id)) {
+            return Err(AppError::ValidationError(format!("Reservation of family {} names unknown seat {}", r.family_id, id)));
+        }
+        if r.start > r.end {
+            return Err(AppError::ValidationError(format!("Reservation of family {} ends before it starts", r.family_id)));
+        }
+    }
+    Ok(reservations)
+}
+
+fn has_event(res: &SushiResources, family_id: u32, sat: bool) -> bool {
+    if sat {
+        res.events.seatings().seated.contains_key(&family_id)
+    } else {
+        res.events.queue().arrived.contains(&family_id)
+    }
+}
+
+// The party's own reserved seats, if it has a booking and could sit there now: the seats
+// are free, hold the party and its wheelchairs, and make a seating the allocator allows
+pub(crate) fn booked_seats(res: &SushiResources, customer: &CustomerConfig, params: &EngineParams, now: u64) -> Option<Vec<String>> {
+    let r = params.reservations.iter().find(|r| r.family_id == customer.family_id && now <= r.end)?;
+    seating_problem(res, customer, &r.seat_ids, params.merge_tables).is_none().then(|| r.seat_ids.clone())
+}
+
+// Seats the party may not take now because its stay would run into someone else's
+// booking. A table is held from the reservation start until the booked party is
+// seated, or until the grace period runs out if it hasn't arrived by then; a party
+// that did arrive keeps the hold until the reservation ends.
+pub(crate) fn held_seats(res: &SushiResources, customer: &CustomerConfig, params: &EngineParams, now: u64) -> HashSet<String> {
+    let stay_end = now + customer.est_dining_time.max(1);
+    params.reservations.iter()
+        .filter(|r| r.family_id != customer.family_id && !has_event(res, r.family_id, true))
+        .filter(|r| {
+            let hold_end = if has_event(res, r.family_id, false) { r.end } else { (r.start + params.reservation_grace).min(r.end) };
+            now < hold_end && stay_end > r.start
+        })
+        .flat_map(|r| r.seat_ids.iter().cloned())
+        .collect()
+}
+
+// Times a hold may lapse, when engines that only re-check on events should look again
+pub(crate) fn expiry_times(params: &EngineParams) -> Vec<u64> {
+    params.reservations.iter()
+        .flat_map(|r| [(r.start + params.reservation_grace).min(r.end), r.end])
+        .collect()
+}
+
+// The engines without an event queue only move the clock when a party acts, so with
+// nobody seated a party kept off a reserved table would wait for good. It looks ahead to
+// the next time a hold may lapse instead, when the DES engine would re-check.
+pub(crate) fn next_lapse(res: &SushiResources, params: &EngineParams, now: u64) -> Option<u64> {
+    if res.events.seat_map().any_occupied() {
+        return None;
+    }
+    expiry_times(params).into_iter().filter(|&t| t > now).min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{engine_params, initial_resources, log_event, parser, seat_party, Action};
+    use super::*;
+    use crate::config::SimulationConfig;
+
+    const SEATS: &str = r#"[{"id":"B1","type":"SINGLE","isWheelchairAccessible":false},{"id":"T1","type":"4P","isWheelchairAccessible":true}]"#;
+
+    // Family 9 has T1 from minute 30 to 90, held for it until 45 unless it shows up
+    fn booked_layout() -> (Vec<SeatConfig>, EngineParams) {
+        let seats: Vec<SeatConfig> = serde_json::from_str(SEATS).unwrap();
+        let config = SimulationConfig {
+            reservations_json: Some(r#"[{"familyId":9,"seatIds":["T1"],"start":30,"end":90}]"#.to_string()),
+            ..SimulationConfig::default()
+        };
+        let params = engine_params(&config, &seats).unwrap();
+        (seats, params)
+    }
+
+    fn party(csv: &str) -> CustomerConfig {
+        parser::parse_customers(csv).unwrap().remove(0)
+    }
+
+    #[test]
+    fn bad_reservations_are_refused() {
+        let seats: Vec<SeatConfig> = serde_json::from_str(SEATS).unwrap();
+        for (json, message) in [
+            (r#"[{"familyId":1,"seatIds":["T1"],"start":0,"end":9},{"familyId":1,"seatIds":["B1"],"start":0,"end":9}]"#, "Family 1 has more than one reservation"),
+            (r#"[{"familyId":1,"seatIds":[],"start":0,"end":9}]"#, "Reservation of family 1 has no seats"),
+            (r#"[{"familyId":1,"seatIds":["T9"],"start":0,"end":9}]"#, "Reservation of family 1 names unknown seat T9"),
+            (r#"[{"familyId":1,"seatIds":["T1"],"start":9,"end":0}]"#, "Reservation of family 1 ends before it starts"),
+        ] {
+            assert!(matches!(parse(json, &seats), Err(AppError::ValidationError(m)) if m == message), "{}", message);
+        }
+    }
+
+    // A walk-in whose stay would run into the booking is kept off the table until the
+    // grace period is up; one that leaves before the booking starts may sit there
+    #[test]
+    fn holds_lapse_after_the_grace_period() {
+        let (seats, params) = booked_layout();
+        let res = initial_resources(&seats, &params);
+        let long_stay = party("1,0,,2,0,0,60\n");
+        assert!(held_seats(&res, &long_stay, &params, 0).contains("T1"));
+        assert!(held_seats(&res, &long_stay, &params, 44).contains("T1"));
+        assert!(held_seats(&res, &long_stay, &params, 45).is_empty());
+        assert!(held_seats(&res, &party("1,0,,2,0,0,30\n"), &params, 0).is_empty());
+    }
+
+    #[test]
+    fn a_booked_party_that_arrived_keeps_its_table_until_the_end() {
+        let (seats, params) = booked_layout();
+        let mut res = initial_resources(&seats, &params);
+        let booked = party("9,40,,3,0,0,60\n");
+        log_event(&mut res, 40, &booked, Action::Arrive, "ARRIVAL", "arrived");
+        let walk_in = party("1,50,,2,0,0,20\n");
+        assert!(held_seats(&res, &walk_in, &params, 50).contains("T1"));
+        assert!(held_seats(&res, &walk_in, &params, 90).is_empty());
+        assert_eq!(booked_seats(&res, &booked, &params, 50), Some(vec!["T1".to_string()]));
+        assert_eq!(booked_seats(&res, &booked, &params, 91), None);
+    }
+
+    // Without anyone seated nothing else moves the clock, so it jumps to the next lapse
+    #[test]
+    fn next_lapse_only_looks_ahead_with_nobody_seated() {
+        let (seats, params) = booked_layout();
+        let mut res = initial_resources(&seats, &params);
+        assert_eq!(expiry_times(&params), [45, 90]);
+        assert_eq!(next_lapse(&res, &params, 0), Some(45));
+        assert_eq!(next_lapse(&res, &params, 45), Some(90));
+        assert_eq!(next_lapse(&res, &params, 90), None);
+        seat_party(&mut res, &party("2,0,,1,0,0,10\n"), &["B1".to_string()], 0);
+        assert_eq!(next_lapse(&res, &params, 0), None);
+    }
+}
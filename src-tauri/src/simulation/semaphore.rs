@@ -0,0 +1,446 @@
+use super::{allocate_primary, log_event, infeasibility, initial_resources, record_shadow_decision, pass_gate, refuse, release_party, renege, reservations, seat_capacity, seat_party, sleep_unless_cancelled, wait_limit, Action, EngineParams, SushiResources, CANCEL_POLL_MS, REALTIME_MS_PER_MINUTE};
+use crate::deadlock::{DeadlockResolution, WaitForGraph, DEADLOCK_EVENT};
+use crate::errors::{AppError, Result};
+use crate::instrumentation::lock_timed;
+use crate::ledger::{BABY_CHAIR_POOL, WHEELCHAIR_POOL};
+use crate::models::{CustomerConfig, SeatConfig};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+// Counting semaphore (std has none): P takes permits, V returns them
+pub(crate) struct Semaphore {
+    permits: Mutex<u32>,
+    changed: Condvar,
+}
+
+impl Semaphore {
+    fn new(permits: u32) -> Self {
+        Semaphore { permits: Mutex::new(permits), changed: Condvar::new() }
+    }
+
+    // P(n): take n permits at once, blocking until they are all free.
+    // Returns false if the run is cancelled or the party runs out of patience first.
+    fn acquire(&self, n: u32, cancel: &AtomicBool, expired: impl Fn() -> bool) -> bool {
+        let mut permits = self.permits.lock().unwrap();
+        while *permits < n {
+            if cancel.load(Ordering::SeqCst) || expired() { return false; }
+            permits = self.changed.wait_timeout(permits, Duration::from_millis(CANCEL_POLL_MS)).unwrap().0;
+        }
+        *permits -= n;
+        true
+    }
+
+    fn try_acquire(&self, n: u32) -> bool {
+        let mut permits = self.permits.lock().unwrap();
+        if *permits < n { return false; }
+        *permits -= n;
+        true
+    }
+
+    // V(n)
+    fn release(&self, n: u32) {
+        *self.permits.lock().unwrap() += n;
+        self.changed.notify_all();
+    }
+}
+
+// Counts the moments a waiting party's allocation can turn out differently: a party
+// gave back seats and chairs, left the queue, or was seated and no longer goes first.
+// A party that couldn't be seated sleeps until the count moves past what it saw before
+// trying, not merely until some permit is free, which may well be one it can't use.
+struct Releases {
+    count: Mutex<u64>,
+    changed: Condvar,
+}
+
+impl Releases {
+    fn seen(&self) -> u64 {
+        *self.count.lock().unwrap()
+    }
+
+    fn bump(&self) {
+        *self.count.lock().unwrap() += 1;
+        self.changed.notify_all();
+    }
+
+    // Block until a release after `seen`, or the timeout passes (to poll the cancel flag)
+    fn wait_after(&self, seen: u64, timeout: Duration) {
+        let count = self.count.lock().unwrap();
+        drop(self.changed.wait_timeout_while(count, timeout, |count| *count == seen).unwrap());
+    }
+}
+
+// How far a party got with its chairs or its seat
+enum Step<T> {
+    Done(T),
+    Retry, // closed a wait cycle and backed off: everything given back, start over
+    Gone,  // left: out of patience, aborted or cancelled
+}
+
+// Semaphore engine: baby chairs and wheelchairs are counting semaphores, every seat type
+// has a semaphore counting its free seats, and the seat table itself sits behind a short
+// seat lock used only to pick and mark seats. Unlike the monitor engine, a party takes its
+// chairs one at a time before it has a seat (hold-and-wait), which is worth comparing:
+// two parties can each hold a chair the other waits for. The wait-for graph catches that.
+struct Shared {
+    baby_chairs: Semaphore,
+    wheelchairs: Semaphore,
+    seat_types: BTreeMap<String, Semaphore>,
+    seat_lock: Mutex<SushiResources>,
+    releases: Releases,
+    wait_for: Mutex<WaitForGraph>, // never locked while waiting for the seat lock
+}
+
+impl Shared {
+    fn new(seats_config: &[SeatConfig], params: &EngineParams) -> Result<Self> {
+        let mut seat_types: BTreeMap<String, Semaphore> = BTreeMap::new();
+        for seat in seats_config {
+            seat_types.entry(seat.type_.clone()).or_insert_with(|| Semaphore::new(0)).release(1);
+        }
+        if seat_types.is_empty() {
+            return Err(AppError::SimulationError("The layout has no seats".to_string()));
+        }
+        Ok(Shared {
+            baby_chairs: Semaphore::new(params.baby_chairs.max(0) as u32),
+            wheelchairs: Semaphore::new(params.wheelchairs.max(0) as u32),
+            seat_types,
+            seat_lock: Mutex::new(initial_resources(seats_config, params)),
+            releases: Releases { count: Mutex::new(0), changed: Condvar::new() },
+            wait_for: Mutex::new(WaitForGraph::default()),
+        })
+    }
+
+    fn log(&self, time: u64, customer: &CustomerConfig, action: Action, event_type: &str, result: &str) {
+        log_event(&mut lock_timed(&self.seat_lock, customer.family_id), time, customer, action, event_type, result);
+    }
+
+    fn now(&self, customer: &CustomerConfig) -> u64 {
+        let res = self.seat_lock.lock().unwrap();
+        std::cmp::max(res.events.latest_time(), customer.arrival_time)
+    }
+
+    // Pick seats under the seat lock and take their type permits.
+    // Returns the seats and dining time, or None if nothing fits yet.
+    fn try_seat(&self, customer: &CustomerConfig, params: &EngineParams) -> Option<(Vec<String>, u64, u64)> {
+        let mut res = lock_timed(&self.seat_lock, customer.family_id);
+        let mut now = std::cmp::max(res.events.latest_time(), customer.arrival_time);
+        let allocation = loop {
+            let allocation = if res.must_yield(customer, params) { None } else { allocate_primary(&res, customer, params, now) };
+            match reservations::next_lapse(&res, params, now) {
+                Some(lapse) if allocation.is_none() => now = lapse,
+                _ => break allocation,
+            }
+        };
+        if res.shadow.is_some() {
+            record_shadow_decision(&mut res, now, customer, allocation.as_deref());
+        }
+        let Some(seat_ids) = allocation else {
+            res.take_ticket(customer);
+            return None;
+        };
+
+        // The seat table and the type semaphores change together under the seat lock,
+        // so the permits for free seats are always there
+        for sid in &seat_ids {
+            if let Some(seat) = res.seats.iter().find(|s| s.config.id == *sid) {
+                let taken = self.seat_types[&seat.config.type_].try_acquire(1);
+                debug_assert!(taken, "seat semaphore out of sync with seat table");
+            }
+        }
+        let dining_time = seat_party(&mut res, customer, &seat_ids, now);
+        Some((seat_ids, now, dining_time))
+    }
+
+    fn refuse_if_infeasible(&self, customer: &CustomerConfig, params: &EngineParams) -> bool {
+        let mut res = lock_timed(&self.seat_lock, customer.family_id);
+        let Some(reason) = infeasibility(&res, customer, params) else { return false };
+        refuse(&mut res, customer, customer.arrival_time, &reason);
+        drop(res);
+        self.releases.bump();
+        true
+    }
+
+    // Whether the party has waited longer than its max_wait_time on the virtual clock.
+    // Takes the seat lock; pool permits are never taken with the seat lock held, so a
+    // party blocked on a pool may ask.
+    fn out_of_patience(&self, customer: &CustomerConfig, limit: Option<u64>) -> bool {
+        limit.is_some_and(|limit| self.now(customer) > customer.arrival_time + limit)
+    }
+
+    // P on the chair pools, one permit at a time; the permits taken so far stay in `held`
+    // while the party waits for the next
+    fn take_pools<'a>(&'a self, customer: &CustomerConfig, params: &EngineParams, cancel: &AtomicBool, limit: Option<u64>, held: &mut Vec<&'a Semaphore>, has_logged_wait: &mut bool) -> Step<()> {
+        for (pool, name, n) in [(&self.baby_chairs, BABY_CHAIR_POOL, customer.baby_chair_count), (&self.wheelchairs, WHEELCHAIR_POOL, customer.wheelchair_count)] {
+            for _ in 0..n {
+                if !pool.try_acquire(1) {
+                    if !*has_logged_wait {
+                        self.log(customer.arrival_time, customer, Action::Wait, "WAITING", "waited for pool");
+                        *has_logged_wait = true;
+                    }
+                    if self.closes_cycle(customer, vec![name.to_string()]) {
+                        return self.resolve(customer, params, held);
+                    }
+                    let taken = pool.acquire(1, cancel, || self.out_of_patience(customer, limit));
+                    self.wait_for.lock().unwrap().stop_waiting(customer.family_id);
+                    if !taken {
+                        if !cancel.load(Ordering::SeqCst) {
+                            self.give_up(customer, held, limit);
+                        }
+                        return Step::Gone;
+                    }
+                }
+                held.push(pool);
+                self.wait_for.lock().unwrap().hold(customer.family_id, &[name.to_string()]);
+            }
+        }
+        Step::Done(())
+    }
+
+    // Then a seat: retry whenever a seat of the wanted type frees up
+    fn take_seat(&self, customer: &CustomerConfig, params: &EngineParams, cancel: &AtomicBool, limit: Option<u64>, held: &mut Vec<&Semaphore>, has_logged_wait: &mut bool) -> Step<(Vec<String>, u64, u64)> {
+        loop {
+            pass_gate(params, cancel);
+            if self.out_of_patience(customer, limit) {
+                self.give_up(customer, held, limit);
+                return Step::Gone;
+            }
+            // Read first, so a release while trying isn't slept through
+            let seen = self.releases.seen();
+            if let Some(seated) = self.try_seat(customer, params) {
+                let mut graph = self.wait_for.lock().unwrap();
+                graph.stop_waiting(customer.family_id);
+                graph.hold(customer.family_id, &seated.0);
+                drop(graph);
+                self.releases.bump();
+                return Step::Done(seated);
+            }
+            if !*has_logged_wait {
+                self.log(customer.arrival_time, customer, Action::Wait, "WAITING", "waited");
+                *has_logged_wait = true;
+            }
+            if cancel.load(Ordering::SeqCst) {
+                let now = self.now(customer);
+                self.log(now, customer, Action::Error, "CANCELLED", "simulation cancelled");
+                return Step::Gone;
+            }
+            if self.closes_cycle(customer, self.wanted_seats(customer)) {
+                return self.resolve(customer, params, held);
+            }
+            self.releases.wait_after(seen, Duration::from_millis(CANCEL_POLL_MS));
+        }
+    }
+
+    // Seats the party could sit at once they free up, for the wait-for graph
+    fn wanted_seats(&self, customer: &CustomerConfig) -> Vec<String> {
+        let res = self.seat_lock.lock().unwrap();
+        res.seats.iter()
+            .filter(|s| if customer.wheelchair_count > 0 {
+                s.config.is_wheelchair_accessible && s.config.type_ != "SINGLE"
+            } else {
+                s.config.type_ == "SINGLE" || seat_capacity(&s.config.type_) >= customer.party_size
+            })
+            .map(|s| s.config.id.clone())
+            .collect()
+    }
+
+    // Record what the party is about to block on. If that closes a wait cycle, withdraw
+    // the wait and log the cycle.
+    fn closes_cycle(&self, customer: &CustomerConfig, resources: Vec<String>) -> bool {
+        let mut graph = self.wait_for.lock().unwrap();
+        graph.wait_for(customer.family_id, resources);
+        let Some(cycle) = graph.cycle_through(customer.family_id) else { return false };
+        let description = graph.describe(&cycle);
+        graph.stop_waiting(customer.family_id);
+        drop(graph);
+        let now = self.now(customer);
+        self.log(now, customer, Action::Deadlock, DEADLOCK_EVENT, &description);
+        true
+    }
+
+    // Break the cycle the party closed: it gives back every permit it holds, then leaves
+    // unserved (Abort) or tries again after the next release (BackOff)
+    fn resolve<T>(&self, customer: &CustomerConfig, params: &EngineParams, held: &mut Vec<&Semaphore>) -> Step<T> {
+        self.give_back(customer, held);
+        held.clear();
+        if params.deadlock_resolution == DeadlockResolution::BackOff {
+            return Step::Retry;
+        }
+        let mut res = lock_timed(&self.seat_lock, customer.family_id);
+        res.drop_ticket(customer.family_id);
+        let now = std::cmp::max(res.events.latest_time(), customer.arrival_time);
+        log_event(&mut res, now, customer, Action::Error, "ABORTED", "left to break a deadlock");
+        drop(res);
+        self.releases.bump();
+        Step::Gone
+    }
+
+    // A backed-off party sleeps until something is released after `seen`
+    fn back_off(&self, customer: &CustomerConfig, seen: u64, cancel: &AtomicBool, limit: Option<u64>) {
+        while self.releases.seen() == seen && !cancel.load(Ordering::SeqCst) && !self.out_of_patience(customer, limit) {
+            self.releases.wait_after(seen, Duration::from_millis(CANCEL_POLL_MS));
+        }
+    }
+
+    fn give_back(&self, customer: &CustomerConfig, held: &[&Semaphore]) {
+        for pool in held {
+            pool.release(1);
+        }
+        let mut graph = self.wait_for.lock().unwrap();
+        graph.stop_waiting(customer.family_id);
+        graph.release_all(customer.family_id);
+    }
+
+    // Out of patience: hand back the pool permits already taken and leave unserved at
+    // the moment patience ran out, as the des engine does
+    fn give_up(&self, customer: &CustomerConfig, held: &[&Semaphore], limit: Option<u64>) {
+        self.give_back(customer, held);
+        let mut res = lock_timed(&self.seat_lock, customer.family_id);
+        let now = std::cmp::max(res.events.latest_time(), customer.arrival_time);
+        renege(&mut res, customer, limit.map_or(now, |limit| customer.arrival_time + limit));
+        drop(res);
+        self.releases.bump();
+    }
+
+    fn leave(&self, customer: &CustomerConfig, seat_ids: &[String], leave_time: u64) {
+        let mut res = lock_timed(&self.seat_lock, customer.family_id);
+        release_party(&mut res, customer, seat_ids, leave_time);
+        for sid in seat_ids {
+            if let Some(seat) = res.seats.iter().find(|s| s.config.id == *sid) {
+                self.seat_types[&seat.config.type_].release(1);
+            }
+        }
+        drop(res);
+
+        self.baby_chairs.release(customer.baby_chair_count);
+        self.wheelchairs.release(customer.wheelchair_count);
+        self.wait_for.lock().unwrap().release_all(customer.family_id);
+        self.releases.bump();
+    }
+}
+
+pub(super) fn run(customers: &[CustomerConfig], seats_config: &[SeatConfig], params: &EngineParams, cancel: &Arc<AtomicBool>) -> Result<SushiResources> {
+    let shared = Arc::new(Shared::new(seats_config, params)?);
+
+    let mut handles = vec![];
+    for customer in customers.iter().cloned() {
+        let shared = Arc::clone(&shared);
+        let params = params.clone();
+        let cancel = Arc::clone(cancel);
+
+        handles.push(thread::spawn(move || {
+            pass_gate(&params, &cancel);
+            shared.log(customer.arrival_time, &customer, Action::Arrive, "ARRIVAL", "arrived");
+
+            // Chairs are taken before a seat, so a party that could never be seated must
+            // be turned away before it blocks on a pool it can never fill
+            if shared.refuse_if_infeasible(&customer, &params) {
+                return;
+            }
+
+            // A party with a max_wait_time leaves unserved once it has waited that long on
+            // the virtual clock
+            let limit = wait_limit(&customer, &params);
+
+            // P on the chair pools first, then a seat; any blocking counts as waiting
+            let mut has_logged_wait = false;
+            let (seat_ids, sit_time, dining_time) = loop {
+                let seen = shared.releases.seen();
+                let mut held = Vec::new();
+                let step = match shared.take_pools(&customer, &params, &cancel, limit, &mut held, &mut has_logged_wait) {
+                    Step::Done(()) => shared.take_seat(&customer, &params, &cancel, limit, &mut held, &mut has_logged_wait),
+                    Step::Retry => Step::Retry,
+                    Step::Gone => Step::Gone,
+                };
+                match step {
+                    Step::Done(seated) => break seated,
+                    Step::Retry => shared.back_off(&customer, seen, &cancel, limit),
+                    Step::Gone => return,
+                }
+            };
+
+            sleep_unless_cancelled(Duration::from_millis(dining_time * REALTIME_MS_PER_MINUTE), &cancel, params.gate.as_deref());
+            shared.leave(&customer, &seat_ids, sit_time + dining_time);
+        }));
+    }
+
+    for h in handles { let _ = h.join(); }
+
+    if cancel.load(Ordering::SeqCst) {
+        return Err(AppError::SimulationError("Simulation cancelled by application shutdown".to_string()));
+    }
+
+    let shared = Arc::try_unwrap(shared)
+        .map_err(|_| AppError::SimulationError("Worker threads still hold the seat lock".to_string()))?;
+    Ok(shared.seat_lock.into_inner().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SimulationConfig;
+    use crate::parser;
+    use crate::simulation::{engine_params, run_sequences};
+
+    // Two tables and a pool of two baby chairs; both parties need both chairs
+    fn setup(resolution: &str) -> (Shared, EngineParams, Vec<CustomerConfig>) {
+        let seats: Vec<SeatConfig> = serde_json::from_str(r#"[{"id":"T1","type":"4P","isWheelchairAccessible":false},{"id":"T2","type":"4P","isWheelchairAccessible":false}]"#).unwrap();
+        let config = SimulationConfig {
+            baby_chairs: 2,
+            engine: Some("semaphores".to_string()),
+            deadlock_resolution: Some(resolution.to_string()),
+            ..SimulationConfig::default()
+        };
+        let params = engine_params(&config, &seats).unwrap();
+        let customers = parser::parse_customers("1,0,,2,2,0,5\n2,0,,2,2,0,5\n").unwrap();
+        (Shared::new(&seats, &params).unwrap(), params, customers)
+    }
+
+    // Party 2 takes one chair, party 1 the other and blocks on its second one; then party
+    // 2 asks for its second chair and the two wait on each other. Returns what became of
+    // party 2, how many chairs party 1 ended up with and each family's events.
+    fn circular_wait(resolution: &str) -> (Step<()>, usize, BTreeMap<u32, Vec<String>>) {
+        let (shared, params, customers) = setup(resolution);
+        let (first, second) = (&customers[0], &customers[1]);
+        let one_chair = CustomerConfig { baby_chair_count: 1, ..second.clone() };
+        let cancel = AtomicBool::new(false);
+        let mut second_held = Vec::new();
+        let mut second_logged = false;
+        assert!(matches!(shared.take_pools(&one_chair, &params, &cancel, None, &mut second_held, &mut second_logged), Step::Done(())));
+
+        let (step, first_chairs) = thread::scope(|scope| {
+            let first_party = scope.spawn(|| {
+                let mut held = Vec::new();
+                let step = shared.take_pools(first, &params, &cancel, None, &mut held, &mut false);
+                assert!(matches!(step, Step::Done(())));
+                held.len()
+            });
+            while !shared.wait_for.lock().unwrap().is_waiting(first.family_id) {
+                thread::sleep(Duration::from_millis(1));
+            }
+            let step = shared.take_pools(&one_chair, &params, &cancel, None, &mut second_held, &mut second_logged);
+            (step, first_party.join().unwrap())
+        });
+        assert!(second_held.is_empty(), "the party closing the cycle gives its chair back");
+        let events = run_sequences(&shared.seat_lock.into_inner().unwrap());
+        (step, first_chairs, events)
+    }
+
+    #[test]
+    fn backing_off_breaks_a_circular_wait_on_chairs() {
+        let (step, first_chairs, events) = circular_wait("BACK_OFF");
+        assert!(matches!(step, Step::Retry));
+        assert_eq!(first_chairs, 2);
+        assert_eq!(events[&2], ["WAITING@0", "DEADLOCK_DETECTED@0"]);
+    }
+
+    #[test]
+    fn aborting_breaks_a_circular_wait_on_chairs() {
+        let (step, first_chairs, events) = circular_wait("ABORT");
+        assert!(matches!(step, Step::Gone));
+        assert_eq!(first_chairs, 2);
+        assert_eq!(events[&2], ["WAITING@0", "DEADLOCK_DETECTED@0", "ERROR@0"]);
+    }
+}
@@ -0,0 +1,131 @@
+use super::{free_bar_run, pools_available, seat_capacity, try_allocate, try_allocate_best_fit, SeatMask, SushiResources};
+use crate::errors::{AppError, Result};
+use crate::models::CustomerConfig;
+use std::cmp::Reverse;
+
+// Seating policy of a run: which seats a party gets, and in which order waiting parties
+// are offered seats as they free up. Chosen per run with SimulationConfig::strategy so
+// policies can be compared on the same roster.
+pub(crate) trait AllocationStrategy: Sync {
+    fn name(&self) -> &'static str;
+
+    // Seats for the party among those `open` leaves free, or None to keep it waiting
+    fn choose_seats(&self, res: &SushiResources, open: &SeatMask, customer: &CustomerConfig) -> Option<Vec<String>>;
+
+    // Reorder the waiting parties (indices into customers, in arrival order)
+    fn order_waiting(&self, _waiting: &mut [usize], _customers: &[CustomerConfig]) {}
+
+    // Never seat a party while an earlier arrival is still waiting
+    fn strict_queue(&self) -> bool {
+        false
+    }
+
+    // Queue disciplines need an engine that keeps an explicit waiting list
+    fn needs_ordered_queue(&self) -> bool {
+        false
+    }
+}
+
+// The engine's built-in heuristic (try_allocate)
+pub(crate) struct DefaultStrategy;
+
+impl AllocationStrategy for DefaultStrategy {
+    fn name(&self) -> &'static str { "DEFAULT" }
+
+    fn choose_seats(&self, res: &SushiResources, open: &SeatMask, customer: &CustomerConfig) -> Option<Vec<String>> {
+        try_allocate(res, open, customer)
+    }
+}
+
+// First free place in seat config order that fits the party: a table big enough or a
+// run of adjacent free bar seats. Otherwise the default heuristic's seating rules hold,
+// so wheelchair users get its accessible table and bar runs never cross groups.
+pub(crate) struct FirstFit;
+
+impl AllocationStrategy for FirstFit {
+    fn name(&self) -> &'static str { "FIRST_FIT" }
+
+    fn choose_seats(&self, res: &SushiResources, open: &SeatMask, customer: &CustomerConfig) -> Option<Vec<String>> {
+        if customer.wheelchair_count > 0 {
+            return try_allocate(res, open, customer);
+        }
+        if !pools_available(res, customer) {
+            return None;
+        }
+        let party = customer.party_size.max(1);
+        let position = |id: &str| res.seats.iter().position(|s| s.config.id == id);
+        let table = res.seats.iter()
+            .find(|s| s.config.type_ != "SINGLE" && open.is_free(s) && seat_capacity(&s.config.type_) >= party)
+            .map(|s| vec![s.config.id.clone()]);
+        let bar = free_bar_run(res, open, party as usize);
+        match (table, bar) {
+            (Some(table), Some(bar)) if position(&bar[0]) < position(&table[0]) => Some(bar),
+            (table, bar) => table.or(bar),
+        }
+    }
+}
+
+// Smallest place that fits (try_allocate_best_fit)
+pub(crate) struct BestFit;
+
+impl AllocationStrategy for BestFit {
+    fn name(&self) -> &'static str { "BEST_FIT" }
+
+    fn choose_seats(&self, res: &SushiResources, open: &SeatMask, customer: &CustomerConfig) -> Option<Vec<String>> {
+        try_allocate_best_fit(res, open, customer)
+    }
+}
+
+// Freed seats go to the largest waiting party first, arrival order among equals
+pub(crate) struct LargestPartyFirst;
+
+impl AllocationStrategy for LargestPartyFirst {
+    fn name(&self) -> &'static str { "LARGEST_PARTY_FIRST" }
+
+    fn choose_seats(&self, res: &SushiResources, open: &SeatMask, customer: &CustomerConfig) -> Option<Vec<String>> {
+        try_allocate(res, open, customer)
+    }
+
+    fn order_waiting(&self, waiting: &mut [usize], customers: &[CustomerConfig]) {
+        waiting.sort_by_key(|&i| Reverse(customers[i].party_size));
+    }
+
+    fn needs_ordered_queue(&self) -> bool {
+        true
+    }
+}
+
+// Strict first come, first served: the head of the queue blocks everyone behind it,
+// even parties that would fit a free seat
+pub(crate) struct FifoStrict;
+
+impl AllocationStrategy for FifoStrict {
+    fn name(&self) -> &'static str { "FIFO_STRICT" }
+
+    fn choose_seats(&self, res: &SushiResources, open: &SeatMask, customer: &CustomerConfig) -> Option<Vec<String>> {
+        try_allocate(res, open, customer)
+    }
+
+    fn strict_queue(&self) -> bool {
+        true
+    }
+
+    fn needs_ordered_queue(&self) -> bool {
+        true
+    }
+}
+
+pub(crate) const ALL: [&dyn AllocationStrategy; 5] = [&DefaultStrategy, &FirstFit, &BestFit, &LargestPartyFirst, &FifoStrict];
+
+pub(crate) fn from_name(name: &str) -> Result<&'static dyn AllocationStrategy> {
+    let wanted = name.trim().to_uppercase().replace('-', "_");
+    let wanted = match wanted.as_str() {
+        "FIRSTFIT" => "FIRST_FIT",
+        "BESTFIT" => "BEST_FIT",
+        "FIFO" => "FIFO_STRICT",
+        other => other,
+    };
+    ALL.into_iter()
+        .find(|s| s.name() == wanted)
+        .ok_or_else(|| AppError::SimulationError(format!("Unknown allocation strategy: {}", name)))
+}
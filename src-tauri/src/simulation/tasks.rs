@@ -0,0 +1,139 @@
+use super::party::{Turn, Waiter};
+use super::{initial_resources, EngineParams, SushiResources, CANCEL_POLL_MS, REALTIME_MS_PER_MINUTE};
+use crate::errors::{AppError, Result};
+use crate::instrumentation::{record_lock, record_notify, record_wait, record_wake};
+use crate::models::{CustomerConfig, SeatConfig};
+use std::pin::pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, MutexGuard, Notify};
+
+// Worker threads of the runtime a tasks run gets; the tasks spend nearly all their time
+// waiting, so a few threads carry thousands of parties
+const WORKER_THREADS: usize = 4;
+
+// Monitor engine on async tasks: the same arrive / wait / dine / leave steps as the
+// thread engine (see party.rs), with one tokio task per party instead of one OS thread,
+// a tokio Mutex for the monitor and a Notify in place of the condition variable. Locks,
+// waits and notifications are instrumented and traced like the thread engine's.
+struct Shared {
+    state: Mutex<SushiResources>,
+    changed: Notify,
+}
+
+impl Shared {
+    async fn lock(&self, family_id: u32) -> MutexGuard<'_, SushiResources> {
+        let started = Instant::now();
+        let mut res = self.state.lock().await;
+        record_lock(&mut *res, family_id, started.elapsed());
+        res
+    }
+
+    // Release the monitor and wait for a change (or the poll timeout), like Condvar::wait_timeout.
+    // The waiter registers before the lock goes, so a notify in between isn't lost.
+    async fn wait<'a>(&'a self, mut res: MutexGuard<'a, SushiResources>, family_id: u32) -> (MutexGuard<'a, SushiResources>, bool) {
+        let mut notified = pin!(self.changed.notified());
+        notified.as_mut().enable();
+        record_wait(&mut *res, family_id);
+        drop(res);
+        let timed_out = tokio::time::timeout(Duration::from_millis(CANCEL_POLL_MS), notified).await.is_err();
+        let mut res = self.state.lock().await;
+        record_wake(&mut *res, family_id, timed_out);
+        (res, timed_out)
+    }
+
+    fn notify(&self, res: &mut SushiResources, family_id: u32) {
+        record_notify(res, family_id);
+        self.changed.notify_waiters();
+    }
+}
+
+// Wait out a pause without holding a worker thread; returns how long that was
+async fn pass_gate(params: &EngineParams, cancel: &AtomicBool) -> Duration {
+    let start = Instant::now();
+    while params.gate.as_ref().is_some_and(|g| g.is_paused()) && !cancel.load(Ordering::SeqCst) {
+        tokio::time::sleep(Duration::from_millis(CANCEL_POLL_MS)).await;
+    }
+    start.elapsed()
+}
+
+// Dining time in wall-clock time; paused time doesn't count
+async fn sleep_unless_cancelled(duration: Duration, params: &EngineParams, cancel: &AtomicBool) {
+    let mut deadline = Instant::now() + duration;
+    loop {
+        if cancel.load(Ordering::SeqCst) { return; }
+        deadline += pass_gate(params, cancel).await;
+        let now = Instant::now();
+        if now >= deadline { return; }
+        tokio::time::sleep(std::cmp::min(deadline - now, Duration::from_millis(CANCEL_POLL_MS))).await;
+    }
+}
+
+async fn party(shared: Arc<Shared>, customer: CustomerConfig, params: EngineParams, cancel: Arc<AtomicBool>) {
+    let family_id = customer.family_id;
+    // 1. Arrive
+    pass_gate(&params, &cancel).await;
+    let mut waiter = Waiter::arrive(&mut *shared.lock(family_id).await, customer);
+
+    // 2. Wait & allocate
+    let mut res = shared.lock(family_id).await;
+    let (seat_ids, dining_time) = loop {
+        if params.gate.as_ref().is_some_and(|g| g.is_paused()) {
+            drop(res);
+            pass_gate(&params, &cancel).await;
+            res = shared.lock(family_id).await;
+        }
+
+        match waiter.attempt(&mut res, &params, &cancel) {
+            Turn::Seated(seat_ids, dining_time) => break (seat_ids, dining_time),
+            Turn::Gone => {
+                shared.notify(&mut res, family_id);
+                return;
+            }
+            Turn::Wait => {
+                let (guard, timed_out) = shared.wait(res, family_id).await;
+                res = guard;
+                waiter.woke(timed_out);
+            }
+        }
+    };
+
+    // 3. Dining, with the monitor released
+    drop(res);
+    sleep_unless_cancelled(Duration::from_millis(dining_time * REALTIME_MS_PER_MINUTE), &params, &cancel).await;
+
+    // 4. Leave
+    let mut res = shared.lock(family_id).await;
+    waiter.leave(&mut res, &seat_ids, dining_time);
+    shared.notify(&mut res, family_id);
+}
+
+pub(super) fn run(customers: &[CustomerConfig], seats_config: &[SeatConfig], params: &EngineParams, cancel: &Arc<AtomicBool>) -> Result<SushiResources> {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(WORKER_THREADS)
+        .enable_time()
+        .build()?;
+    let shared = Arc::new(Shared { state: Mutex::new(initial_resources(seats_config, params)), changed: Notify::new() });
+
+    // Driven from a thread of its own: the caller may itself be on an async runtime
+    // (a Tauri async command), where blocking on another one isn't allowed
+    std::thread::scope(|scope| {
+        scope.spawn(|| runtime.block_on(async {
+            let tasks: Vec<_> = customers.iter().cloned()
+                .map(|customer| tokio::spawn(party(Arc::clone(&shared), customer, params.clone(), Arc::clone(cancel))))
+                .collect();
+            for task in tasks {
+                let _ = task.await;
+            }
+        })).join()
+    }).map_err(|_| AppError::SimulationError("The tasks engine stopped unexpectedly".to_string()))?;
+
+    if cancel.load(Ordering::SeqCst) {
+        return Err(AppError::SimulationError("Simulation cancelled by application shutdown".to_string()));
+    }
+
+    let shared = Arc::try_unwrap(shared)
+        .map_err(|_| AppError::SimulationError("Tasks still hold the monitor".to_string()))?;
+    Ok(shared.state.into_inner())
+}
@@ -0,0 +1,116 @@
+use crate::models::{CustomerConfig, SelfServiceStation, StationOccupancy, StationReport, StationVisit};
+
+// Self-service stations (tea, soup, ...) seated parties walk up to during their meal.
+// Each visiting party sends one trip per station (one per visiting guest for per-guest
+// stations like the restroom), spread over its stay; a trip that finds every slot busy
+// queues, and the time spent queueing stretches the party's stay.
+#[derive(Clone)]
+pub struct StationModel {
+    stations: Vec<SelfServiceStation>,
+    busy: Vec<Vec<Vec<(u64, u64)>>>, // per station, per slot: booked [start, end) trips
+    visits: Vec<StationVisit>,
+}
+
+// Deterministic stand-in for "does this party (guest) use this station": spreads family
+// ids evenly over [0, 1) so runs stay reproducible
+fn draw(family_id: u32, station: usize, guest: u32) -> f64 {
+    let key = (family_id as u64) << 8 | guest as u64;
+    let mixed = (key ^ (station as u64).wrapping_mul(0x9E37_79B9)).wrapping_mul(2_654_435_761) % 1_000;
+    mixed as f64 / 1_000.0
+}
+
+// Earliest start at or after `from` when the slot is free for `length` minutes
+fn earliest_gap(slot: &[(u64, u64)], from: u64, length: u64) -> u64 {
+    let mut start = from;
+    for &(busy_start, busy_end) in slot {
+        if busy_end <= start { continue; }
+        if busy_start >= start + length { break; }
+        start = busy_end;
+    }
+    start
+}
+
+// Book `length` minutes from `from` on whichever slot frees up first and return the
+// start; an empty slot list books nothing and starts at once
+pub(crate) fn book_slot(slots: &mut [Vec<(u64, u64)>], from: u64, length: u64) -> u64 {
+    let Some((slot, start)) = slots.iter().enumerate()
+        .map(|(slot, booked)| (slot, earliest_gap(booked, from, length)))
+        .min_by_key(|&(_, start)| start) else { return from };
+    let booked = &mut slots[slot];
+    let at = booked.partition_point(|&(s, _)| s < start);
+    booked.insert(at, (start, start + length));
+    start
+}
+
+impl StationModel {
+    pub fn new(stations: Vec<SelfServiceStation>) -> Self {
+        let busy = stations.iter().map(|s| vec![Vec::new(); s.capacity.max(1) as usize]).collect();
+        StationModel { stations, busy, visits: Vec::new() }
+    }
+
+    // Book the party's trips for a stay starting at `sit_time`; returns the minutes the
+    // party spends queueing, which are added to its stay
+    pub fn visit(&mut self, sit_time: u64, customer: &CustomerConfig) -> u64 {
+        let count = self.stations.len() as u64;
+        let mut queued = 0;
+        for i in 0..self.stations.len() {
+            let station = &self.stations[i];
+            let guests = if station.per_guest { customer.party_size.max(1) } else { 1 };
+            let (share, length) = (station.visit_share, station.visit_time.max(1));
+            for guest in 0..guests {
+                if draw(customer.family_id, i, guest) >= share { continue; }
+                // Trips are spread over the stay, e.g. tea early and soup later;
+                // a party's guests go one after another
+                let arrive = sit_time + customer.est_dining_time * (i as u64 + 1) / (count + 1) + guest as u64 * length + queued;
+                queued += self.book(i, customer.family_id, arrive, length);
+            }
+        }
+        queued
+    }
+
+    // Book one trip arriving at `arrive` in the first slot free for it; returns the queue wait
+    fn book(&mut self, i: usize, family_id: u32, arrive: u64, length: u64) -> u64 {
+        let start = book_slot(&mut self.busy[i], arrive, length);
+        self.visits.push(StationVisit {
+            station: self.stations[i].name.clone(),
+            family_id,
+            arrive,
+            start,
+            end: start + length,
+        });
+        start - arrive
+    }
+
+    // Who is at and queueing for each station at time t
+    pub fn occupancy(&self, t: u64) -> Vec<StationOccupancy> {
+        self.stations.iter().map(|s| {
+            let here = self.visits.iter().filter(|v| v.station == s.name);
+            let (mut in_use, mut queued) = (0, 0);
+            for v in here {
+                if v.start <= t && t < v.end { in_use += 1; }
+                if v.arrive <= t && t < v.start { queued += 1; }
+            }
+            StationOccupancy { name: s.name.clone(), in_use, queued }
+        }).collect()
+    }
+
+    // Per-station usage over a run lasting `duration` minutes
+    pub fn report(&self, duration: u64) -> Vec<StationReport> {
+        self.stations.iter().map(|s| {
+            let visits: Vec<&StationVisit> = self.visits.iter().filter(|v| v.station == s.name).collect();
+            let waits: Vec<u64> = visits.iter().map(|v| v.start - v.arrive).collect();
+            let busy_minutes: u64 = visits.iter().map(|v| v.end - v.start).sum();
+            let capacity_minutes = s.capacity.max(1) as u64 * duration;
+            StationReport {
+                name: s.name.clone(),
+                capacity: s.capacity.max(1),
+                visits: visits.len(),
+                queued_visits: waits.iter().filter(|&&w| w > 0).count(),
+                total_queue_wait: waits.iter().sum(),
+                max_queue_wait: waits.iter().copied().max().unwrap_or(0),
+                busy_minutes,
+                utilization_pct: if capacity_minutes == 0 { 0.0 } else { busy_minutes as f64 * 100.0 / capacity_minutes as f64 },
+            }
+        }).collect()
+    }
+}
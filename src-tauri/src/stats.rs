@@ -0,0 +1,504 @@
+use crate::access;
+use crate::models::{CustomerConfig, AbandonmentCostModel, AbandonmentCostSummary, SimulationFrame, KpiSummary, ConveyorPreferenceStats, OrderAheadStats, SeatClassWait, FamilyStarvation, StarvationReport, DayReport, OperationsStatus, ServiceTarget, SlaResult, SlaReport, FamilyWait, SeatTypeUtilization, SimulationReport};
+use crate::ledger::{BABY_CHAIR_POOL, WHEELCHAIR_POOL};
+use crate::simulation::{SeatClass, INFEASIBLE_EVENT};
+use crate::errors::{AppError, Result};
+use crate::session::{SessionRun, SessionStore};
+use std::collections::HashMap;
+use tauri::{State, Window};
+
+// Event type of a party that left without being served
+pub const RENEGE_EVENT: &str = "LEAVE_UNSERVED";
+
+fn count_events(frames: &[SimulationFrame], event_type: &str) -> usize {
+    frames.iter()
+        .flat_map(|f| f.events.iter())
+        .filter(|e| e.type_ == event_type)
+        .count()
+}
+
+// Collapse reneges and turned away parties into one composite cost figure
+pub fn abandonment_costs(frames: &[SimulationFrame], model: &AbandonmentCostModel) -> AbandonmentCostSummary {
+    let reneged = count_events(frames, RENEGE_EVENT);
+    let turned_away = count_events(frames, INFEASIBLE_EVENT);
+
+    let renege_cost_total = reneged as f64 * model.renege_cost;
+    let turned_away_cost_total = turned_away as f64 * model.turned_away_cost;
+
+    AbandonmentCostSummary {
+        reneged,
+        turned_away,
+        renege_cost_total,
+        turned_away_cost_total,
+        total_cost: renege_cost_total + turned_away_cost_total,
+    }
+}
+
+// Attach a cost model to a session; later stats for that session use it
+#[tauri::command]
+pub fn set_cost_model(session_id: Option<String>, cost_model: AbandonmentCostModel, window: Window, sessions: State<'_, SessionStore>) -> Result<AbandonmentCostSummary> {
+    access::require_host(&window, "set the cost model")?;
+    let session_id = sessions.resolve(session_id, &window);
+    sessions.with_session(&session_id, |run| {
+        run.cost_model = cost_model;
+        abandonment_costs(&run.frames, &run.cost_model)
+    })
+}
+
+#[tauri::command]
+pub fn get_abandonment_costs(session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<AbandonmentCostSummary> {
+    let session_id = sessions.resolve(session_id, &window);
+    sessions.with_session(&session_id, |run| abandonment_costs(&run.frames, &run.cost_model))
+}
+
+// Headline numbers for the dashboard, computed from the run's event log
+pub fn kpis(run: &SessionRun) -> KpiSummary {
+    let mut arrivals: HashMap<u32, u64> = HashMap::new();
+    let mut seated: HashMap<u32, u64> = HashMap::new();
+    let mut seatings = 0usize;
+
+    for e in run.frames.iter().flat_map(|f| f.events.iter()) {
+        match e.type_.as_str() {
+            "ARRIVAL" => { arrivals.entry(e.family_id).or_insert(e.timestamp); }
+            "SEATED" => {
+                seated.entry(e.family_id).or_insert(e.timestamp);
+                seatings += e.seat_id.as_deref().map(|ids| ids.split(',').count()).unwrap_or(0);
+            }
+            _ => {}
+        }
+    }
+
+    let waits: Vec<u64> = seated.iter()
+        .map(|(fid, t)| t.saturating_sub(arrivals.get(fid).copied().unwrap_or(*t)))
+        .collect();
+
+    let covers_served = run.parties()
+        .filter(|c| seated.contains_key(&c.family_id))
+        .map(|c| c.party_size)
+        .sum();
+
+    let seat_count = run.seats_config.len();
+    let peak_occupied = run.frames.iter()
+        .map(|f| f.seats.iter().filter(|s| s.occupied_by.is_some()).count())
+        .max()
+        .unwrap_or(0);
+
+    let arrived = arrivals.len();
+
+    KpiSummary {
+        covers_served,
+        families_served: seated.len(),
+        avg_wait: if waits.is_empty() { 0.0 } else { waits.iter().sum::<u64>() as f64 / waits.len() as f64 },
+        table_turns_per_seat: if seat_count == 0 { 0.0 } else { seatings as f64 / seat_count as f64 },
+        peak_occupancy_pct: if seat_count == 0 { 0.0 } else { peak_occupied as f64 * 100.0 / seat_count as f64 },
+        rejection_rate: if arrived == 0 { 0.0 } else { arrived.saturating_sub(seated.len()) as f64 / arrived as f64 },
+        total_cost: abandonment_costs(&run.frames, &run.cost_model).total_cost,
+        starved_families: starvation_report(run).starved_families,
+        fingerprint: run.metadata.fingerprint.clone(),
+    }
+}
+
+#[tauri::command]
+pub fn get_kpis(session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<KpiSummary> {
+    let session_id = sessions.resolve(session_id, &window);
+    sessions.with_session(&session_id, |run| kpis(run))
+}
+
+// Compare waits of conveyor-minded parties with everyone else
+pub fn conveyor_preference_stats(run: &SessionRun) -> ConveyorPreferenceStats {
+    let mut arrivals: HashMap<u32, u64> = HashMap::new();
+    let mut seated: HashMap<u32, (u64, String)> = HashMap::new();
+    for e in run.frames.iter().flat_map(|f| f.events.iter()) {
+        match e.type_.as_str() {
+            "ARRIVAL" => { arrivals.entry(e.family_id).or_insert(e.timestamp); }
+            "SEATED" => { seated.entry(e.family_id).or_insert((e.timestamp, e.seat_id.clone().unwrap_or_default())); }
+            _ => {}
+        }
+    }
+
+    let on_conveyor = |ids: &str| !ids.is_empty() && ids.split(',')
+        .all(|id| run.seats_config.iter().any(|s| s.id == id.trim() && s.faces_conveyor));
+    let avg = |waits: &[u64]| if waits.is_empty() { 0.0 } else { waits.iter().sum::<u64>() as f64 / waits.len() as f64 };
+
+    let mut preferring_waits = Vec::new();
+    let mut other_waits = Vec::new();
+    let mut preferring_families = 0;
+    let mut preferring_got_conveyor_seat = 0;
+
+    for c in &run.customers {
+        let prefers = c.conveyor_preference > 0.0;
+        if prefers { preferring_families += 1; }
+        if let Some((t, ids)) = seated.get(&c.family_id) {
+            let wait = t.saturating_sub(arrivals.get(&c.family_id).copied().unwrap_or(*t));
+            if prefers {
+                preferring_waits.push(wait);
+                if on_conveyor(ids) { preferring_got_conveyor_seat += 1; }
+            } else {
+                other_waits.push(wait);
+            }
+        }
+    }
+
+    ConveyorPreferenceStats {
+        preferring_families,
+        preferring_got_conveyor_seat,
+        preferring_avg_wait: avg(&preferring_waits),
+        other_avg_wait: avg(&other_waits),
+    }
+}
+
+#[tauri::command]
+pub fn get_conveyor_preference_stats(session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<ConveyorPreferenceStats> {
+    let session_id = sessions.resolve(session_id, &window);
+    sessions.with_session(&session_id, |run| conveyor_preference_stats(run))
+}
+
+// Seat occupancy (SEATED -> LEFT) per family
+fn occupancy_times(run: &SessionRun) -> HashMap<u32, u64> {
+    let mut seated: HashMap<u32, u64> = HashMap::new();
+    let mut occupancy = HashMap::new();
+    for e in run.frames.iter().flat_map(|f| f.events.iter()) {
+        match e.type_.as_str() {
+            "SEATED" => { seated.entry(e.family_id).or_insert(e.timestamp); }
+            "LEFT" => {
+                if let Some(t) = seated.get(&e.family_id) {
+                    occupancy.insert(e.family_id, e.timestamp.saturating_sub(*t));
+                }
+            }
+            _ => {}
+        }
+    }
+    occupancy
+}
+
+// Turnover of order-ahead parties vs. walk-in orders
+pub fn order_ahead_stats(run: &SessionRun) -> OrderAheadStats {
+    let occupancy = occupancy_times(run);
+    let (ahead, walk_in): (Vec<&CustomerConfig>, Vec<&CustomerConfig>) = run.customers.iter()
+        .partition(|c| c.ordered_ahead);
+
+    let avg_occupancy = |group: &[&CustomerConfig]| {
+        let times: Vec<u64> = group.iter().filter_map(|c| occupancy.get(&c.family_id).copied()).collect();
+        if times.is_empty() { 0.0 } else { times.iter().sum::<u64>() as f64 / times.len() as f64 }
+    };
+    // Scenario times are in minutes, so a seat turns 60 / occupancy times per hour
+    let turns_per_hour = |avg: f64| if avg > 0.0 { 60.0 / avg } else { 0.0 };
+
+    let ordered_ahead_avg_occupancy = avg_occupancy(&ahead);
+    let walk_in_avg_occupancy = avg_occupancy(&walk_in);
+
+    OrderAheadStats {
+        ordered_ahead_parties: ahead.len(),
+        walk_in_parties: walk_in.len(),
+        ordered_ahead_avg_occupancy,
+        walk_in_avg_occupancy,
+        ordered_ahead_turns_per_hour: turns_per_hour(ordered_ahead_avg_occupancy),
+        walk_in_turns_per_hour: turns_per_hour(walk_in_avg_occupancy),
+    }
+}
+
+#[tauri::command]
+pub fn get_order_ahead_stats(session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<OrderAheadStats> {
+    let session_id = sessions.resolve(session_id, &window);
+    sessions.with_session(&session_id, |run| order_ahead_stats(run))
+}
+
+// Wait per seat class, so the separate lines can be compared directly
+pub fn wait_by_seat_class(run: &SessionRun) -> Vec<SeatClassWait> {
+    let mut arrivals: HashMap<u32, u64> = HashMap::new();
+    let mut seated: HashMap<u32, u64> = HashMap::new();
+    for e in run.frames.iter().flat_map(|f| f.events.iter()) {
+        match e.type_.as_str() {
+            "ARRIVAL" => { arrivals.entry(e.family_id).or_insert(e.timestamp); }
+            "SEATED" => { seated.entry(e.family_id).or_insert(e.timestamp); }
+            _ => {}
+        }
+    }
+
+    SeatClass::ALL.iter().map(|class| {
+        let waits: Vec<u64> = run.customers.iter()
+            .filter(|c| SeatClass::for_customer(c) == *class)
+            .filter_map(|c| {
+                let sat = seated.get(&c.family_id)?;
+                Some(sat.saturating_sub(arrivals.get(&c.family_id).copied().unwrap_or(*sat)))
+            })
+            .collect();
+
+        SeatClassWait {
+            seat_class: class.name().to_string(),
+            families: waits.len(),
+            avg_wait: if waits.is_empty() { 0.0 } else { waits.iter().sum::<u64>() as f64 / waits.len() as f64 },
+            max_wait: waits.iter().copied().max().unwrap_or(0),
+            peak_queue_length: run.frames.iter().map(|f| class.queue(&f.sub_queues).len()).max().unwrap_or(0),
+        }
+    }).collect()
+}
+
+#[tauri::command]
+pub fn get_wait_by_seat_class(session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<Vec<SeatClassWait>> {
+    let session_id = sessions.resolve(session_id, &window);
+    sessions.with_session(&session_id, |run| wait_by_seat_class(run))
+}
+
+// How many families to call out as worst affected
+const WORST_STARVED: usize = 3;
+
+// Families passed over while waiting: each time a smaller family that arrived later
+// got a seat first, the waiting family's own attempt at that moment failed
+pub fn starvation_report(run: &SessionRun) -> StarvationReport {
+    let mut seatings: Vec<(u64, usize, u32)> = run.frames.iter()
+        .flat_map(|f| f.events.iter())
+        .filter(|e| e.type_ == "SEATED")
+        .map(|e| (e.timestamp, e.sequence, e.family_id))
+        .collect();
+    seatings.sort();
+    let seated_at: HashMap<u32, (u64, usize)> = seatings.iter().map(|&(t, seq, fid)| (fid, (t, seq))).collect();
+
+    let mut families: Vec<FamilyStarvation> = run.parties().map(|waiting| {
+        let until = seated_at.get(&waiting.family_id).copied();
+        let overtaken_by = seatings.iter()
+            .filter(|&&(t, seq, _)| t >= waiting.arrival_time && until.is_none_or(|u| (t, seq) < u))
+            .filter_map(|&(_, _, fid)| run.parties().find(|c| c.family_id == fid))
+            .filter(|c| c.arrival_time > waiting.arrival_time && c.party_size < waiting.party_size)
+            .map(|c| c.family_id)
+            .collect();
+        FamilyStarvation {
+            family_id: waiting.family_id,
+            party_size: waiting.party_size,
+            arrival_time: waiting.arrival_time,
+            wait: until.map(|(t, _)| t.saturating_sub(waiting.arrival_time)),
+            overtaken_by,
+        }
+    }).collect();
+    families.sort_by(|a, b| b.overtaken_by.len().cmp(&a.overtaken_by.len()).then(a.family_id.cmp(&b.family_id)));
+
+    StarvationReport {
+        objective: run.metadata.objective.clone(),
+        starved_families: families.iter().filter(|f| !f.overtaken_by.is_empty()).count(),
+        worst_families: families.iter()
+            .filter(|f| !f.overtaken_by.is_empty())
+            .take(WORST_STARVED)
+            .map(|f| f.family_id)
+            .collect(),
+        families,
+    }
+}
+
+#[tauri::command]
+pub fn get_starvation_report(session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<StarvationReport> {
+    let session_id = sessions.resolve(session_id, &window);
+    sessions.with_session(&session_id, |run| starvation_report(run))
+}
+
+// End-of-day report for a closed operations shift: the simulation statistics over the
+// shift's event log plus what was still open when the host closed
+pub fn day_report(run: &SessionRun, status: &OperationsStatus) -> DayReport {
+    let kpis = kpis(run);
+    let starvation = starvation_report(run);
+    let unseated_family_ids: Vec<u32> = status.waiting.iter().map(|p| p.family_id).collect();
+    let still_dining_family_ids: Vec<u32> = status.seated.iter().map(|p| p.family_id).collect();
+    let no_show_reservation_ids: Vec<u32> = status.upcoming_reservations.iter().map(|r| r.family_id).collect();
+
+    let mut summary = vec![
+        format!("Shift of {}h{:02}: {} parties ({} covers) seated, average wait {:.1} min.",
+            status.now / 60, status.now % 60, kpis.families_served, kpis.covers_served, kpis.avg_wait),
+        format!("Peak occupancy {:.0}%, {:.2} turns per seat, revenue {:.2}.",
+            kpis.peak_occupancy_pct, kpis.table_turns_per_seat, run.metadata.total_revenue),
+    ];
+    if !unseated_family_ids.is_empty() {
+        summary.push(format!("{} parties were still waiting at close.", unseated_family_ids.len()));
+    }
+    if !still_dining_family_ids.is_empty() {
+        summary.push(format!("{} parties had no departure recorded.", still_dining_family_ids.len()));
+    }
+    if !no_show_reservation_ids.is_empty() {
+        summary.push(format!("{} reservations never checked in.", no_show_reservation_ids.len()));
+    }
+    if starvation.starved_families > 0 {
+        summary.push(format!("{} parties were overtaken by smaller parties that arrived later.", starvation.starved_families));
+    }
+
+    DayReport {
+        session_id: status.session_id.clone(),
+        shift_minutes: status.now,
+        wait_by_seat_class: wait_by_seat_class(run),
+        total_revenue: run.metadata.total_revenue,
+        kpis,
+        starvation,
+        unseated_family_ids,
+        still_dining_family_ids,
+        no_show_reservation_ids,
+        summary: summary.join(" "),
+    }
+}
+
+#[tauri::command]
+pub fn get_day_report(session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<DayReport> {
+    let session_id = sessions.resolve(session_id, &window);
+    sessions.with_session(&session_id, |run| run.day_report.clone())?
+        .ok_or_else(|| AppError::SessionError("Session has no end-of-day report; it is written when an operations shift closes".to_string()))
+}
+
+fn target_applies(target: &ServiceTarget, customer: &CustomerConfig) -> Result<bool> {
+    let Some(parties) = target.parties.as_deref() else { return Ok(true) };
+    match parties.to_uppercase().as_str() {
+        "ALL" => Ok(true),
+        "WHEELCHAIR" => Ok(customer.wheelchair_count > 0),
+        "BABY" => Ok(customer.baby_chair_count > 0),
+        class => SeatClass::ALL.iter()
+            .find(|c| c.name() == class)
+            .map(|c| SeatClass::for_customer(customer) == *c)
+            .ok_or_else(|| AppError::ValidationError(format!("Unknown SLA party group: {}", parties))),
+    }
+}
+
+fn target_name(target: &ServiceTarget) -> String {
+    target.name.clone().unwrap_or_else(|| format!("{}% of {} parties seated within {} min",
+        target.percentile.unwrap_or(100.0), target.parties.as_deref().unwrap_or("all").to_lowercase(), target.max_wait))
+}
+
+// Check service targets against first seating times; parties never seated count as late.
+// Shared by check_slas (one stored run) and Monte Carlo batches.
+pub fn evaluate_slas(customers: &[CustomerConfig], sit_times: &HashMap<u32, u64>, targets: &[ServiceTarget]) -> Result<Vec<SlaResult>> {
+    targets.iter().map(|target| {
+        let percentile = target.percentile.unwrap_or(100.0);
+        if !(0.0..=100.0).contains(&percentile) {
+            return Err(AppError::ValidationError(format!("SLA percentile {} is outside 0-100", percentile)));
+        }
+
+        let mut waits: Vec<(Option<u64>, u32)> = Vec::new();
+        for c in customers {
+            if target_applies(target, c)? {
+                waits.push((sit_times.get(&c.family_id).map(|t| t.saturating_sub(c.arrival_time)), c.family_id));
+            }
+        }
+        // Unseated last: None sorts below Some, so order on (seated?, wait)
+        waits.sort_by_key(|(wait, fid)| (wait.is_none(), wait.unwrap_or(0), *fid));
+
+        let within = waits.iter().filter(|(wait, _)| wait.is_some_and(|w| w <= target.max_wait)).count();
+        let share_within = if waits.is_empty() { 100.0 } else { within as f64 * 100.0 / waits.len() as f64 };
+        // Nearest-rank percentile
+        let rank = ((percentile / 100.0 * waits.len() as f64).ceil() as usize).clamp(1, waits.len().max(1));
+        let percentile_wait = waits.get(rank - 1).and_then(|(wait, _)| *wait);
+
+        Ok(SlaResult {
+            name: target_name(target),
+            passed: share_within >= percentile,
+            families: waits.len(),
+            share_within,
+            percentile_wait,
+            violating_family_ids: waits.iter()
+                .filter(|(wait, _)| !wait.is_some_and(|w| w <= target.max_wait))
+                .map(|(_, fid)| *fid)
+                .collect(),
+        })
+    }).collect()
+}
+
+// Pass/fail of each service target for the session's run
+#[tauri::command]
+pub fn check_slas(targets: Vec<ServiceTarget>, session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<SlaReport> {
+    let session_id = sessions.resolve(session_id, &window);
+    let results = sessions.with_session(&session_id, |run| {
+        let mut seated: HashMap<u32, u64> = HashMap::new();
+        for e in run.frames.iter().flat_map(|f| f.events.iter()).filter(|e| e.type_ == "SEATED") {
+            seated.entry(e.family_id).or_insert(e.timestamp);
+        }
+        evaluate_slas(&run.parties().cloned().collect::<Vec<_>>(), &seated, &targets)
+    })??;
+    Ok(SlaReport { passed: results.iter().all(|r| r.passed), results })
+}
+
+// Time-weighted share of a pool in use over [start, end], from the ledger's balances
+fn pool_utilization(run: &SessionRun, pool: &str, capacity: i32, start: u64, end: u64) -> f64 {
+    if capacity <= 0 || end <= start {
+        return 0.0;
+    }
+    let mut entries: Vec<_> = run.ledger.iter().filter(|e| e.resource == pool).collect();
+    entries.sort_by_key(|e| (e.time, e.sequence));
+
+    let (mut in_use, mut since, mut area) = (0u64, start, 0u64);
+    for e in entries {
+        let t = e.time.clamp(start, end);
+        area += in_use * (t - since);
+        in_use = (capacity - e.balance).max(0) as u64;
+        since = t;
+    }
+    area += in_use * (end - since);
+    area as f64 * 100.0 / (capacity as u64 * (end - start)) as f64
+}
+
+// Per-family waits, utilization of each seat type and of the chair pools, and how many
+// parties were served or lost
+pub fn simulation_report(run: &SessionRun) -> SimulationReport {
+    // family_id -> (seated at, seat ids)
+    let mut seated: HashMap<u32, (u64, String)> = HashMap::new();
+    let mut left: HashMap<u32, u64> = HashMap::new();
+    let mut reneged = std::collections::HashSet::new();
+    let mut infeasible = std::collections::HashSet::new();
+    for e in run.frames.iter().flat_map(|f| f.events.iter()) {
+        match e.type_.as_str() {
+            "SEATED" => { seated.entry(e.family_id).or_insert((e.timestamp, e.seat_id.clone().unwrap_or_default())); }
+            "LEFT" => { left.entry(e.family_id).or_insert(e.timestamp); }
+            RENEGE_EVENT => { reneged.insert(e.family_id); }
+            INFEASIBLE_EVENT => { infeasible.insert(e.family_id); }
+            _ => {}
+        }
+    }
+
+    let families: Vec<FamilyWait> = run.parties().map(|c| {
+        let seated_at = seated.get(&c.family_id).map(|(t, _)| *t);
+        FamilyWait {
+            family_id: c.family_id,
+            party_size: c.party_size,
+            arrival_time: c.arrival_time,
+            seated_at,
+            wait: seated_at.map(|t| t.saturating_sub(c.arrival_time)),
+        }
+    }).collect();
+    let waits: Vec<u64> = families.iter().filter_map(|f| f.wait).collect();
+
+    let start = run.frames.first().map(|f| f.timestamp).unwrap_or(0);
+    let end = run.frames.last().map(|f| f.timestamp).unwrap_or(0);
+    let duration = end.saturating_sub(start);
+
+    let mut seat_types: Vec<&str> = run.seats_config.iter().map(|s| s.type_.as_str()).collect();
+    seat_types.sort_unstable();
+    seat_types.dedup();
+    let seat_utilization = seat_types.into_iter().map(|seat_type| {
+        let ids: Vec<&str> = run.seats_config.iter().filter(|s| s.type_ == seat_type).map(|s| s.id.as_str()).collect();
+        let occupied_minutes: u64 = seated.iter()
+            .map(|(fid, (sat, seat_ids))| {
+                let here = seat_ids.split(',').filter(|id| ids.contains(&id.trim())).count() as u64;
+                here * left.get(fid).copied().unwrap_or(end).saturating_sub(*sat)
+            })
+            .sum();
+        SeatTypeUtilization {
+            seat_type: seat_type.to_string(),
+            seats: ids.len(),
+            occupied_minutes,
+            utilization_pct: if duration == 0 { 0.0 } else { occupied_minutes as f64 * 100.0 / (ids.len() as u64 * duration) as f64 },
+        }
+    }).collect();
+
+    SimulationReport {
+        duration,
+        avg_wait: if waits.is_empty() { 0.0 } else { waits.iter().sum::<u64>() as f64 / waits.len() as f64 },
+        max_wait: waits.iter().copied().max().unwrap_or(0),
+        seat_utilization,
+        baby_chair_utilization_pct: pool_utilization(run, BABY_CHAIR_POOL, run.params.baby_chairs, start, end),
+        wheelchair_utilization_pct: pool_utilization(run, WHEELCHAIR_POOL, run.params.wheelchairs, start, end),
+        customers_served: seated.len(),
+        customers_reneged: reneged.len(),
+        customers_infeasible: infeasible.len(),
+        customers_never_seated: families.iter().filter(|f| f.seated_at.is_none()).count(),
+        families,
+        stations: run.station_report.clone(),
+    }
+}
+
+#[tauri::command]
+pub fn get_simulation_stats(session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<SimulationReport> {
+    let session_id = sessions.resolve(session_id, &window);
+    sessions.with_session(&session_id, |run| simulation_report(run))
+}
@@ -0,0 +1,45 @@
+use crate::access;
+use crate::config::SimulationConfig;
+use crate::errors::Result;
+use crate::models::{StreamDone, StreamedFrame};
+use crate::session::SessionStore;
+use crate::simulation::{run_session, RunKind};
+use tauri::{Emitter, State, Window};
+
+// Events a streamed run is delivered on, to the window that started it
+pub const FRAME_EVENT: &str = "simulation://frame";
+pub const DONE_EVENT: &str = "simulation://done";
+
+// Run a simulation and deliver it as it happens: while the engine runs, its seats, queue
+// and pools go out on the live channel (simulation://live) on every engine, DES included;
+// the frames follow one event each as soon as they are built, instead of as one large
+// command result. The run is stored in the session like any other, and event subscribers
+// get its events as usual, so frames can be re-fetched later.
+#[tauri::command(async)]
+pub fn stream_simulation(config: SimulationConfig, session_id: Option<String>, window: Window, sessions: State<'_, SessionStore>) -> Result<StreamDone> {
+    access::require_host(&window, "run simulations")?;
+    let session_id = sessions.resolve(session_id, &window);
+    let label = window.label().to_string();
+
+    let frames = match run_session(config, session_id.clone(), &sessions, &window, RunKind::Streamed) {
+        Ok(frames) => frames,
+        Err(e) => {
+            let done = StreamDone { session_id, frame_count: 0, metadata: None, error: Some(e.to_string()) };
+            let _ = window.emit_to(label.as_str(), DONE_EVENT, done);
+            return Err(e);
+        }
+    };
+
+    let total = frames.len();
+    for (index, frame) in frames.into_iter().enumerate() {
+        // A window closed mid-stream stops the stream; the run stays in the session
+        if window.emit_to(label.as_str(), FRAME_EVENT, StreamedFrame { session_id: session_id.clone(), index, total, frame }).is_err() {
+            break;
+        }
+    }
+
+    let metadata = sessions.with_session(&session_id, |run| run.metadata.clone()).ok();
+    let done = StreamDone { session_id, frame_count: total, metadata, error: None };
+    let _ = window.emit_to(label.as_str(), DONE_EVENT, done.clone());
+    Ok(done)
+}
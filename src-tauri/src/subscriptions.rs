@@ -0,0 +1,97 @@
+use crate::errors::Result;
+use crate::models::{EventBatch, EventFilter, SimulationEvent, SimulationFrame};
+use crate::session::SessionStore;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{Emitter, State, Window};
+
+// Event name the filtered batches are emitted under
+pub const EVENT_STREAM: &str = "simulation-events";
+
+struct Subscription {
+    session_id: String,
+    window_label: String,
+    filter: EventFilter,
+}
+
+// Filtered event subscriptions, so each panel only receives the events it renders
+#[derive(Default)]
+pub struct SubscriptionStore {
+    subscriptions: Mutex<HashMap<u32, Subscription>>,
+    next_id: Mutex<u32>,
+}
+
+fn matches(filter: &EventFilter, event: &SimulationEvent) -> bool {
+    (filter.types.is_empty() || filter.types.iter().any(|t| t.eq_ignore_ascii_case(&event.type_)))
+        && (filter.family_ids.is_empty() || filter.family_ids.contains(&event.family_id))
+}
+
+fn filter_events(filter: &EventFilter, frames: &[SimulationFrame]) -> Vec<SimulationEvent> {
+    frames.iter()
+        .flat_map(|f| f.events.iter())
+        .filter(|e| matches(filter, e))
+        .cloned()
+        .collect()
+}
+
+impl SubscriptionStore {
+    // Send each subscriber of the session the events of a finished run that pass its filter
+    pub fn publish(&self, window: &Window, session_id: &str, frames: &[SimulationFrame]) {
+        let subscriptions = self.subscriptions.lock().unwrap();
+        for (&id, sub) in subscriptions.iter().filter(|(_, s)| s.session_id == session_id) {
+            let batch = EventBatch {
+                subscription_id: id,
+                session_id: session_id.to_string(),
+                events: filter_events(&sub.filter, frames),
+            };
+            // A window that closed mid-run just misses its batch
+            let _ = window.emit_to(sub.window_label.as_str(), EVENT_STREAM, batch);
+        }
+    }
+
+    // Drop the subscriptions of a closed window
+    pub fn drop_window(&self, label: &str) {
+        self.subscriptions.lock().unwrap().retain(|_, s| s.window_label != label);
+    }
+}
+
+// Subscribe the calling window to the session's events. With `replay`, events of a run
+// already stored in the session are sent right away.
+#[tauri::command]
+pub fn subscribe_events(
+    session_id: Option<String>,
+    filter: EventFilter,
+    replay: Option<bool>,
+    window: Window,
+    sessions: State<'_, SessionStore>,
+    subscriptions: State<'_, SubscriptionStore>,
+) -> Result<u32> {
+    let session_id = sessions.resolve(session_id, &window);
+
+    let id = {
+        let mut next = subscriptions.next_id.lock().unwrap();
+        *next += 1;
+        *next
+    };
+
+    if replay.unwrap_or(false) {
+        let events = sessions.with_session(&session_id, |run| filter_events(&filter, &run.frames))?;
+        let _ = window.emit_to(window.label(), EVENT_STREAM, EventBatch {
+            subscription_id: id,
+            session_id: session_id.clone(),
+            events,
+        });
+    }
+
+    subscriptions.subscriptions.lock().unwrap().insert(id, Subscription {
+        session_id,
+        window_label: window.label().to_string(),
+        filter,
+    });
+    Ok(id)
+}
+
+#[tauri::command]
+pub fn unsubscribe_events(subscription_id: u32, subscriptions: State<'_, SubscriptionStore>) -> bool {
+    subscriptions.subscriptions.lock().unwrap().remove(&subscription_id).is_some()
+}
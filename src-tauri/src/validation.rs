@@ -0,0 +1,66 @@
+use crate::diagnostics;
+use crate::errors::{AppError, Result};
+use crate::feasibility;
+use crate::models::{CustomerConfig, DiagnosticSeverity, FeasibilityIssue, SeatConfig};
+use crate::parser;
+
+// Inputs a run refuses to start with. The feasibility check only warns about demand a
+// sound layout can't meet; these make no sense at all. The layout gets the seat editor's
+// own checks, so the two never disagree about what a broken layout is. A party too large
+// for any seating it may use is only a warning: the run turns it away as INFEASIBLE.
+pub fn input_issues(customers: &[CustomerConfig], seats: &[SeatConfig], baby_chairs: i32, wheelchairs: i32, merge_tables: bool) -> Vec<FeasibilityIssue> {
+    let mut issues = Vec::new();
+    let mut report = |severity: DiagnosticSeverity, code: &str, family_ids: Vec<u32>, message: String| issues.push(FeasibilityIssue {
+        severity,
+        code: code.to_string(),
+        family_ids,
+        time: None,
+        message,
+    });
+
+    for (pool, count) in [("baby_chairs", baby_chairs), ("wheelchairs", wheelchairs)] {
+        if count < 0 {
+            report(DiagnosticSeverity::Error, "negative-count", Vec::new(), format!("{} is {}; a pool can't hold fewer than 0", pool, count));
+        }
+    }
+
+    // Its warnings (overlaps, no accessible table) don't stop a run
+    let layout = serde_json::to_string(seats).unwrap_or_default();
+    for d in diagnostics::validate_seat_layout_json(&layout).into_iter().filter(|d| d.severity == DiagnosticSeverity::Error) {
+        report(DiagnosticSeverity::Error, &d.code, Vec::new(), d.message);
+    }
+
+    // Without seats every party is too large, which says nothing new
+    if !seats.is_empty() {
+        for c in customers {
+            let largest = feasibility::party_capacity(c, seats, merge_tables);
+            if c.party_size > largest {
+                report(DiagnosticSeverity::Warning, "party-too-large", vec![c.family_id], format!("Family {} has {} people but the largest table, bar run or table group it can use seats {}", c.family_id, c.party_size, largest));
+            }
+        }
+    }
+    issues
+}
+
+// Refuse to start on invalid inputs. The error lists every problem, one "[code] message"
+// per line; validate_simulation_inputs returns the same problems (and the warnings) as data.
+pub fn validate_inputs(customers: &[CustomerConfig], seats: &[SeatConfig], baby_chairs: i32, wheelchairs: i32, merge_tables: bool) -> Result<()> {
+    let details: Vec<String> = input_issues(customers, seats, baby_chairs, wheelchairs, merge_tables).iter()
+        .filter(|i| i.severity == DiagnosticSeverity::Error)
+        .map(|i| format!("[{}] {}", i.code, i.message))
+        .collect();
+    if details.is_empty() {
+        return Ok(());
+    }
+    Err(AppError::ValidationError(format!("Invalid simulation input:\n{}", details.join("\n"))))
+}
+
+// The checks a run makes before it starts, for a form to show next to its fields
+#[tauri::command]
+pub fn validate_simulation_inputs(csv_content: String, seat_config_json: String, baby_chairs: i32, wheelchairs: i32, merge_tables: Option<bool>) -> Result<Vec<FeasibilityIssue>> {
+    let customers = parser::parse_customers(&csv_content)
+        .map_err(|e| AppError::CsvParseError(e.to_string()))?;
+    let seats: Vec<SeatConfig> = serde_json::from_str(&seat_config_json)
+        .map_err(|e| AppError::JsonParseError(e.to_string()))?;
+    Ok(input_issues(&customers, &seats, baby_chairs, wheelchairs, merge_tables.unwrap_or(false)))
+}
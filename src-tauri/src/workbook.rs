@@ -0,0 +1,157 @@
+use crate::access;
+use crate::errors::{AppError, Result};
+use crate::session::{SessionRun, SessionStore};
+use crate::stats;
+use rust_xlsxwriter::{Format, Workbook, Worksheet, XlsxError};
+use std::collections::HashMap;
+use tauri::{State, Window};
+
+fn xlsx_error(e: XlsxError) -> AppError {
+    AppError::IoError(std::io::Error::other(e.to_string()))
+}
+
+fn header(sheet: &mut Worksheet, titles: &[&str]) -> std::result::Result<(), XlsxError> {
+    let bold = Format::new().set_bold();
+    for (col, title) in titles.iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, *title, &bold)?;
+    }
+    sheet.set_freeze_panes(1, 0)?;
+    Ok(())
+}
+
+// Optional cell: left blank when there is no value
+fn write_opt(sheet: &mut Worksheet, row: u32, col: u16, value: Option<u64>) -> std::result::Result<(), XlsxError> {
+    if let Some(v) = value {
+        sheet.write_number(row, col, v as f64)?;
+    }
+    Ok(())
+}
+
+// First SEATED and LEFT time and the seats of each family
+struct Stay {
+    seated: Option<u64>,
+    left: Option<u64>,
+    seat_ids: String,
+}
+
+fn stays(run: &SessionRun) -> HashMap<u32, Stay> {
+    let mut stays: HashMap<u32, Stay> = HashMap::new();
+    for e in run.frames.iter().flat_map(|f| f.events.iter()) {
+        let stay = || Stay { seated: None, left: None, seat_ids: String::new() };
+        match e.type_.as_str() {
+            "SEATED" => {
+                let s = stays.entry(e.family_id).or_insert_with(stay);
+                if s.seated.is_none() {
+                    s.seated = Some(e.timestamp);
+                    s.seat_ids = e.seat_id.clone().unwrap_or_default();
+                }
+            }
+            "LEFT" => { stays.entry(e.family_id).or_insert_with(stay).left.get_or_insert(e.timestamp); }
+            _ => {}
+        }
+    }
+    stays
+}
+
+fn write_workbook(run: &SessionRun, path: &str) -> std::result::Result<(), XlsxError> {
+    let mut workbook = Workbook::new();
+    let stays = stays(run);
+
+    // Summary: headline KPIs and the settings they came from
+    let kpis = stats::kpis(run);
+    let summary = workbook.add_worksheet();
+    summary.set_name("Summary")?;
+    header(summary, &["Metric", "Value"])?;
+    let numbers: [(&str, f64); 8] = [
+        ("Families served", kpis.families_served as f64),
+        ("Covers served", kpis.covers_served as f64),
+        ("Average wait (min)", kpis.avg_wait),
+        ("Table turns per seat", kpis.table_turns_per_seat),
+        ("Peak occupancy (%)", kpis.peak_occupancy_pct),
+        ("Rejection rate", kpis.rejection_rate),
+        ("Abandonment cost", kpis.total_cost),
+        ("Total revenue", run.metadata.total_revenue),
+    ];
+    let mut row = 1;
+    for (name, value) in numbers {
+        summary.write_string(row, 0, name)?;
+        summary.write_number(row, 1, value)?;
+        row += 1;
+    }
+    for (name, value) in [
+        ("Engine", run.metadata.engine.as_str()),
+        ("Strategy", run.metadata.strategy.as_str()),
+        ("Fairness", run.metadata.fairness.as_str()),
+        ("Objective", run.metadata.objective.as_str()),
+        ("Fingerprint", run.metadata.fingerprint.as_str()),
+    ] {
+        summary.write_string(row, 0, name)?;
+        summary.write_string(row, 1, value)?;
+        row += 1;
+    }
+
+    // One row per family
+    let families = workbook.add_worksheet();
+    families.set_name("Families")?;
+    header(families, &["Family", "Party size", "Type", "Baby chairs", "Wheelchairs", "Arrival", "Seated", "Left", "Wait", "Seats"])?;
+    for (i, c) in run.customers.iter().enumerate() {
+        let row = i as u32 + 1;
+        let stay = stays.get(&c.family_id);
+        let seated = stay.and_then(|s| s.seated);
+        families.write_number(row, 0, c.family_id)?;
+        families.write_number(row, 1, c.party_size)?;
+        families.write_string(row, 2, c.type_.as_str())?;
+        families.write_number(row, 3, c.baby_chair_count)?;
+        families.write_number(row, 4, c.wheelchair_count)?;
+        families.write_number(row, 5, c.arrival_time as f64)?;
+        write_opt(families, row, 6, seated)?;
+        write_opt(families, row, 7, stay.and_then(|s| s.left))?;
+        write_opt(families, row, 8, seated.map(|t| t.saturating_sub(c.arrival_time)))?;
+        families.write_string(row, 9, stay.map(|s| s.seat_ids.as_str()).unwrap_or(""))?;
+    }
+
+    // One row per seat: how often and how long it was used
+    let end = run.frames.last().map(|f| f.timestamp).unwrap_or(0);
+    let seats = workbook.add_worksheet();
+    seats.set_name("Seats")?;
+    header(seats, &["Seat", "Type", "Accessible", "Seatings", "Occupied (min)", "Utilization (%)"])?;
+    for (i, seat) in run.seats_config.iter().enumerate() {
+        let uses: Vec<&Stay> = stays.values()
+            .filter(|s| s.seat_ids.split(',').any(|id| id.trim() == seat.id))
+            .collect();
+        let occupied: u64 = uses.iter()
+            .filter_map(|s| Some(s.left.unwrap_or(end).saturating_sub(s.seated?)))
+            .sum();
+        let row = i as u32 + 1;
+        seats.write_string(row, 0, seat.id.as_str())?;
+        seats.write_string(row, 1, seat.type_.as_str())?;
+        seats.write_string(row, 2, if seat.is_wheelchair_accessible { "yes" } else { "no" })?;
+        seats.write_number(row, 3, uses.len() as f64)?;
+        seats.write_number(row, 4, occupied as f64)?;
+        seats.write_number(row, 5, if end == 0 { 0.0 } else { occupied as f64 * 100.0 / end as f64 })?;
+    }
+
+    // One row per frame
+    let series = workbook.add_worksheet();
+    series.set_name("Time series")?;
+    header(series, &["Time", "Occupied seats", "Waiting families", "Baby chairs in use", "Wheelchairs seated"])?;
+    for (i, frame) in run.frames.iter().enumerate() {
+        let row = i as u32 + 1;
+        series.write_number(row, 0, frame.timestamp as f64)?;
+        series.write_number(row, 1, frame.seats.iter().filter(|s| s.occupied_by.is_some()).count() as f64)?;
+        series.write_number(row, 2, frame.waiting_queue.len() as f64)?;
+        series.write_number(row, 3, frame.seats.iter().map(|s| s.baby_chair_count).sum::<u32>())?;
+        series.write_number(row, 4, frame.seats.iter().map(|s| s.wheelchair_count).sum::<u32>())?;
+    }
+
+    workbook.save(path)
+}
+
+// Write the session's statistics to an Excel workbook with Summary, Families, Seats and
+// Time series sheets
+#[tauri::command]
+pub fn export_stats_xlsx(session_id: Option<String>, path: String, window: Window, sessions: State<'_, SessionStore>) -> Result<()> {
+    access::require_host(&window, "export workbooks")?;
+    let session_id = sessions.resolve(session_id, &window);
+    sessions.with_session(&session_id, |run| write_workbook(run, &path))?.map_err(xlsx_error)
+}
@@ -1,7 +1,7 @@
 import { writable, derived, get } from 'svelte/store';
 import { invoke } from '@tauri-apps/api/core';
 import { simulationStore } from './simulation';
-import type { SeatConfig, CustomerConfig } from '../types';
+import type { SeatConfig, CustomerConfig, GeneratedRoster, GeneratorConfig, RosterImport } from '../types';
 
 // ===== Default Seat Configuration =====
 const defaultSeats: SeatConfig[] = [
@@ -67,11 +67,11 @@ export function addCustomer(customer: CustomerConfig) {
   customerConfigStore.update(customers => [...customers, customer]);
 }
 
-export function removeCustomer(id: number) {
+export function removeCustomer(id: CustomerConfig['id']) {
   customerConfigStore.update(customers => customers.filter(c => c.id !== id));
 }
 
-export function updateCustomer(id: number, updates: Partial<CustomerConfig>) {
+export function updateCustomer(id: CustomerConfig['id'], updates: Partial<CustomerConfig>) {
   customerConfigStore.update(customers =>
     customers.map(c => {
       if (c.id === id) {
@@ -101,7 +101,7 @@ export async function importCustomersFromCSV(csvContent: string) {
     console.log("Config: Calling Rust load_customers...");
     
     // Receive correctly formatted data directly
-    const customers = await invoke<CustomerConfig[]>('load_customers', { csvContent });
+    const { customers } = await invoke<RosterImport>('load_customers', { csvContent });
     
     console.log("Config: Rust returned customers:", customers);
     
@@ -130,14 +130,15 @@ export function exportCustomersToCSV(): string {
 }
 
 // Generate Random Customers
-export async function generateCustomersInRust(count: number, maxArrivalTime: number) {
+export async function generateCustomersInRust(count: number, maxArrivalTime: number, config?: GeneratorConfig) {
   try {
     console.log("Generating random customers...");
     
     // Receive correctly formatted data directly
-    const customers = await invoke<CustomerConfig[]>('generate_customers', { 
+    const { customers } = await invoke<GeneratedRoster>('generate_customers', { 
         count, 
-        maxArrivalTime: Number(maxArrivalTime) // Ensure numeric conversion
+        maxArrivalTime: Number(maxArrivalTime), // Ensure numeric conversion
+        config
     });
     
     customerConfigStore.set(customers);
@@ -1,6 +1,6 @@
 import { writable, derived, get } from 'svelte/store';
 import { invoke } from '@tauri-apps/api/core';
-import type { SimulationFrame, CustomerConfig, SeatConfig } from '../types';
+import type { SimulationFrame, CustomerConfig, SeatConfig, RosterImport } from '../types';
 import { customerConfigStore, seatConfigStore, exportCustomersToCSV, resourceLimitsStore } from './config';
 
 // ===== State Interface Definition =====
@@ -182,7 +182,7 @@ export const actions = {
 
       // 2. Load and parse customer data
       // Use generic <CustomerConfig[]> for automatic camelCase mapping
-      const customers = await invoke<CustomerConfig[]>('load_customers', { csvContent: finalCsvContent });
+      const { customers } = await invoke<RosterImport>('load_customers', { csvContent: finalCsvContent });
       
       console.log("Customers loaded from backend:", customers.length);
       // Save parsed data back to store
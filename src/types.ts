@@ -5,12 +5,40 @@ export interface SeatConfig {
   type: 'SINGLE' | '4P' | '6P';
   isWheelchairAccessible: boolean;
   label?: string;
+  revenueWeight?: number; // Relative revenue per cover, defaults to 1.0
+  facesConveyor?: boolean;
+  adjacentTo?: string[]; // Tables that can be pushed together with this one
+  maxBabyChairs?: number; // Baby chairs the seat takes; defaults to none at the bar, party size at tables
+  wheelchairCapacity?: number; // Wheelchair spaces at an accessible table; defaults to one
+  contiguityGroup?: string; // Bar seats in one row, e.g. "counter-north"; families never span two groups
+  groupPosition?: number; // Place within the contiguity group; seat order if unset
+  attributes?: Record<string, number>; // e.g. noise, temperature, view; parties set limits with <name>_tolerance columns
+}
+
+// Returned by list_seat_layouts; load one with load_seat_layout
+export interface SavedLayout {
+  name: string;
+  seats: number;
+}
+
+export interface SeatIdPatterns {
+  bar?: string | null;
+  fourTop?: string | null;
+  sixTop?: string | null;
+}
+
+// A booked table, held for the family over [start, end] minutes
+export interface TableReservation {
+  familyId: number;
+  seatIds: string[];
+  start: number;
+  end: number;
 }
 
 export type CustomerType = 'INDIVIDUAL' | 'FAMILY' | 'WITH_BABY' | 'WHEELCHAIR' | 'LARGE_GROUP';
 
 export interface CustomerConfig {
-  id: number;
+  id: number | string;  // numeric, or a booking code such as "BK-7F3A"
   familyId: number;      // Maps to rust: family_id
   arrivalTime: number;   // Maps to rust: arrival_time
   type: string;          // Maps to rust: type_
@@ -22,6 +50,12 @@ export interface CustomerConfig {
   
   estDiningTime: number;
   estimatedDiningTime: number; // Keep both for compatibility
+  conveyorPreference?: number; // 0..1, wants a seat facing the conveyor belt
+  dietaryFlags?: string[];
+  orderedAhead?: boolean;
+  extra?: Record<string, string>; // extra CSV columns by header name, echoed on events
+  maxWaitTime?: number | null; // minutes before leaving unserved; overrides the run's maxWaitTime
+  priority?: number; // 0 for walk-ins; higher parties get freed seats first (PRIORITY_SEATED events)
 }
 
 export interface Seat {
@@ -30,13 +64,618 @@ export interface Seat {
   occupiedBy: number | null; // Stores Family ID
   occupantType: string | null; // BABY, WHEELCHAIR, NORMAL
   babyChairCount: number;
+  wheelchairCount?: number; // wheelchair users placed at this seat
   isWheelchairAccessible: boolean;
+  state?: OccupancyState;
 }
 
+// Seat state named by the backend; unknown kinds should render as unavailable
+export type OccupancyState =
+  | { kind: 'FREE' }
+  | { kind: 'RESERVED' }
+  | { kind: 'CLEANING' }
+  | { kind: 'OUT_OF_SERVICE' }
+  | { kind: 'OCCUPIED'; familyId: number };
+
 export interface SimulationFrame {
   timestamp: number;
   seats: Seat[];
   waitingQueue: CustomerConfig[];
   events: any[];
   logs: string[];
+  subQueues?: WaitingSubQueues;
+  stations?: StationOccupancy[];
+}
+
+// Self-service station (tea, soup, ...) seated parties visit
+export interface SelfServiceStation {
+  name: string;
+  capacity: number;
+  visitTime: number;  // minutes per trip
+  visitShare: number; // 0..1, share of parties (guests, if perGuest) that use it
+  perGuest?: boolean; // one trip per guest instead of per party
+}
+
+// Restroom scenario setting: stalls shared by every seated guest
+export interface RestroomConfig {
+  stalls?: number;
+  visitTime?: number;
+  visitShare?: number; // share of guests who go during their meal
+}
+
+export interface StationOccupancy {
+  name: string;
+  inUse: number;
+  queued: number;
+}
+
+export interface StationReport {
+  name: string;
+  capacity: number;
+  visits: number;
+  queuedVisits: number;
+  totalQueueWait: number;
+  maxQueueWait: number;
+  busyMinutes: number;
+  utilizationPct: number;
+}
+
+// Waiting family ids per required seat class, in arrival order
+export interface WaitingSubQueues {
+  single: number[];
+  fourPerson: number[];
+  sixPerson: number[];
+  accessible: number[];
+}
+
+// How densely a run is sampled into frames; events of dropped frames move to the next one
+export type FrameResolution =
+  | { mode: 'PER_SECOND' }
+  | { mode: 'PER_EVENT' }
+  | { mode: 'EVERY'; seconds: number }
+  | { mode: 'SKIP_IDLE' };
+
+// A party's needs changing at minute `at`, or on arrival if it comes later; unset fields
+// stay as they were. Logged as a REQUIREMENTS_CHANGED event.
+export interface RequirementChange {
+  familyId: number;
+  at: number;
+  partySize?: number | null;
+  babyChairCount?: number | null;
+  wheelchairCount?: number | null;
+}
+
+export interface SimulationConfig {
+  csvContent?: string;
+  seatConfigJson?: string;
+  babyChairs?: number;
+  wheelchairs?: number;
+  shadowStrategy?: string | null;
+  objective?: string | null;
+  fairness?: 'FIFO' | 'OPPORTUNISTIC'; // default 'FIFO'; 'OPPORTUNISTIC' needs a threaded engine
+  strategy?: 'DEFAULT' | 'FIRST_FIT' | 'BEST_FIT' | 'LARGEST_PARTY_FIRST' | 'FIFO_STRICT' | null; // queue orders need engine 'des'
+  conveyorPatience?: number;
+  deadlockResolution?: 'BACK_OFF' | 'ABORT'; // what a semaphores party closing a wait cycle does
+  instrument?: boolean; // collect the engine-health report
+  traceSync?: boolean;  // teaching mode: record lock/condvar trace
+  engine?: 'des' | 'threads' | 'realtime' | 'semaphores' | 'tasks'; // default 'des' (deterministic); 'tasks' for large wall-clock runs
+  deliverBabyChairsLater?: boolean; // seat now, bring baby chairs once freed; not on engine 'semaphores'
+  mergeTables?: boolean; // push adjacent tables together for parties too big for one
+  reservationsJson?: string; // JSON list of TableReservation
+  reservationGrace?: number; // minutes a reserved table waits for a late party
+  stations?: SelfServiceStation[];
+  restroom?: RestroomConfig | null;
+  generator?: GeneratorConfig | null; // seeded generator the roster came from; draws it when csvContent is empty
+  strictCsv?: boolean; // fail on unreadable roster rows instead of skipping them
+  maxWaitTime?: number | null; // minutes a party waits before leaving unserved (LEAVE_UNSERVED); unset waits forever
+  frameResolution?: FrameResolution; // default one frame per second
+  requirementChanges?: RequirementChange[]; // waiting parties whose needs change; engine 'des' only
+  liveEmitRate?: number; // max 'simulation://live' updates per second in realtime runs (default 30, 0 = off)
+  condiments?: {
+    enabled?: boolean;
+    serviceTime?: number;
+    priorityInheritance?: boolean;
+  };
+  kitchen?: {
+    dietaryStationCapacity?: number;
+    dietaryPrepTime?: number;
+    standardPrepTime?: number;
+    lineCapacity?: number; // standard orders prepared at once; 0 (default) leaves the line out
+  };
+  diningTimeBands?: DiningTimeBand[]; // scale the roster's stays by arrival time; bands may not overlap
+  couriers?: {
+    orders?: CourierOrder[]; // need kitchen.lineCapacity, the line they share with dine-in orders
+    pickupSlots?: number; // default 1
+    pickupTime?: number;  // minutes per handover, default 1
+    sla?: number;         // minutes a courier should spend at the shop, default 5
+  };
+}
+
+// Delivery order collected at the pickup counter; couriers never take a seat. Each one
+// logs COURIER_ORDER, COURIER_ARRIVAL and COURIER_LEFT events under family id 3000000000 + id.
+export interface CourierOrder {
+  id: number;
+  orderTime: number;
+  arrivalTime: number;
+  prepTime?: number; // kitchen.standardPrepTime if omitted
+}
+
+export interface CourierReport {
+  pickupSlots: number;
+  sla: number;
+  couriers: number;
+  slaBreaches: number;
+  avgWait: number;
+  maxWait: number;
+  pickups: {
+    id: number;
+    orderTime: number;
+    arrivalTime: number;
+    readyTime: number;
+    pickupStart: number;
+    departure: number;
+    wait: number;
+    slaMet: boolean;
+  }[];
+}
+
+export interface EngineCapabilities {
+  engineVersion: string;
+  protocolVersion: string;
+  streaming: boolean;
+  wasmStrategies: boolean;
+  beltModel: boolean;
+  restServer: boolean;
+  allocationStrategies: string[];
+  objectives: string[];
+  engines: string[];
+}
+
+// IPC protocol this frontend bundle speaks; sent with versioned commands
+export const PROTOCOL_VERSION = '1.2.0';
+
+export interface VersionedResponse<T> {
+  protocolVersion: string;
+  result: T;
+}
+
+export interface EventFilter {
+  types?: string[];     // empty or missing = all event types
+  familyIds?: number[]; // empty or missing = all families
+}
+
+// Latest state published by a (possibly still running) simulation
+export interface LiveSnapshot {
+  sessionId: string;
+  running: boolean;
+  version: number; // changes whenever the state does
+  virtualTime: number;
+  seats: Seat[];
+  waitingFamilyIds: number[]; // in the order parties will be offered seats
+  babyChairsAvailable: number;
+  wheelchairsAvailable: number;
+  eventCount: number;
+}
+
+// Payload of the rate-limited 'simulation://live' event pushed during realtime runs
+export interface LiveDelta {
+  sessionId: string;
+  running: boolean;
+  version: number;
+  coalesced: number; // engine changes folded into this update
+  virtualTime: number;
+  seats: Seat[]; // changed seats only; every seat in the first update
+  waitingFamilyIds: number[];
+  babyChairsAvailable: number;
+  wheelchairsAvailable: number;
+  eventCount: number;
+}
+
+export interface ReproManifest {
+  engineVersion: string;
+  protocolVersion: string;
+  engine: string;
+  seed: string | null;
+  fingerprint: string;
+  files: string[];
+}
+
+export interface ReproCheck {
+  sessionId: string;
+  manifest: ReproManifest;
+  sameEngineVersion: boolean;
+  fingerprint: string;
+  reproduced: boolean;
+  divergences: { familyId: number; bundled: string[]; rerun: string[] }[];
+}
+
+export interface LedgerEntry {
+  sequence: number;
+  time: number;
+  familyId: number;
+  resource: 'BABY_CHAIRS' | 'WHEELCHAIRS';
+  operation: 'ACQUIRE' | 'RELEASE';
+  count: number;
+  balance: number; // pool level after this entry
+}
+
+export interface CondimentReport {
+  config: { enabled: boolean; serviceTime: number; priorityInheritance: boolean };
+  inversions: {
+    familyId: number;
+    holderFamilyId: number;
+    blockedFrom: number;
+    blockedUntil: number;
+    invertedTime: number;
+  }[];
+  maxInvertedTime: number;
+  timeline: {
+    time: number;
+    familyId: number;
+    priority: number;
+    effectivePriority: number;
+    event: string;
+    detail: string;
+  }[];
+}
+
+// Seats per type and pools of a floor plan built with generate_seat_layout
+export interface CapacityConfig {
+  barSeats: number;
+  fourTops: number;
+  sixTops: number;
+  accessibleTables: number; // the first tables of the plan; at most all of them
+  babyChairs: number;
+  wheelchairs: number;
+}
+
+export interface CapacityEvaluation {
+  config: CapacityConfig;
+  runs: number;
+  p95Wait: number | null; // null when more than 5% of parties were never seated
+  avgWait: number;
+  seatedShare: number;
+  meetsTarget: boolean;
+}
+
+// Returned by plan_capacity: a small plan meeting the target, found by a heuristic
+// search, so not necessarily the smallest
+export interface CapacityPlan {
+  targetWait: number;
+  recommended: CapacityConfig;
+  layout: SeatConfig[];
+  evidence: CapacityEvaluation;
+  evaluations: CapacityEvaluation[]; // every configuration tried, in search order
+}
+
+export interface StarvationReport {
+  objective: string;
+  starvedFamilies: number;
+  worstFamilies: number[];
+  families: {
+    familyId: number;
+    partySize: number;
+    arrivalTime: number;
+    wait: number | null; // null if never seated
+    overtakenBy: number[];
+  }[];
+}
+
+// Emitted on 'lobby-announcements' as playback passes them
+export interface Announcement {
+  timestamp: number;
+  kind: 'TABLE_READY' | 'WAIT_ESTIMATE';
+  familyId: number | null;
+  partySize: number | null;
+  estimatedWait: number | null;
+  message: string;
+}
+
+// get_quote_accuracy: wait quoted on joining the queue vs the wait actually had
+export interface WaitQuote {
+  familyId: number;
+  partySize: number;
+  quotedAt: number;
+  quotedWait: number;
+  actualWait: number | null; // null if never seated
+  error: number | null;      // actual - quoted
+}
+
+export interface QuoteSizeAccuracy {
+  partySize: number;
+  quotes: number;
+  resolved: number;
+  meanAbsoluteError: number;
+  bias: number;
+}
+
+export interface QuoteAccuracyReport {
+  quoted: number;
+  resolved: number;
+  tolerance: number;
+  withinTolerance: number; // share of resolved quotes within tolerance minutes
+  meanAbsoluteError: number;
+  bias: number;
+  byPartySize: QuoteSizeAccuracy[];
+  quotes: WaitQuote[];
+}
+
+// Result of load_customers (CSV or JSON text, or a CSV, JSON or spreadsheet file)
+export interface RosterImport {
+  format: 'csv' | 'json' | 'xlsx';
+  customers: CustomerConfig[];
+  csvContent: string;
+  warnings: CsvParseWarning[]; // rows and values skipped or defaulted (empty with strict)
+}
+
+// load_customers' options for a roster in its own layout
+export interface ParserOptions {
+  delimiter?: string; // one character; ',' by default
+  hasHeader?: boolean; // default: a first line starting with "id" is the header
+  columns?: Record<string, string | number>; // standard column (e.g. "party_size") -> header name or 0-based index
+}
+
+// A roster row or value the lenient parser couldn't read
+export interface CsvParseWarning {
+  line: number; // 1-based
+  column: string | null;
+  text: string;
+  reason: string;
+  skipped: boolean; // the whole row was dropped
+}
+
+// Result of import_bookings / import_calendar_bookings: the roster, plus the same roster as simulation CSV
+export interface BookingImport {
+  customers: CustomerConfig[];
+  csvContent: string;
+  skipped: string[]; // calendar entries that weren't bookings
+}
+
+// Result of preview_customers, shown before a run is started
+export interface CsvRowIssue {
+  line: number; // 1-based
+  familyId: number | null;
+  field: string | null; // null for whole-row issues
+  severity: 'error' | 'warning' | 'information' | 'hint';
+  code: string;
+  reason: string;
+}
+
+export interface CustomerPreview {
+  customers: CustomerConfig[];
+  issues: CsvRowIssue[];
+  hasErrors: boolean;
+}
+
+// How a stored run was produced (RunMetadata on the Rust side)
+export interface RunMetadata {
+  objective: string;
+  strategy: string;
+  fairness: string;
+  shadowStrategy: string | null;
+  babyChairs: number;
+  wheelchairs: number;
+  totalRevenue: number;
+  engine: string; // 'threads', 'des', 'semaphores' or 'tasks'
+  fingerprint: string; // hash of the canonical event log; equal runs share it
+  resumedFrom: number | null; // checkpoint time an incremental re-run picked up from
+}
+
+// Payloads of stream_simulation's 'simulation://frame' and 'simulation://done' events
+export interface StreamedFrame {
+  sessionId: string;
+  index: number;
+  total: number;
+  frame: SimulationFrame;
+}
+
+export interface StreamDone {
+  sessionId: string;
+  frameCount: number;
+  metadata: RunMetadata | null;
+  error: string | null;
+}
+
+// Operations mode (start_operations / inject_arrival / seat_walk_in / record_departure)
+export interface WaitingParty {
+  familyId: number;
+  partySize: number;
+  name: string | null;
+  arrivalTime: number; // minutes into the shift
+  waited: number;
+  suggestedSeats: string[] | null; // null while nothing fits
+}
+
+export interface SeatedParty {
+  familyId: number;
+  partySize: number;
+  name: string | null;
+  seatIds: string[];
+  seatedAt: number;
+  expectedLeave: number;
+}
+
+export interface SeatRecommendation {
+  seatIds: string[];
+  score: number; // higher is better
+  wastedSeats: number;
+  facesConveyor: boolean;
+  blockedReservations: number[]; // bookings due at these seats before the party is projected to leave
+  engineChoice: boolean;
+}
+
+export interface OperationsStatus {
+  sessionId: string;
+  now: number; // minutes since the shift started
+  waiting: WaitingParty[];
+  seated: SeatedParty[];
+  upcomingReservations: TableReservation[]; // check in with inject_arrival's reservationId
+  babyChairsAvailable: number;
+  wheelchairsAvailable: number;
+}
+
+// Role of a window; viewers can't run simulations, drive playback or change the floor
+export type Role = 'HOST' | 'VIEWER';
+
+// get_access_settings / set_access_settings. By default only the main window is a HOST.
+export interface AccessSettings {
+  defaultRole: Role; // for windows not listed in windowRoles
+  windowRoles: Record<string, Role>; // by window label
+}
+
+// Returned by list_operations_journals: a shift left open by a crash, restorable with restore_operations
+export interface OperationsJournal {
+  journalId: string;
+  startedAt: number; // unix seconds
+  lastChange: number; // minutes into the shift
+  arrivals: number;
+  waiting: number;
+  seated: number;
+}
+
+// Returned by end_operations and get_day_report
+export interface DayReport {
+  sessionId: string;
+  shiftMinutes: number;
+  kpis: any;
+  waitBySeatClass: any[];
+  starvation: StarvationReport;
+  totalRevenue: number;
+  unseatedFamilyIds: number[];
+  stillDiningFamilyIds: number[];
+  noShowReservationIds: number[];
+  summary: string;
+}
+
+export type DiningDistribution = 'uniform' | 'normal' | 'logNormal';
+
+export interface DiningTimeModel {
+  distribution: DiningDistribution;
+  mean: number; // for logNormal, of ln(minutes)
+  stdDev: number;
+  min: number;
+  max: number;
+}
+
+export interface GeneratorConfig {
+  count: number;
+  maxArrivalTime: number;
+  arrivalRates?: number[]; // relative intensity per bucket; empty means uniform
+  bucketMinutes?: number;
+  partySizeWeights: number[]; // index 0 is a party of one
+  babyProbability: number;
+  wheelchairProbability: number;
+  diningTime: DiningTimeModel;
+  seed?: string; // decimal; a u64 doesn't fit a JavaScript number
+  diningTimeBands?: DiningTimeBand[]; // applied to each sampled stay by arrival time
+}
+
+// Parties arriving in [from, to) stay multiplier times as long, e.g. 0.7 for quick lunches
+export interface DiningTimeBand {
+  name?: string;
+  from: number;
+  to: number;
+  multiplier: number;
+}
+
+// Returned by generate_customers
+export interface GeneratedRoster {
+  customers: CustomerConfig[];
+  csvContent: string;
+  seed: string; // pass back in the config to regenerate the same roster
+  draws: RngDraw[]; // empty unless recordDraws was set
+}
+
+// One random draw of a seeded run, tagged with what it decided
+export interface RngDraw {
+  index: number;
+  purpose: string;
+  value: number; // booleans are 0/1
+}
+
+// Returned by fit_demand_model
+export interface DemandFit {
+  generator: GeneratorConfig;
+  sources: number;
+  parties: number;
+  arrivalsPerMinute: number[];
+  diningLogLikelihood: number;
+  notes: string[];
+}
+
+// Returned by run_monte_carlo
+export interface MonteCarloResult {
+  metric: 'avgWait' | 'maxWait' | 'seatedShare';
+  replications: number; // runs actually made
+  converged: boolean; // false if maxReplications ran out first
+  mean: number;
+  stdDev: number;
+  halfWidth: number;
+  confidence: number;
+  tolerance: number | null;
+  baseSeed: string; // decimal u64; replication i uses baseSeed + i
+  values: number[];
+  slas: SlaBatchResult[]; // only when service targets were given
+}
+
+// e.g. { percentile: 95, maxWait: 20 } or { parties: 'WHEELCHAIR', maxWait: 10 }
+export interface ServiceTarget {
+  name?: string;
+  percentile?: number; // 0-100, default 100
+  maxWait: number;
+  parties?: 'ALL' | 'WHEELCHAIR' | 'BABY' | 'SINGLE' | '4P' | '6P' | 'ACCESSIBLE';
+}
+
+export interface SlaResult {
+  name: string;
+  passed: boolean;
+  families: number;
+  shareWithin: number; // 0-100; unseated parties count as late
+  percentileWait: number | null;
+  violatingFamilyIds: number[];
+}
+
+// Returned by check_slas
+export interface SlaReport {
+  passed: boolean;
+  results: SlaResult[];
+}
+
+export interface SlaBatchResult {
+  name: string;
+  passedRuns: number;
+  runs: number;
+  passRate: number;
+  violatingFamilyIds: number[][]; // per replication
+}
+
+export interface FamilyWait {
+  familyId: number;
+  partySize: number;
+  arrivalTime: number;
+  seatedAt: number | null; // null if never seated
+  wait: number | null;
+}
+
+export interface SeatTypeUtilization {
+  seatType: string;
+  seats: number;
+  occupiedMinutes: number;
+  utilizationPct: number;
+}
+
+// Returned by get_simulation_stats
+export interface SimulationReport {
+  duration: number;
+  families: FamilyWait[];
+  avgWait: number; // over seated families
+  maxWait: number;
+  seatUtilization: SeatTypeUtilization[];
+  babyChairUtilizationPct: number;
+  wheelchairUtilizationPct: number;
+  customersServed: number;
+  customersReneged: number; // left after their max wait time (LEAVE_UNSERVED)
+  customersInfeasible: number; // turned away as the layout can never seat them (INFEASIBLE)
+  customersNeverSeated: number;
+  stations: StationReport[];
 }